@@ -0,0 +1,551 @@
+pub mod alignment_algos {
+    use std::fmt::Write;
+
+    /**
+     * Scores for aligning each pair of residues, like BLOSUM62 or PAM250 for proteins.
+     * Residues are single ASCII letters (or symbols like *), looked up in either case.
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct SubstitutionMatrix {
+        alphabet: Vec<u8>,
+        index: Vec<Option<usize>>,  //position in the alphabet of each byte
+        scores: Vec<i32>            //row by row
+    }
+
+    impl SubstitutionMatrix {
+        fn new(alphabet: Vec<u8>, scores: Vec<i32>) -> Self {
+            let mut index = vec![None; 256];
+            for (i, &residue) in alphabet.iter().enumerate() {
+                index[residue.to_ascii_uppercase() as usize] = Some(i);
+                index[residue.to_ascii_lowercase() as usize] = Some(i);
+            }
+            SubstitutionMatrix {
+                alphabet,
+                index,
+                scores
+            }
+        }
+
+        /**
+         * Reads a matrix in the NCBI format of BLOSUM62 and PAM250: # comment lines, a line
+         * of the residues, then a line for each residue starting with it and giving its scores
+         * in the order of the first line. Returns None if the text isn't such a matrix,
+         * or if the scores aren't symmetric.
+         */
+        pub fn parse(text: &str) -> Option<Self> {
+            let mut lines = text.lines().map(|line| {line.trim()}).filter(|line| {!line.is_empty() && !line.starts_with('#')});
+            let mut alphabet = Vec::new();
+            for residue in lines.next()?.split_whitespace() {
+                match residue.as_bytes() {
+                    [byte] if !alphabet.contains(byte) => alphabet.push(*byte),
+                    _ => return None
+                }
+            }
+            let size = alphabet.len();
+            let mut scores = vec![0; size * size];
+            let mut seen = vec![false; size];
+            for line in lines {
+                let mut fields = line.split_whitespace();
+                let row = match fields.next()?.as_bytes() {
+                    [byte] => alphabet.iter().position(|residue| {residue == byte})?,
+                    _ => return None
+                };
+                let values: Vec<i32> = fields.map(|field| {field.parse().ok()}).collect::<Option<Vec<i32>>>()?;
+                if seen[row] || values.len() != size {
+                    return None;
+                }
+                seen[row] = true;
+                scores[row * size..(row + 1) * size].copy_from_slice(&values);
+            }
+            if seen.contains(&false) || (0..size * size).any(|i| {scores[i] != scores[i % size * size + i / size]}) {
+                return None;
+            }
+            Some(SubstitutionMatrix::new(alphabet, scores))
+        }
+
+        /**
+         * Reads a matrix file, see parse
+         */
+        pub fn load(path: &str) -> Option<Self> {
+            SubstitutionMatrix::parse(&std::fs::read_to_string(path).ok()?)
+        }
+
+        /**
+         * BLOSUM62 (Henikoff and Henikoff), the usual matrix for proteins, built from blocks
+         * of aligned sequences at most 62% identical
+         */
+        pub fn blosum62() -> Self {
+            SubstitutionMatrix::parse(include_str!("../fixtures/alignment/BLOSUM62")).unwrap()
+        }
+
+        /**
+         * PAM250 (Dayhoff), extrapolated from closely related proteins to 250 accepted
+         * point mutations per 100 residues, for distant sequences
+         */
+        pub fn pam250() -> Self {
+            SubstitutionMatrix::parse(include_str!("../fixtures/alignment/PAM250")).unwrap()
+        }
+
+        /**
+         * A matrix giving one score to identical residues and another to different ones,
+         * as used for DNA, e.g. match_mismatch("ACGT", 5, -4)
+         */
+        pub fn match_mismatch(alphabet: &str, match_score: i32, mismatch_score: i32) -> Self {
+            let alphabet: Vec<u8> = alphabet.bytes().collect();
+            let size = alphabet.len();
+            let scores = (0..size * size).map(|i| {if i / size == i % size {match_score} else {mismatch_score}}).collect();
+            SubstitutionMatrix::new(alphabet, scores)
+        }
+
+        pub fn alphabet(&self) -> &[u8] {
+            &self.alphabet
+        }
+
+        /**
+         * The score of aligning residue a with residue b.
+         * Panics if either isn't in the matrix.
+         */
+        pub fn score(&self, a: u8, b: u8) -> i32 {
+            match (self.index[a as usize], self.index[b as usize]) {
+                (Some(i), Some(j)) => self.scores[i * self.alphabet.len() + j],
+                _ => panic!("Residue {} or {} is not in the substitution matrix.", a as char, b as char)
+            }
+        }
+    }
+
+    /**
+     * The penalty for a gap of length L, open + (L - 1) * extend: opening a gap costs more
+     * than making one longer, since one insertion or deletion event can span many residues.
+     * Penalties are positive and subtracted from the score.
+     */
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct GapPenalty {
+        pub open: i32,
+        pub extend: i32
+    }
+
+    impl GapPenalty {
+        /**
+         * Affine gaps. BLAST's default for BLOSUM62, gap existence 11 and extension 1, charges
+         * 11 + L for a gap of length L, which is affine(12, 1) here, as open counts the first residue.
+         */
+        pub fn affine(open: i32, extend: i32) -> Self {
+            GapPenalty {
+                open,
+                extend
+            }
+        }
+
+        /**
+         * Every gap position costs the same
+         */
+        pub fn linear(gap: i32) -> Self {
+            GapPenalty {
+                open: gap,
+                extend: gap
+            }
+        }
+
+        pub fn penalty(&self, length: usize) -> i32 {
+            if length == 0 {
+                0
+            } else {
+                self.open + (length as i32 - 1) * self.extend
+            }
+        }
+    }
+
+    /**
+     * An alignment of two sequences and its score. Like the lcs alignment, it is a list of
+     * pairs of indexes: residues aligned together are paired, and a residue facing a gap is
+     * paired with None. A local alignment only covers the aligned parts of the sequences.
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Alignment {
+        pub score: i32,
+        pub pairs: Vec<(Option<usize>, Option<usize>)>
+    }
+
+    //small enough to never be the best, but far from overflowing when penalties are subtracted
+    const NEG_INFINITY: i32 = i32::MIN / 4;
+
+    //the three tables of Gotoh's algorithm
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum State {
+        Match,      //ends with a[i-1] aligned to b[j-1]
+        GapIn2,     //ends with a[i-1] facing a gap
+        GapIn1      //ends with b[j-1] facing a gap
+    }
+
+    struct GotohTables {
+        matches: Vec<Vec<i32>>,
+        gaps_in_2: Vec<Vec<i32>>,
+        gaps_in_1: Vec<Vec<i32>>
+    }
+
+    impl GotohTables {
+        fn get(&self, state: State, i: usize, j: usize) -> i32 {
+            match state {
+                State::Match => self.matches[i][j],
+                State::GapIn2 => self.gaps_in_2[i][j],
+                State::GapIn1 => self.gaps_in_1[i][j]
+            }
+        }
+    }
+
+    /**
+     * Fills the tables of Gotoh's algorithm, the lcs table approach with affine gaps.
+     * One table per way an alignment of the first i and j residues can end:
+     *  M(i, j) = s(a_i, b_j) + max(M, X, Y at (i-1, j-1))       (a_i aligned to b_j)
+     *  X(i, j) = max(M(i-1, j) - open, X(i-1, j) - extend, Y(i-1, j) - open)   (a_i vs a gap)
+     *  Y(i, j) = max(M(i, j-1) - open, Y(i, j-1) - extend, X(i, j-1) - open)   (b_j vs a gap)
+     * so a gap is charged open once and extend for every further residue.
+     * For a local alignment, M may also start afresh from 0.
+     */
+    fn gotoh(seq_1: &[u8], seq_2: &[u8], matrix: &SubstitutionMatrix, gaps: &GapPenalty, local: bool) -> GotohTables {
+        let (n, m) = (seq_1.len(), seq_2.len());
+        let mut tables = GotohTables {
+            matches: vec![vec![NEG_INFINITY; m + 1]; n + 1],
+            gaps_in_2: vec![vec![NEG_INFINITY; m + 1]; n + 1],
+            gaps_in_1: vec![vec![NEG_INFINITY; m + 1]; n + 1]
+        };
+        if !local {
+            //a global alignment may start with a gap in either sequence
+            tables.matches[0][0] = 0;
+            for i in 1..=n {
+                tables.gaps_in_2[i][0] = -gaps.penalty(i);
+            }
+            for j in 1..=m {
+                tables.gaps_in_1[0][j] = -gaps.penalty(j);
+            }
+        }
+
+        for i in 1..=n {
+            for j in 1..=m {
+                let mut best_before = tables.matches[i - 1][j - 1].max(tables.gaps_in_2[i - 1][j - 1]).max(tables.gaps_in_1[i - 1][j - 1]);
+                if local {
+                    best_before = best_before.max(0);
+                }
+                tables.matches[i][j] = best_before + matrix.score(seq_1[i - 1], seq_2[j - 1]);
+                tables.gaps_in_2[i][j] = (tables.matches[i - 1][j] - gaps.open)
+                    .max(tables.gaps_in_2[i - 1][j] - gaps.extend)
+                    .max(tables.gaps_in_1[i - 1][j] - gaps.open);
+                tables.gaps_in_1[i][j] = (tables.matches[i][j - 1] - gaps.open)
+                    .max(tables.gaps_in_1[i][j - 1] - gaps.extend)
+                    .max(tables.gaps_in_2[i][j - 1] - gaps.open);
+            }
+        }
+        tables
+    }
+
+    /**
+     * Walks back from start = (state, i, j) along the choices that made each square, until (0, 0)
+     * for a global alignment or the square where a local one started from 0
+     */
+    fn traceback(seq_1: &[u8], seq_2: &[u8], matrix: &SubstitutionMatrix, gaps: &GapPenalty, tables: &GotohTables, local: bool, start: (State, usize, usize)) -> Vec<(Option<usize>, Option<usize>)> {
+        let (mut state, mut i, mut j) = start;
+        let states = [State::Match, State::GapIn2, State::GapIn1];
+        let mut pairs = Vec::new();
+        while i > 0 || j > 0 {
+            let value = tables.get(state, i, j);
+            match state {
+                State::Match => {
+                    pairs.push((Some(i - 1), Some(j - 1)));
+                    let before = value - matrix.score(seq_1[i - 1], seq_2[j - 1]);
+                    i -= 1;
+                    j -= 1;
+                    if (local && before == 0) || (i == 0 && j == 0) {
+                        break;
+                    }
+                    state = *states.iter().find(|&&s| {tables.get(s, i, j) == before}).unwrap();
+                },
+                State::GapIn2 => {
+                    pairs.push((Some(i - 1), None));
+                    i -= 1;
+                    state = *states.iter().find(|&&s| {
+                        tables.get(s, i, j) - if s == State::GapIn2 {gaps.extend} else {gaps.open} == value
+                    }).unwrap();
+                },
+                State::GapIn1 => {
+                    pairs.push((None, Some(j - 1)));
+                    j -= 1;
+                    state = *states.iter().find(|&&s| {
+                        tables.get(s, i, j) - if s == State::GapIn1 {gaps.extend} else {gaps.open} == value
+                    }).unwrap();
+                }
+            }
+        }
+        pairs.reverse();
+        pairs
+    }
+
+    /**
+     * Needleman-Wunsch global alignment of two whole sequences, with Gotoh's affine gaps.
+     * Takes O(nm) time and space.
+     */
+    pub fn needleman_wunsch(seq_1: &[u8], seq_2: &[u8], matrix: &SubstitutionMatrix, gaps: &GapPenalty) -> Alignment {
+        let (n, m) = (seq_1.len(), seq_2.len());
+        if n == 0 || m == 0 {
+            let pairs = (0..n).map(|i| {(Some(i), None)}).chain((0..m).map(|j| {(None, Some(j))})).collect();
+            return Alignment{score: -gaps.penalty(n + m), pairs};
+        }
+        let tables = gotoh(seq_1, seq_2, matrix, gaps, false);
+        let (state, score) = [State::Match, State::GapIn2, State::GapIn1].iter()
+            .map(|&s| {(s, tables.get(s, n, m))})
+            .fold((State::Match, NEG_INFINITY), |best, candidate| {if candidate.1 > best.1 {candidate} else {best}});
+        Alignment {
+            score,
+            pairs: traceback(seq_1, seq_2, matrix, gaps, &tables, false, (state, n, m))
+        }
+    }
+
+    /**
+     * Smith-Waterman local alignment: the best scoring pair of stretches of the two sequences,
+     * with Gotoh's affine gaps. Any square may start an alignment from 0, so stretches that
+     * score badly are left out, and the best one ends at the highest square of the tables.
+     * Returns an empty alignment with score 0 if no residues score positively.
+     * Takes O(nm) time and space.
+     */
+    pub fn smith_waterman(seq_1: &[u8], seq_2: &[u8], matrix: &SubstitutionMatrix, gaps: &GapPenalty) -> Alignment {
+        let tables = gotoh(seq_1, seq_2, matrix, gaps, true);
+        let mut best = (0, 0, 0);
+        for (i, row) in tables.matches.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate() {
+                if value > best.0 {
+                    best = (value, i, j);
+                }
+            }
+        }
+        let (score, i, j) = best;
+        if score == 0 {
+            return Alignment{score: 0, pairs: Vec::new()};
+        }
+        Alignment {
+            score,
+            pairs: traceback(seq_1, seq_2, matrix, gaps, &tables, true, (State::Match, i, j))
+        }
+    }
+
+    impl Alignment {
+        /**
+         * Prints the alignment in blocks of width columns, in the style of EMBOSS:
+         * each block shows the positions of its first and last residues around the
+         * two aligned rows, with gaps as '-', and a line between them marking identical
+         * residues with '|', other pairs that score positively with ':' and the rest with '.'.
+         * A summary of the identities, similarities and gaps comes first.
+         */
+        pub fn pretty(&self, seq_1: &[u8], seq_2: &[u8], matrix: &SubstitutionMatrix, width: usize) -> String {
+            let width = width.max(1);
+            let mut row_1: Vec<char> = Vec::new();
+            let mut row_2: Vec<char> = Vec::new();
+            let mut marks: Vec<char> = Vec::new();
+            let (mut identical, mut similar, mut gaps) = (0, 0, 0);
+            for pair in self.pairs.iter() {
+                match *pair {
+                    (Some(i), Some(j)) => {
+                        row_1.push(seq_1[i] as char);
+                        row_2.push(seq_2[j] as char);
+                        let mark = if seq_1[i].eq_ignore_ascii_case(&seq_2[j]) {
+                            identical += 1;
+                            '|'
+                        } else if matrix.score(seq_1[i], seq_2[j]) > 0 {
+                            ':'
+                        } else {
+                            '.'
+                        };
+                        if mark != '.' {
+                            similar += 1;
+                        }
+                        marks.push(mark);
+                    },
+                    (Some(i), None) => {
+                        row_1.push(seq_1[i] as char);
+                        row_2.push('-');
+                        marks.push(' ');
+                        gaps += 1;
+                    },
+                    (None, Some(j)) => {
+                        row_1.push('-');
+                        row_2.push(seq_2[j] as char);
+                        marks.push(' ');
+                        gaps += 1;
+                    },
+                    (None, None) => {}
+                }
+            }
+
+            let length = self.pairs.len();
+            let percent = |count: usize| {if length == 0 {0.0} else {100.0 * count as f64 / length as f64}};
+            let mut out = String::new();
+            writeln!(out, "Length: {}", length).unwrap();
+            writeln!(out, "Identity: {}/{} ({:.1}%)", identical, length, percent(identical)).unwrap();
+            writeln!(out, "Similarity: {}/{} ({:.1}%)", similar, length, percent(similar)).unwrap();
+            writeln!(out, "Gaps: {}/{} ({:.1}%)", gaps, length, percent(gaps)).unwrap();
+            writeln!(out, "Score: {}", self.score).unwrap();
+
+            //positions are 1-based, and a block of only gaps shows the residue before it
+            let digits = seq_1.len().max(seq_2.len()).max(1).to_string().len();
+            let mut position_1 = self.pairs.iter().find_map(|pair| {pair.0}).unwrap_or(0);
+            let mut position_2 = self.pairs.iter().find_map(|pair| {pair.1}).unwrap_or(0);
+            for (block, start) in (0..length).step_by(width).enumerate() {
+                let end = (start + width).min(length);
+                let residues_1 = self.pairs[start..end].iter().filter(|pair| {pair.0.is_some()}).count();
+                let residues_2 = self.pairs[start..end].iter().filter(|pair| {pair.1.is_some()}).count();
+                let first_1 = if residues_1 > 0 {position_1 + 1} else {position_1};
+                let first_2 = if residues_2 > 0 {position_2 + 1} else {position_2};
+                position_1 += residues_1;
+                position_2 += residues_2;
+                if block > 0 {
+                    out.push('\n');
+                }
+                let line = |row: &[char]| {row[start..end].iter().collect::<String>()};
+                writeln!(out, "{:>w$} {} {}", first_1, line(&row_1), position_1, w = digits).unwrap();
+                writeln!(out, "{:>w$} {}", "", line(&marks).trim_end(), w = digits).unwrap();
+                writeln!(out, "{:>w$} {} {}", first_2, line(&row_2), position_2, w = digits).unwrap();
+            }
+            out
+        }
+    }
+
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        //whether the pairs walk both sequences in order, all of them or, if local, a stretch of each
+        fn covers(pairs: &[(Option<usize>, Option<usize>)], len_1: usize, len_2: usize, local: bool) -> bool {
+            let mut i = pairs.iter().find_map(|pair| {pair.0}).unwrap_or(0);
+            let mut j = pairs.iter().find_map(|pair| {pair.1}).unwrap_or(0);
+            if !local && (i, j) != (0, 0) {
+                return false;
+            }
+            for pair in pairs.iter() {
+                match *pair {
+                    (Some(a), Some(b)) if (a, b) == (i, j) => {i += 1; j += 1;},
+                    (Some(a), None) if a == i => i += 1,
+                    (None, Some(b)) if b == j => j += 1,
+                    _ => return false
+                }
+            }
+            local || (i, j) == (len_1, len_2)
+        }
+
+        //the score of the pairs, adding up residue scores and gap penalties run by run
+        fn rescore(pairs: &[(Option<usize>, Option<usize>)], seq_1: &[u8], seq_2: &[u8], matrix: &SubstitutionMatrix, gaps: &GapPenalty) -> i32 {
+            let mut score = 0;
+            let mut run = (0, 0);   //lengths of the current gaps in seq_2 and seq_1
+            for pair in pairs.iter() {
+                match *pair {
+                    (Some(i), Some(j)) => {
+                        score += matrix.score(seq_1[i], seq_2[j]) - gaps.penalty(run.0) - gaps.penalty(run.1);
+                        run = (0, 0);
+                    },
+                    (Some(_i), None) => {
+                        score -= gaps.penalty(run.1);
+                        run = (run.0 + 1, 0);
+                    },
+                    _ => {
+                        score -= gaps.penalty(run.0);
+                        run = (0, run.1 + 1);
+                    }
+                }
+            }
+            score - gaps.penalty(run.0) - gaps.penalty(run.1)
+        }
+
+        #[test]
+        fn textbook_alignments() {
+            //the examples of the Wikipedia articles on the two algorithms
+            let dna = SubstitutionMatrix::match_mismatch("ACGT", 3, -3);
+            let local = smith_waterman(b"TGTTACGG", b"GGTTGACTA", &dna, &GapPenalty::linear(2));
+            assert_eq!(local.score, 13);
+            assert_eq!(local.pairs, vec![(Some(1), Some(1)), (Some(2), Some(2)), (Some(3), Some(3)), (None, Some(4)), (Some(4), Some(5)), (Some(5), Some(6))]);
+
+            let dna = SubstitutionMatrix::match_mismatch("ACGT", 1, -1);
+            let global = needleman_wunsch(b"GCATGCG", b"GATTACA", &dna, &GapPenalty::linear(1));
+            assert_eq!(global.score, 0);
+            assert!(covers(&global.pairs, 7, 7, false));
+            assert_eq!(rescore(&global.pairs, b"GCATGCG", b"GATTACA", &dna, &GapPenalty::linear(1)), 0);
+        }
+
+        #[test]
+        fn protein_alignments_with_affine_gaps() {
+            //Durbin et al.'s HEAGAWGHEE and PAWHEAE, with BLAST's gap costs
+            let (seq_1, seq_2) = (b"HEAGAWGHEE", b"PAWHEAE");
+            let gaps = GapPenalty::affine(12, 1);
+            let gapped: Vec<(Option<usize>, Option<usize>)> = (0..3).map(|i| {(Some(i), None)})
+                .chain((3..10).map(|i| {(Some(i), Some(i - 3))}))
+                .collect();
+            for (matrix, global_score, local_score) in [(SubstitutionMatrix::blosum62(), 1, 17), (SubstitutionMatrix::pam250(), 8, 22)].iter() {
+                let global = needleman_wunsch(seq_1, seq_2, matrix, &gaps);
+                assert_eq!(global.score, *global_score);
+                assert_eq!(global.pairs, gapped);
+                assert_eq!(rescore(&global.pairs, seq_1, seq_2, matrix, &gaps), global.score);
+
+                let local = smith_waterman(seq_1, seq_2, matrix, &gaps);
+                assert_eq!(local.score, *local_score);
+                assert!(covers(&local.pairs, seq_1.len(), seq_2.len(), true));
+                assert_eq!(rescore(&local.pairs, seq_1, seq_2, matrix, &gaps), local.score);
+            }
+            //HEA against HEA, and AWGHEE against AWHEAE
+            assert_eq!(smith_waterman(seq_1, seq_2, &SubstitutionMatrix::blosum62(), &gaps).pairs, vec![(Some(0), Some(3)), (Some(1), Some(4)), (Some(2), Some(5))]);
+            assert_eq!(smith_waterman(seq_1, seq_2, &SubstitutionMatrix::pam250(), &gaps).pairs, (4..10).map(|i| {(Some(i), Some(i - 3))}).collect::<Vec<_>>());
+
+            //one long gap beats two short ones
+            let blosum62 = SubstitutionMatrix::blosum62();
+            let alignment = needleman_wunsch(b"WWWWKKKKWWWW", b"WWWWWWWW", &blosum62, &gaps);
+            assert_eq!(alignment.score, 8 * 11 - gaps.penalty(4));
+            assert_eq!(smith_waterman(b"PPP", b"WWW", &blosum62, &gaps), Alignment{score: 0, pairs: vec![]});
+            assert_eq!(needleman_wunsch(b"", b"WW", &blosum62, &gaps).score, -13);
+        }
+
+        #[test]
+        fn parse_rejects_malformed_matrices() {
+            let good = "# a comment\n   A  C\nA  2 -1\nC -1  3\n";
+            let matrix = SubstitutionMatrix::parse(good).unwrap();
+            assert_eq!((matrix.alphabet(), matrix.score(b'a', b'C'), matrix.score(b'C', b'c')), (&b"AC"[..], -1, 3));
+            //a ragged row, an asymmetric matrix, no header line, a missing row
+            assert_eq!(SubstitutionMatrix::parse("   A  C\nA  2 -1\nC -1\n"), None);
+            assert_eq!(SubstitutionMatrix::parse("   A  C\nA  2 -1\nC  0  3\n"), None);
+            assert_eq!(SubstitutionMatrix::parse("A  2 -1\nC -1  3\n"), None);
+            assert_eq!(SubstitutionMatrix::parse("   A  C\nA  2 -1\n"), None);
+            assert_eq!(SubstitutionMatrix::parse(""), None);
+        }
+
+        #[test]
+        fn pretty_alignment() {
+            let dna = SubstitutionMatrix::match_mismatch("ACGT", 3, -3);
+            let alignment = smith_waterman(b"TGTTACGG", b"GGTTGACTA", &dna, &GapPenalty::linear(2));
+            let expected = concat!(
+                "Length: 6\n",
+                "Identity: 5/6 (83.3%)\n",
+                "Similarity: 5/6 (83.3%)\n",
+                "Gaps: 1/6 (16.7%)\n",
+                "Score: 13\n",
+                "2 GTT- 4\n",
+                "  |||\n",
+                "2 GTTG 5\n",
+                "\n",
+                "5 AC 6\n",
+                "  ||\n",
+                "6 AC 7\n"
+            );
+            assert_eq!(alignment.pretty(b"TGTTACGG", b"GGTTGACTA", &dna, 4), expected);
+
+            let blosum62 = SubstitutionMatrix::blosum62();
+            let alignment = needleman_wunsch(b"HEAGAWGHEE", b"PAWHEAE", &SubstitutionMatrix::pam250(), &GapPenalty::affine(12, 1));
+            let expected = concat!(
+                "Length: 10\n",
+                "Identity: 3/10 (30.0%)\n",
+                "Similarity: 3/10 (30.0%)\n",
+                "Gaps: 3/10 (30.0%)\n",
+                "Score: 8\n",
+                " 1 HEAGAWGHEE 10\n",
+                "      .||...|\n",
+                " 1 ---PAWHEAE 7\n"
+            );
+            //marked with BLOSUM62, where G-H scores -2
+            assert_eq!(alignment.pretty(b"HEAGAWGHEE", b"PAWHEAE", &blosum62, 60), expected);
+        }
+    }
+}
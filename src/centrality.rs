@@ -0,0 +1,466 @@
+pub mod centrality_algos {
+    use crate::graph::*;
+
+    /**
+     * How per-vertex scores are scaled before they are returned.
+     * Standard is the usual scaling of each measure and is described on each function.
+     */
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Normalization {
+        None,
+        Standard,
+        Sum,        //scores sum to 1
+        Max         //the largest score is 1
+    }
+
+    /**
+     * Stopping rule for the measures computed by power iteration.
+     * Iteration stops once the total change of the scores between two rounds is below
+     * tolerance * n. If that doesn't happen within max_iterations, no answer is returned.
+     */
+    #[derive(Clone, Copy, Debug)]
+    pub struct PowerIteration {
+        pub tolerance: f64,
+        pub max_iterations: usize
+    }
+
+    impl PowerIteration {
+        pub fn new(tolerance: f64, max_iterations: usize) -> Self {
+            PowerIteration {
+                tolerance,
+                max_iterations
+            }
+        }
+    }
+
+    impl Default for PowerIteration {
+        fn default() -> Self {
+            PowerIteration::new(1e-6, 1000)
+        }
+    }
+
+    //scales scores to sum or max 1. None and Standard leave them as they are
+    fn rescale(mut scores: Vec<f64>, norm: Normalization) -> Vec<f64> {
+        let total = match norm {
+            Normalization::Sum => scores.iter().sum(),
+            Normalization::Max => scores.iter().cloned().fold(0.0, f64::max),
+            _ => 1.0
+        };
+        if total != 0.0 {
+            scores.iter_mut().for_each(|s| {*s /= total});
+        }
+        scores
+    }
+
+    fn euclidean_norm(scores: &[f64]) -> f64 {
+        scores.iter().map(|s| {s * s}).sum::<f64>().sqrt()
+    }
+
+    //the graph the shortest path searches run on. For undirected graphs every edge is added in both directions
+    fn search_graph(graph: &Graph, directed: bool) -> Graph {
+        let mut edges = graph.edges.clone();
+        if !directed {
+            edges.extend(graph.edges.iter().map(|e| {Edge::new(e.to, e.from, e.weight)}));
+        }
+        Graph::new(edges, graph.vertices.clone())
+    }
+
+    //distances from vertices[source], using bfs or dijkstra
+    fn distances_from(graph: &Graph, source: usize, weighted: bool) -> Vec<i32> {
+        if weighted {
+            dijkstra_paths(graph, graph.vertices[source]).0
+        } else {
+            bfs(graph, source)
+        }
+    }
+
+    //incoming edges of every vertex, as (source position, weight) pairs
+    fn reverse_adjacency(graph: &Graph) -> Vec<Vec<(usize, i32)>> {
+        let mut incoming: Vec<Vec<(usize, i32)>> = vec![Vec::new(); graph.vertices.len()];
+        for (u, outgoing) in graph.adjacency_list(true).into_iter().enumerate() {
+            for (v, w) in outgoing {
+                incoming[v].push((u, w));
+            }
+        }
+        incoming
+    }
+
+    /**
+     * Betweenness centrality counts how often a vertex lies on shortest paths between
+     * other vertices: the sum over pairs (s, t) of the fraction of shortest s-t paths through v.
+     *
+     * This is Brandes' algorithm. For every source s, the distances from bfs (or dijkstra when
+     * weighted) give the shortest path DAG, sigma[v] counts the shortest s-v paths through it,
+     * and the dependencies delta[v] = sum over successors w of sigma[v]/sigma[w] * (1 + delta[w])
+     * are accumulated from the farthest vertices back to s.
+     *
+     * Weights must be positive. For undirected graphs each pair is counted once.
+     * Standard normalization divides by the number of pairs not including v:
+     * (n-1)(n-2) for directed graphs and (n-1)(n-2)/2 for undirected ones.
+     *
+     * Runtime: O(nm) unweighted, O(nm log n) weighted
+     */
+    pub fn betweenness_centrality(graph: &Graph, directed: bool, weighted: bool, norm: Normalization) -> Vec<f64> {
+        let n = graph.vertices.len();
+        let search = search_graph(graph, directed);
+        let incoming = reverse_adjacency(&search);
+        let mut centrality = vec![0.0; n];
+
+        for s in 0..n {
+            let dist = distances_from(&search, s, weighted);
+            let mut order: Vec<usize> = (0..n).filter(|&v| {dist[v] != i32::MAX}).collect();
+            order.sort_by_key(|&v| {dist[v]});
+
+            //count shortest paths and record the predecessors on them
+            let mut sigma = vec![0.0; n];
+            let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+            sigma[s] = 1.0;
+            for &v in order.iter() {
+                for &(u, w) in incoming[v].iter() {
+                    let step = if weighted {w} else {1};
+                    if u != v && dist[u] != i32::MAX && dist[u] + step == dist[v] {
+                        sigma[v] += sigma[u];
+                        preds[v].push(u);
+                    }
+                }
+            }
+
+            //accumulate dependencies, farthest vertices first
+            let mut delta = vec![0.0; n];
+            for &v in order.iter().rev() {
+                for &u in preds[v].iter() {
+                    delta[u] += sigma[u] / sigma[v] * (1.0 + delta[v]);
+                }
+                if v != s {
+                    centrality[v] += delta[v];
+                }
+            }
+        }
+
+        //undirected pairs were counted from both ends
+        if !directed {
+            centrality.iter_mut().for_each(|c| {*c /= 2.0});
+        }
+        if norm == Normalization::Standard && n > 2 {
+            let pairs = ((n - 1) * (n - 2)) as f64;
+            let pairs = if directed {pairs} else {pairs / 2.0};
+            centrality.iter_mut().for_each(|c| {*c /= pairs});
+        }
+        rescale(centrality, norm)
+    }
+
+    /**
+     * Closeness centrality is the inverse of the average distance from a vertex to the
+     * vertices it can reach: (r - 1) / (sum of distances), where r - 1 vertices are reachable.
+     * Distances are measured from the vertex along outgoing edges.
+     *
+     * Standard normalization applies the Wasserman-Faust correction for disconnected graphs,
+     * multiplying by (r - 1) / (n - 1) so that vertices in small components score lower.
+     *
+     * Runtime: n runs of bfs (or dijkstra when weighted)
+     */
+    pub fn closeness_centrality(graph: &Graph, directed: bool, weighted: bool, norm: Normalization) -> Vec<f64> {
+        let n = graph.vertices.len();
+        let search = search_graph(graph, directed);
+
+        let centrality = (0..n).map(|u| {
+            let dist = distances_from(&search, u, weighted);
+            let reachable: Vec<f64> = dist.iter().filter(|&&d| {d != i32::MAX}).map(|&d| {d as f64}).collect();
+            let total: f64 = reachable.iter().sum();
+            let others = (reachable.len() - 1) as f64;
+
+            if total == 0.0 {
+                0.0
+            } else if norm == Normalization::Standard {
+                (others / total) * (others / (n - 1) as f64)
+            } else {
+                others / total
+            }
+        }).collect();
+
+        rescale(centrality, norm)
+    }
+
+    /**
+     * Harmonic centrality sums the inverse distances from a vertex to all other vertices,
+     * where unreachable vertices add 0. Unlike closeness it needs no correction for
+     * disconnected graphs. Distances are measured from the vertex along outgoing edges.
+     *
+     * Standard normalization divides by n - 1, the score of a vertex adjacent to all others.
+     */
+    pub fn harmonic_centrality(graph: &Graph, directed: bool, weighted: bool, norm: Normalization) -> Vec<f64> {
+        let n = graph.vertices.len();
+        let search = search_graph(graph, directed);
+
+        let mut centrality: Vec<f64> = (0..n).map(|u| {
+            let dist = distances_from(&search, u, weighted);
+            dist.iter().enumerate()
+                .filter(|&(v, &d)| {v != u && d != i32::MAX && d > 0})
+                .map(|(_, &d)| {1.0 / d as f64})
+                .sum()
+        }).collect();
+
+        if norm == Normalization::Standard && n > 1 {
+            centrality.iter_mut().for_each(|c| {*c /= (n - 1) as f64});
+        }
+        rescale(centrality, norm)
+    }
+
+    //incoming edge weights used by the spectral measures
+    fn spectral_incoming(graph: &Graph, directed: bool, weighted: bool) -> Vec<Vec<(usize, f64)>> {
+        reverse_adjacency(&search_graph(graph, directed)).into_iter().map(|edges| {
+            edges.into_iter().map(|(u, w)| {(u, if weighted {w as f64} else {1.0})}).collect()
+        }).collect()
+    }
+
+    /**
+     * Eigenvector centrality scores a vertex by the scores of the vertices pointing to it:
+     * x[v] is proportional to the sum of A[u][v] * x[u]. The answer is the principal
+     * eigenvector of the transposed adjacency matrix.
+     *
+     * This is power iteration on (A^T + I), which has the same principal eigenvector
+     * but doesn't oscillate on bipartite graphs. Returns None if it doesn't converge.
+     * Standard (and None) normalization gives a vector of Euclidean length 1.
+     */
+    pub fn eigenvector_centrality(
+        graph: &Graph,
+        directed: bool,
+        weighted: bool,
+        norm: Normalization,
+        iteration: PowerIteration
+    ) -> Option<Vec<f64>> {
+        let n = graph.vertices.len();
+        if n == 0 {
+            return Some(Vec::new());
+        }
+        let incoming = spectral_incoming(graph, directed, weighted);
+        let mut x = vec![1.0 / n as f64; n];
+
+        for _i in 0..iteration.max_iterations {
+            let mut next: Vec<f64> = x.clone();
+            for (v, edges) in incoming.iter().enumerate() {
+                for &(u, w) in edges.iter() {
+                    next[v] += w * x[u];
+                }
+            }
+            let length = euclidean_norm(&next);
+            if length == 0.0 {
+                return None;
+            }
+            next.iter_mut().for_each(|s| {*s /= length});
+
+            let change: f64 = next.iter().zip(x.iter()).map(|(a, b)| {(a - b).abs()}).sum();
+            x = next;
+            if change < iteration.tolerance * n as f64 {
+                return Some(rescale(x, norm));
+            }
+        }
+        None
+    }
+
+    /**
+     * Katz centrality generalizes eigenvector centrality by giving every vertex a base score:
+     *
+     *  x[v] = alpha * (sum of A[u][v] * x[u]) + beta
+     *
+     * so a vertex counts all walks ending at it, with walks of length k weighted by alpha^k.
+     * alpha must be below 1 / (largest eigenvalue of A) for the series to converge;
+     * otherwise None is returned. Standard normalization gives a vector of Euclidean length 1.
+     */
+    pub fn katz_centrality(
+        graph: &Graph,
+        alpha: f64,
+        beta: f64,
+        directed: bool,
+        weighted: bool,
+        norm: Normalization,
+        iteration: PowerIteration
+    ) -> Option<Vec<f64>> {
+        let n = graph.vertices.len();
+        let incoming = spectral_incoming(graph, directed, weighted);
+        let mut x = vec![0.0; n];
+
+        for _i in 0..iteration.max_iterations {
+            let next: Vec<f64> = incoming.iter().map(|edges| {
+                alpha * edges.iter().map(|&(u, w)| {w * x[u]}).sum::<f64>() + beta
+            }).collect();
+
+            let change: f64 = next.iter().zip(x.iter()).map(|(a, b)| {(a - b).abs()}).sum();
+            x = next;
+            if change < iteration.tolerance * n as f64 {
+                if norm == Normalization::Standard {
+                    let length = euclidean_norm(&x);
+                    if length != 0.0 {
+                        x.iter_mut().for_each(|s| {*s /= length});
+                    }
+                }
+                return Some(rescale(x, norm));
+            }
+        }
+        None
+    }
+
+    /**
+     * HITS (hyperlink-induced topic search) gives each vertex of a directed graph two scores.
+     * A good hub points to many good authorities, and a good authority is pointed to by
+     * many good hubs:
+     *
+     *  authority[v] = sum of hub[u] over edges u -> v
+     *  hub[u] = sum of authority[v] over edges u -> v
+     *
+     * The scores are found by power iteration and returned as (hubs, authorities),
+     * or None if they don't converge. Standard (and None) normalization makes each sum to 1.
+     */
+    pub fn hits(graph: &Graph, weighted: bool, norm: Normalization, iteration: PowerIteration) -> Option<(Vec<f64>, Vec<f64>)> {
+        let n = graph.vertices.len();
+        if n == 0 {
+            return Some((Vec::new(), Vec::new()));
+        }
+        let outgoing: Vec<Vec<(usize, f64)>> = graph.adjacency_list(true).into_iter().map(|edges| {
+            edges.into_iter().map(|(v, w)| {(v, if weighted {w as f64} else {1.0})}).collect()
+        }).collect();
+        let mut hubs = vec![1.0 / n as f64; n];
+        let mut authorities = vec![0.0; n];
+
+        //scale by the largest score while iterating, by the sum at the end
+        let scale_max = |scores: &mut Vec<f64>| {
+            let max = scores.iter().cloned().fold(0.0, f64::max);
+            if max != 0.0 {
+                scores.iter_mut().for_each(|s| {*s /= max});
+            }
+        };
+
+        for _i in 0..iteration.max_iterations {
+            authorities = vec![0.0; n];
+            for (u, edges) in outgoing.iter().enumerate() {
+                for &(v, w) in edges.iter() {
+                    authorities[v] += w * hubs[u];
+                }
+            }
+            scale_max(&mut authorities);
+
+            let mut next_hubs = vec![0.0; n];
+            for (u, edges) in outgoing.iter().enumerate() {
+                for &(v, w) in edges.iter() {
+                    next_hubs[u] += w * authorities[v];
+                }
+            }
+            scale_max(&mut next_hubs);
+
+            let change: f64 = next_hubs.iter().zip(hubs.iter()).map(|(a, b)| {(a - b).abs()}).sum();
+            hubs = next_hubs;
+            if change < iteration.tolerance * n as f64 {
+                let final_norm = match norm {
+                    Normalization::None | Normalization::Standard => Normalization::Sum,
+                    other => other
+                };
+                return Some((rescale(hubs, final_norm), rescale(authorities, final_norm)));
+            }
+        }
+        None
+    }
+
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::generators::graph_generators::gnp;
+
+        //a graph on the ids 1..=n
+        fn graph_of(n: i32, pairs: &[(i32, i32)]) -> Graph {
+            let vertices: Vec<Vertex> = (1..=n).map(Vertex::new).collect();
+            let edges = pairs.iter().map(|&(u, v)| {Edge::new(Vertex::new(u), Vertex::new(v), 1)}).collect();
+            Graph::new(edges, vertices)
+        }
+
+        //1 - 2 - 3 - 4 - 5
+        fn path() -> Graph {
+            graph_of(5, &[(1, 2), (2, 3), (3, 4), (4, 5)])
+        }
+
+        //1 joined to 2, 3, 4 and 5
+        fn star() -> Graph {
+            graph_of(5, &[(1, 2), (1, 3), (1, 4), (1, 5)])
+        }
+
+        //a cycle of 6 with both directions of every edge
+        fn symmetric_cycle() -> Graph {
+            let mut pairs: Vec<(i32, i32)> = (1..=6).map(|i| {(i, i % 6 + 1)}).collect();
+            pairs.extend((1..=6).map(|i| {(i % 6 + 1, i)}));
+            graph_of(6, &pairs)
+        }
+
+        fn assert_close(actual: &[f64], expected: &[f64]) {
+            assert_eq!(actual.len(), expected.len());
+            for (a, e) in actual.iter().zip(expected.iter()) {
+                assert!((a - e).abs() < 1e-6, "{:?} != {:?}", actual, expected);
+            }
+        }
+
+        #[test]
+        fn betweenness_of_a_path_and_a_star() {
+            //3 of the 6 pairs of other vertices pass through 2, 4 through the middle
+            assert_close(&betweenness_centrality(&path(), false, false, Normalization::None), &[0.0, 3.0, 4.0, 3.0, 0.0]);
+            assert_close(&betweenness_centrality(&path(), false, false, Normalization::Standard), &[0.0, 0.5, 4.0 / 6.0, 0.5, 0.0]);
+            assert_close(&betweenness_centrality(&path(), false, true, Normalization::Max), &[0.0, 0.75, 1.0, 0.75, 0.0]);
+            //every pair of leaves goes through the center
+            assert_close(&betweenness_centrality(&star(), false, false, Normalization::None), &[6.0, 0.0, 0.0, 0.0, 0.0]);
+            assert_close(&betweenness_centrality(&star(), false, false, Normalization::Standard), &[1.0, 0.0, 0.0, 0.0, 0.0]);
+            //directed, only 1 -> 3 passes through 2
+            let directed = graph_of(3, &[(1, 2), (2, 3)]);
+            assert_close(&betweenness_centrality(&directed, true, false, Normalization::None), &[0.0, 1.0, 0.0]);
+        }
+
+        #[test]
+        fn closeness_and_harmonic_on_a_path() {
+            assert_close(&closeness_centrality(&path(), false, false, Normalization::None), &[0.4, 4.0 / 7.0, 4.0 / 6.0, 4.0 / 7.0, 0.4]);
+            assert_close(&harmonic_centrality(&path(), false, false, Normalization::None), &[25.0 / 12.0, 17.0 / 6.0, 3.0, 17.0 / 6.0, 25.0 / 12.0]);
+            assert_close(&harmonic_centrality(&path(), false, false, Normalization::Standard), &[25.0 / 48.0, 17.0 / 24.0, 0.75, 17.0 / 24.0, 25.0 / 48.0]);
+            //along the edges only, 1 reaches everything and 5 nothing
+            let closeness = closeness_centrality(&path(), true, false, Normalization::None);
+            assert_close(&closeness, &[0.4, 3.0 / 6.0, 2.0 / 3.0, 1.0, 0.0]);
+            //two separate edges: each vertex reaches 1 of the 3 others
+            let split = graph_of(4, &[(1, 2), (3, 4)]);
+            assert_close(&closeness_centrality(&split, false, false, Normalization::Standard), &[1.0 / 3.0; 4]);
+        }
+
+        #[test]
+        fn page_rank_sums_to_one() {
+            for seed in 0..10 {
+                let graph = gnp(30, 0.1, true, seed);
+                let ranks = page_rank(&graph, 0.85, 1e-10, 1000);
+                assert!((ranks.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+                assert!(ranks.iter().all(|&r| {r >= 0.15 / 30.0 - 1e-12}));
+            }
+            assert_close(&page_rank(&symmetric_cycle(), 0.85, 1e-10, 1000), &[1.0 / 6.0; 6]);
+        }
+
+        #[test]
+        fn spectral_measures_on_a_symmetric_graph() {
+            let graph = symmetric_cycle();
+            let iteration = PowerIteration::default();
+            let eigenvector = eigenvector_centrality(&graph, true, false, Normalization::Standard, iteration).unwrap();
+            assert_close(&eigenvector, &[1.0 / 6f64.sqrt(); 6]);
+            //every vertex has 2 in-neighbors: x = 2 alpha x + beta
+            let katz = katz_centrality(&graph, 0.1, 1.0, true, false, Normalization::None, iteration).unwrap();
+            assert_close(&katz, &[1.25; 6]);
+            let (hubs, authorities) = hits(&graph, false, Normalization::Standard, iteration).unwrap();
+            assert_close(&hubs, &[1.0 / 6.0; 6]);
+            assert_close(&authorities, &[1.0 / 6.0; 6]);
+
+            //the center of a star with 4 leaves is twice as central as each leaf
+            let star = eigenvector_centrality(&star(), false, false, Normalization::Standard, iteration).unwrap();
+            assert_close(&star, &[0.5f64.sqrt(), 0.125f64.sqrt(), 0.125f64.sqrt(), 0.125f64.sqrt(), 0.125f64.sqrt()]);
+        }
+
+        #[test]
+        fn power_iteration_gives_up() {
+            let graph = symmetric_cycle();
+            let never = PowerIteration::new(0.0, 50);
+            assert_eq!(eigenvector_centrality(&graph, true, false, Normalization::Standard, never), None);
+            assert_eq!(hits(&graph, false, Normalization::Standard, never), None);
+            //alpha above 1 / 2, the inverse of the largest eigenvalue
+            assert_eq!(katz_centrality(&graph, 0.6, 1.0, true, false, Normalization::None, PowerIteration::default()), None);
+        }
+    }
+}
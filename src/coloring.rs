@@ -0,0 +1,617 @@
+pub mod coloring_algos {
+    use crate::graph::*;
+    use std::time::{Duration, Instant};
+
+    /**
+     * Limits for the exact solvers in this module, which all run in exponential time.
+     * A node is a single call of the backtracking search.
+     * When either limit is reached the search stops and returns the best answer found so far.
+     */
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct SearchBudget {
+        pub max_nodes: Option<u64>,
+        pub time_limit: Option<Duration>
+    }
+
+    impl SearchBudget {
+        pub fn new(max_nodes: Option<u64>, time_limit: Option<Duration>) -> Self {
+            SearchBudget {
+                max_nodes,
+                time_limit
+            }
+        }
+
+        pub fn unlimited() -> Self {
+            SearchBudget::new(None, None)
+        }
+    }
+
+    /**
+     * Answer of an exact solver.
+     * complete is true if the whole search space was explored within the budget,
+     * meaning the value is exact. Otherwise it is the best value found before stopping.
+     */
+    #[derive(Clone, Debug)]
+    pub struct SearchResult<T> {
+        pub value: T,
+        pub complete: bool,
+        pub nodes: u64
+    }
+
+    //counts search nodes and checks them against the budget
+    struct BudgetTracker {
+        budget: SearchBudget,
+        start: Instant,
+        nodes: u64,
+        exhausted: bool
+    }
+
+    impl BudgetTracker {
+        fn new(budget: SearchBudget) -> Self {
+            BudgetTracker {
+                budget,
+                start: Instant::now(),
+                nodes: 0,
+                exhausted: false
+            }
+        }
+
+        //returns false once the budget has run out
+        fn tick(&mut self) -> bool {
+            if self.exhausted {
+                return false;
+            }
+            self.nodes += 1;
+
+            if let Some(max) = self.budget.max_nodes {
+                if self.nodes > max {
+                    self.exhausted = true;
+                }
+            }
+            if let Some(limit) = self.budget.time_limit {
+                if self.start.elapsed() > limit {
+                    self.exhausted = true;
+                }
+            }
+            !self.exhausted
+        }
+
+        fn finish<T>(self, value: T) -> SearchResult<T> {
+            SearchResult {
+                value,
+                complete: !self.exhausted,
+                nodes: self.nodes
+            }
+        }
+    }
+
+    /**
+     * A vertex coloring. colors[i] is the color of graph.vertices[i],
+     * colors are numbered 0..num_colors.
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Coloring {
+        pub colors: Vec<usize>,
+        pub num_colors: usize
+    }
+
+    impl Coloring {
+        fn from_colors(colors: Vec<usize>) -> Self {
+            let num_colors = colors.iter().map(|c| {c + 1}).max().unwrap_or(0);
+            Coloring {
+                colors,
+                num_colors
+            }
+        }
+
+        /**
+         * Returns true if no edge of the graph joins two vertices of the same color
+         */
+        pub fn is_proper(&self, graph: &Graph) -> bool {
+            let adj = simple_adjacency(graph);
+            adj.iter().enumerate().all(|(u, neighbors)| {
+                neighbors.iter().all(|&v| {self.colors[u] != self.colors[v]})
+            })
+        }
+
+        /**
+         * Groups the vertex ids by color, e.g. the exams sharing a time slot
+         */
+        pub fn classes(&self, graph: &Graph) -> Vec<Vec<i32>> {
+            let mut classes: Vec<Vec<i32>> = vec![Vec::new(); self.num_colors];
+            for (i, c) in self.colors.iter().enumerate() {
+                classes[*c].push(graph.vertices[i].id);
+            }
+            classes
+        }
+    }
+
+    //undirected neighbor lists without self loops or parallel edges
+    fn simple_adjacency(graph: &Graph) -> Vec<Vec<usize>> {
+        graph.adjacency_list(false).into_iter().enumerate().map(|(u, neighbors)| {
+            let mut list: Vec<usize> = neighbors.iter().map(|n| {n.0}).filter(|&v| {v != u}).collect();
+            list.sort_unstable();
+            list.dedup();
+            list
+        }).collect()
+    }
+
+    fn adjacency_matrix(adj: &[Vec<usize>]) -> Vec<Vec<bool>> {
+        let mut matrix = vec![vec![false; adj.len()]; adj.len()];
+        for (u, neighbors) in adj.iter().enumerate() {
+            for &v in neighbors {
+                matrix[u][v] = true;
+            }
+        }
+        matrix
+    }
+
+    //smallest color not used by any already colored neighbor
+    fn smallest_free_color(neighbors: &[usize], colors: &[Option<usize>]) -> usize {
+        let mut taken = vec![false; neighbors.len() + 1];
+        for &v in neighbors {
+            if let Some(c) = colors[v] {
+                if c < taken.len() {
+                    taken[c] = true;
+                }
+            }
+        }
+        taken.iter().position(|t| {!t}).unwrap()
+    }
+
+    /**
+     * The Welsh-Powell algorithm is a greedy coloring heuristic.
+     * Vertices are sorted by degree in descending order. The first uncolored vertex in that
+     * order starts a new color, and the rest of the list is scanned to give the same color
+     * to every vertex that has no neighbor with it yet. This repeats until all vertices are colored.
+     *
+     * The graph is treated as undirected. Uses at most (max degree + 1) colors.
+     * Runtime: O(n^2 + m)
+     */
+    pub fn welsh_powell(graph: &Graph) -> Coloring {
+        let adj = simple_adjacency(graph);
+        let n = adj.len();
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|a, b| {adj[*b].len().cmp(&adj[*a].len())});    //stable, so ties keep input order
+
+        let mut colors: Vec<Option<usize>> = vec![None; n];
+        let mut current_color = 0;
+        let mut num_colored = 0;
+
+        while num_colored < n {
+            for &v in order.iter() {
+                let fits = colors[v].is_none() && adj[v].iter().all(|&u| {colors[u] != Some(current_color)});
+                if fits {
+                    colors[v] = Some(current_color);
+                    num_colored += 1;
+                }
+            }
+            current_color += 1;
+        }
+
+        Coloring::from_colors(colors.into_iter().map(|c| {c.unwrap()}).collect())
+    }
+
+    /**
+     * DSATUR (degree of saturation) is a greedy coloring heuristic by Brélaz.
+     * The saturation of a vertex is the number of distinct colors among its neighbors.
+     * At each step the uncolored vertex with the highest saturation is colored with the
+     * smallest available color, with ties broken by degree.
+     *
+     * DSATUR is exact for bipartite graphs and usually beats Welsh-Powell elsewhere.
+     * The graph is treated as undirected.
+     * Runtime: O(n(n + m))
+     */
+    pub fn dsatur(graph: &Graph) -> Coloring {
+        let adj = simple_adjacency(graph);
+        let n = adj.len();
+        let mut colors: Vec<Option<usize>> = vec![None; n];
+
+        for _step in 0..n {
+            let v = most_saturated(&adj, &colors).unwrap();
+            colors[v] = Some(smallest_free_color(&adj[v], &colors));
+        }
+
+        Coloring::from_colors(colors.into_iter().map(|c| {c.unwrap()}).collect())
+    }
+
+    //uncolored vertex with the highest saturation, ties broken by degree then position
+    fn most_saturated(adj: &[Vec<usize>], colors: &[Option<usize>]) -> Option<usize> {
+        let mut best: Option<(usize, usize, usize)> = None;      //(vertex, saturation, degree)
+
+        for (v, neighbors) in adj.iter().enumerate() {
+            if colors[v].is_some() {
+                continue;
+            }
+            let mut seen: Vec<usize> = neighbors.iter().filter_map(|&u| {colors[u]}).collect();
+            seen.sort_unstable();
+            seen.dedup();
+            let saturation = seen.len();
+            let degree = neighbors.len();
+
+            let better = match best {
+                Some((_, s, d)) => saturation > s || (saturation == s && degree > d),
+                None => true
+            };
+            if better {
+                best = Some((v, saturation, degree));
+            }
+        }
+
+        best.map(|b| {b.0})
+    }
+
+    /**
+     * Computes the chromatic number (the fewest colors needed) with an exact backtracking search.
+     *
+     * The search starts from the DSATUR coloring as an upper bound and uses the size of a
+     * greedily found clique as a lower bound. It then colors vertices in DSATUR order,
+     * trying only colors below the best known answer, so each improvement shrinks the tree.
+     * The search stops early when it meets the clique bound.
+     *
+     * If the budget runs out, the best coloring found so far is returned with complete = false.
+     */
+    pub fn chromatic_number(graph: &Graph, budget: SearchBudget) -> SearchResult<Coloring> {
+        let adj = simple_adjacency(graph);
+        let n = adj.len();
+        let mut tracker = BudgetTracker::new(budget);
+        let mut best = dsatur(graph);
+        let lower_bound = greedy_clique(&adj).len();
+
+        if best.num_colors > lower_bound {
+            let mut colors: Vec<Option<usize>> = vec![None; n];
+            color_backtrack(&adj, &mut colors, 0, 0, lower_bound, &mut best, &mut tracker);
+        }
+
+        tracker.finish(best)
+    }
+
+    fn color_backtrack(
+        adj: &[Vec<usize>],
+        colors: &mut Vec<Option<usize>>,
+        num_colored: usize,
+        used: usize,
+        lower_bound: usize,
+        best: &mut Coloring,
+        tracker: &mut BudgetTracker
+    ) {
+        if best.num_colors <= lower_bound || !tracker.tick() {
+            return;
+        }
+
+        //every vertex is colored with fewer colors than the best answer
+        if num_colored == colors.len() {
+            *best = Coloring::from_colors(colors.iter().map(|c| {c.unwrap()}).collect());
+            return;
+        }
+
+        let v = most_saturated(adj, colors).unwrap();
+        //a brand new color is only worth trying if it still beats the best answer
+        let max_color = std::cmp::min(used + 1, best.num_colors - 1);
+
+        for c in 0..max_color {
+            if c + 1 >= best.num_colors {
+                break;                                      //an improvement elsewhere tightened the bound
+            }
+            if adj[v].iter().any(|&u| {colors[u] == Some(c)}) {
+                continue;
+            }
+            colors[v] = Some(c);
+            color_backtrack(adj, colors, num_colored + 1, std::cmp::max(used, c + 1), lower_bound, best, tracker);
+            colors[v] = None;
+
+            if best.num_colors <= lower_bound || tracker.exhausted {
+                return;
+            }
+        }
+    }
+
+    //a clique built by repeatedly adding the highest degree vertex adjacent to all chosen ones
+    fn greedy_clique(adj: &[Vec<usize>]) -> Vec<usize> {
+        let matrix = adjacency_matrix(adj);
+        let mut order: Vec<usize> = (0..adj.len()).collect();
+        order.sort_by(|a, b| {adj[*b].len().cmp(&adj[*a].len())});
+
+        let mut clique: Vec<usize> = Vec::new();
+        for v in order {
+            if clique.iter().all(|&u| {matrix[u][v]}) {
+                clique.push(v);
+            }
+        }
+        clique
+    }
+
+    /**
+     * The Bron-Kerbosch algorithm enumerates all maximal cliques of an undirected graph.
+     * A clique is a set of vertices that are all adjacent to one another. It is maximal
+     * if no other vertex can be added to it.
+     *
+     * The search keeps three sets: R (the clique being grown), P (candidates that can extend R)
+     * and X (vertices already covered, used to skip non-maximal cliques).
+     * With Tomita's pivoting rule, only the vertices of P that are not neighbors of a pivot
+     * vertex u are branched on, where u is chosen to have the most neighbors in P.
+     * This keeps the runtime at O(3^(n/3)), the maximum number of maximal cliques.
+     *
+     * Each clique is returned as a sorted list of vertex ids.
+     */
+    pub fn bron_kerbosch(graph: &Graph, budget: SearchBudget) -> SearchResult<Vec<Vec<i32>>> {
+        let adj = simple_adjacency(graph);
+        let matrix = adjacency_matrix(&adj);
+        let mut tracker = BudgetTracker::new(budget);
+        let mut cliques: Vec<Vec<usize>> = Vec::new();
+
+        if !adj.is_empty() {
+            let candidates: Vec<usize> = (0..adj.len()).collect();
+            bron_kerbosch_pivot(&matrix, &mut Vec::new(), candidates, Vec::new(), &mut cliques, &mut tracker);
+        }
+
+        let ids = cliques.iter().map(|clique| {to_sorted_ids(graph, clique)}).collect();
+        tracker.finish(ids)
+    }
+
+    fn bron_kerbosch_pivot(
+        matrix: &[Vec<bool>],
+        r: &mut Vec<usize>,
+        mut p: Vec<usize>,
+        mut x: Vec<usize>,
+        cliques: &mut Vec<Vec<usize>>,
+        tracker: &mut BudgetTracker
+    ) {
+        if !tracker.tick() {
+            return;
+        }
+        if p.is_empty() {
+            if x.is_empty() {
+                cliques.push(r.clone());
+            }
+            return;
+        }
+
+        //pivot with the most neighbors in P
+        let pivot = *p.iter().chain(x.iter()).max_by_key(|&&u| {
+            p.iter().filter(|&&v| {matrix[u][v]}).count()
+        }).unwrap();
+
+        let branches: Vec<usize> = p.iter().cloned().filter(|&v| {!matrix[pivot][v]}).collect();
+        for v in branches {
+            let new_p = p.iter().cloned().filter(|&u| {matrix[v][u]}).collect();
+            let new_x = x.iter().cloned().filter(|&u| {matrix[v][u]}).collect();
+
+            r.push(v);
+            bron_kerbosch_pivot(matrix, r, new_p, new_x, cliques, tracker);
+            r.pop();
+
+            if tracker.exhausted {
+                return;
+            }
+            p.retain(|&u| {u != v});
+            x.push(v);
+        }
+    }
+
+    /**
+     * Finds a maximum clique (a largest set of pairwise adjacent vertices).
+     *
+     * This is a branch and bound search in the style of Tomita's MCQ. At each node the
+     * candidates are greedily colored; a candidate with color k can extend the current clique
+     * by at most k vertices, so branches that cannot beat the best clique are pruned.
+     *
+     * Returns the clique as a sorted list of vertex ids.
+     */
+    pub fn maximum_clique(graph: &Graph, budget: SearchBudget) -> SearchResult<Vec<i32>> {
+        let adj = simple_adjacency(graph);
+        let matrix = adjacency_matrix(&adj);
+        let clique = max_clique_indices(&matrix, &adj, budget);
+        SearchResult {
+            value: to_sorted_ids(graph, &clique.value),
+            complete: clique.complete,
+            nodes: clique.nodes
+        }
+    }
+
+    /**
+     * Finds a maximum independent set (a largest set of vertices with no edges between them).
+     * An independent set of a graph is a clique of its complement graph, so this runs the
+     * maximum clique search on the complement. Intended for small graphs, since the
+     * complement is stored as an n x n matrix.
+     *
+     * Returns the set as a sorted list of vertex ids.
+     */
+    pub fn maximum_independent_set(graph: &Graph, budget: SearchBudget) -> SearchResult<Vec<i32>> {
+        let adj = simple_adjacency(graph);
+        let n = adj.len();
+        let matrix = adjacency_matrix(&adj);
+
+        let mut complement = vec![vec![false; n]; n];
+        let mut complement_adj: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for u in 0..n {
+            for v in 0..n {
+                if u != v && !matrix[u][v] {
+                    complement[u][v] = true;
+                    complement_adj[u].push(v);
+                }
+            }
+        }
+
+        let set = max_clique_indices(&complement, &complement_adj, budget);
+        SearchResult {
+            value: to_sorted_ids(graph, &set.value),
+            complete: set.complete,
+            nodes: set.nodes
+        }
+    }
+
+    fn max_clique_indices(matrix: &[Vec<bool>], adj: &[Vec<usize>], budget: SearchBudget) -> SearchResult<Vec<usize>> {
+        let mut tracker = BudgetTracker::new(budget);
+        let mut best = greedy_clique(adj);
+
+        //visit candidates in descending degree order, which tightens the coloring bound
+        let mut candidates: Vec<usize> = (0..adj.len()).collect();
+        candidates.sort_by(|a, b| {adj[*b].len().cmp(&adj[*a].len())});
+        max_clique_expand(matrix, &mut Vec::new(), candidates, &mut best, &mut tracker);
+
+        tracker.finish(best)
+    }
+
+    fn max_clique_expand(
+        matrix: &[Vec<bool>],
+        r: &mut Vec<usize>,
+        p: Vec<usize>,
+        best: &mut Vec<usize>,
+        tracker: &mut BudgetTracker
+    ) {
+        if !tracker.tick() {
+            return;
+        }
+
+        //greedy coloring of the candidates: bounds[i] is an upper bound on the clique
+        //size that can be found among order[0..=i]
+        let mut order: Vec<usize> = Vec::new();
+        let mut bounds: Vec<usize> = Vec::new();
+        let mut color_classes: Vec<Vec<usize>> = Vec::new();
+        for &v in p.iter() {
+            let class = color_classes.iter().position(|class| {class.iter().all(|&u| {!matrix[u][v]})});
+            match class {
+                Some(k) => color_classes[k].push(v),
+                None => color_classes.push(vec![v])
+            }
+        }
+        for (k, class) in color_classes.iter().enumerate() {
+            for &v in class {
+                order.push(v);
+                bounds.push(k + 1);
+            }
+        }
+
+        let mut remaining = p;
+        while let Some(v) = order.pop() {
+            let bound = bounds.pop().unwrap();
+            if r.len() + bound <= best.len() {
+                return;
+            }
+
+            r.push(v);
+            let new_p: Vec<usize> = remaining.iter().cloned().filter(|&u| {matrix[v][u]}).collect();
+            if new_p.is_empty() {
+                if r.len() > best.len() {
+                    *best = r.clone();
+                }
+            } else {
+                max_clique_expand(matrix, r, new_p, best, tracker);
+            }
+            r.pop();
+
+            if tracker.exhausted {
+                return;
+            }
+            remaining.retain(|&u| {u != v});
+        }
+    }
+
+    fn to_sorted_ids(graph: &Graph, indices: &[usize]) -> Vec<i32> {
+        let mut ids: Vec<i32> = indices.iter().map(|&i| {graph.vertices[i].id}).collect();
+        ids.sort_unstable();
+        ids
+    }
+
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::generators::graph_generators::{complete, complete_bipartite, gnp};
+
+        //an undirected graph on the ids 1..=n
+        fn graph_of(n: i32, pairs: &[(i32, i32)]) -> Graph {
+            let vertices: Vec<Vertex> = (1..=n).map(Vertex::new).collect();
+            let edges = pairs.iter().map(|&(u, v)| {Edge::new(Vertex::new(u), Vertex::new(v), 1)}).collect();
+            Graph::new(edges, vertices)
+        }
+
+        fn cycle(n: i32) -> Graph {
+            let pairs: Vec<(i32, i32)> = (1..=n).map(|i| {(i, i % n + 1)}).collect();
+            graph_of(n, &pairs)
+        }
+
+        //outer cycle 1..=5, inner pentagram 6..=10, spokes i - (i + 5)
+        fn petersen() -> Graph {
+            let mut pairs: Vec<(i32, i32)> = (1..=5).map(|i| {(i, i % 5 + 1)}).collect();
+            pairs.extend((0..5).map(|i| {(6 + i, 6 + (i + 2) % 5)}));
+            pairs.extend((1..=5).map(|i| {(i, i + 5)}));
+            graph_of(10, &pairs)
+        }
+
+        #[test]
+        fn chromatic_numbers() {
+            for &(n, expected) in [(3, 3), (5, 3), (7, 3), (4, 2), (6, 2)].iter() {
+                let result = chromatic_number(&cycle(n), SearchBudget::unlimited());
+                assert!(result.complete);
+                assert_eq!(result.value.num_colors, expected, "C{}", n);
+                assert!(result.value.is_proper(&cycle(n)));
+            }
+            let graph = petersen();
+            let result = chromatic_number(&graph, SearchBudget::unlimited());
+            assert!(result.complete && result.value.is_proper(&graph));
+            assert_eq!(result.value.num_colors, 3);
+            assert_eq!(result.value.classes(&graph).iter().map(|class| {class.len()}).sum::<usize>(), 10);
+            assert_eq!(chromatic_number(&complete(6), SearchBudget::unlimited()).value.num_colors, 6);
+            assert_eq!(chromatic_number(&complete_bipartite(3, 4), SearchBudget::unlimited()).value.num_colors, 2);
+        }
+
+        #[test]
+        fn petersen_cliques_and_independent_sets() {
+            let graph = petersen();
+            let clique = maximum_clique(&graph, SearchBudget::unlimited());
+            assert!(clique.complete);
+            assert_eq!(clique.value.len(), 2);
+            let set = maximum_independent_set(&graph, SearchBudget::unlimited());
+            assert!(set.complete);
+            assert_eq!(set.value.len(), 4);
+            //no edge inside the set
+            assert!(graph.edges.iter().all(|e| {!(set.value.contains(&e.from.id) && set.value.contains(&e.to.id))}));
+            //the 15 edges are its maximal cliques
+            let cliques = bron_kerbosch(&graph, SearchBudget::unlimited());
+            assert_eq!(cliques.value.len(), 15);
+        }
+
+        #[test]
+        fn bron_kerbosch_lists_every_maximal_clique() {
+            //triangles 1 2 3 and 2 3 4 sharing an edge, a pendant edge 4 5 and an isolated 6
+            let graph = graph_of(6, &[(1, 2), (1, 3), (2, 3), (2, 4), (3, 4), (4, 5)]);
+            let result = bron_kerbosch(&graph, SearchBudget::unlimited());
+            assert!(result.complete);
+            let mut cliques = result.value;
+            cliques.sort();
+            assert_eq!(cliques, vec![vec![1, 2, 3], vec![2, 3, 4], vec![4, 5], vec![6]]);
+            assert_eq!(maximum_clique(&graph, SearchBudget::unlimited()).value.len(), 3);
+            assert_eq!(maximum_independent_set(&graph, SearchBudget::unlimited()).value.len(), 3);
+        }
+
+        #[test]
+        fn heuristic_colorings_are_proper() {
+            for seed in 0..20 {
+                let graph = gnp(25, 0.3, false, seed);
+                for coloring in [dsatur(&graph), welsh_powell(&graph)].iter() {
+                    assert!(coloring.is_proper(&graph));
+                    assert!(coloring.num_colors >= chromatic_number(&graph, SearchBudget::unlimited()).value.num_colors);
+                }
+            }
+            //DSATUR is exact on bipartite graphs
+            assert_eq!(dsatur(&complete_bipartite(4, 5)).num_colors, 2);
+            assert_eq!(dsatur(&cycle(8)).num_colors, 2);
+            let improper = Coloring{colors: vec![0; 3], num_colors: 1};
+            assert!(!improper.is_proper(&cycle(3)));
+        }
+
+        #[test]
+        fn node_limit_stops_the_search() {
+            let graph = petersen();
+            let budget = SearchBudget::new(Some(1), None);
+            let result = chromatic_number(&graph, budget);
+            assert!(!result.complete);
+            assert!(result.value.is_proper(&graph));
+            assert!(!bron_kerbosch(&graph, budget).complete);
+            assert!(!maximum_clique(&gnp(30, 0.5, false, 26), budget).complete);
+            assert!(!maximum_independent_set(&graph, budget).complete);
+        }
+    }
+}
@@ -0,0 +1,502 @@
+pub mod community_algos {
+    extern crate rand;
+    use crate::graph::*;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+
+    /**
+     * A clustering of the vertices of a graph.
+     * Each community is a sorted list of vertex ids, and the communities are ordered by
+     * their smallest id. modularity is the score of the partition on the input graph.
+     */
+    #[derive(Clone, Debug)]
+    pub struct Partition {
+        pub communities: Vec<Vec<i32>>,
+        pub modularity: f64
+    }
+
+    //undirected weighted graph over positions 0..n with parallel edges merged.
+    //Self loops are kept apart, loops[i] is the total weight of the loops at i.
+    struct WeightedGraph {
+        adj: Vec<Vec<(usize, f64)>>,
+        loops: Vec<f64>
+    }
+
+    impl WeightedGraph {
+        fn from_graph(graph: &Graph) -> Self {
+            let n = graph.vertices.len();
+            let mut edges: Vec<(usize, usize, f64)> = Vec::new();
+            let mut loops = vec![0.0; n];
+
+            for (u, neighbors) in graph.adjacency_list(true).into_iter().enumerate() {
+                for (v, w) in neighbors {
+                    if u == v {
+                        loops[u] += w as f64;
+                    } else {
+                        edges.push((u, v, w as f64));
+                    }
+                }
+            }
+            WeightedGraph::from_edges(n, edges, loops)
+        }
+
+        fn from_edges(n: usize, edges: Vec<(usize, usize, f64)>, loops: Vec<f64>) -> Self {
+            let mut adj: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+            for (u, v, w) in edges {
+                adj[u].push((v, w));
+                adj[v].push((u, w));
+            }
+
+            //merge parallel edges
+            for neighbors in adj.iter_mut() {
+                neighbors.sort_by(|a, b| {a.0.cmp(&b.0)});
+                let mut merged: Vec<(usize, f64)> = Vec::new();
+                for &(v, w) in neighbors.iter() {
+                    match merged.last_mut() {
+                        Some(last) if last.0 == v => last.1 += w,
+                        _ => merged.push((v, w))
+                    }
+                }
+                *neighbors = merged;
+            }
+
+            WeightedGraph {
+                adj,
+                loops
+            }
+        }
+
+        fn len(&self) -> usize {
+            self.adj.len()
+        }
+
+        //a self loop adds twice its weight to the degree, as in the adjacency matrix
+        fn degree(&self, u: usize) -> f64 {
+            self.adj[u].iter().map(|n| {n.1}).sum::<f64>() + 2.0 * self.loops[u]
+        }
+
+        fn total_weight(&self) -> f64 {
+            (0..self.len()).map(|u| {self.degree(u)}).sum::<f64>() / 2.0
+        }
+
+        //Q = sum over communities c of L_c / m - resolution * (d_c / 2m)^2
+        //where L_c is the weight inside c and d_c is the total degree of c
+        fn modularity(&self, labels: &[usize], resolution: f64) -> f64 {
+            let m = self.total_weight();
+            if m == 0.0 {
+                return 0.0;
+            }
+            let num_labels = labels.iter().map(|l| {l + 1}).max().unwrap_or(0);
+            let mut inside = vec![0.0; num_labels];
+            let mut degrees = vec![0.0; num_labels];
+
+            for u in 0..self.len() {
+                degrees[labels[u]] += self.degree(u);
+                inside[labels[u]] += self.loops[u];
+                for &(v, w) in self.adj[u].iter() {
+                    if labels[u] == labels[v] {
+                        inside[labels[u]] += w / 2.0;       //each edge is seen from both ends
+                    }
+                }
+            }
+
+            (0..num_labels).map(|c| {
+                inside[c] / m - resolution * (degrees[c] / (2.0 * m)).powi(2)
+            }).sum()
+        }
+    }
+
+    //renumbers labels to 0..k in order of first appearance
+    fn compact_labels(labels: &[usize]) -> Vec<usize> {
+        let mut mapping: Vec<Option<usize>> = vec![None; labels.iter().map(|l| {l + 1}).max().unwrap_or(0)];
+        let mut next = 0;
+        labels.iter().map(|&l| {
+            *mapping[l].get_or_insert_with(|| {
+                next += 1;
+                next - 1
+            })
+        }).collect()
+    }
+
+    fn to_partition(graph: &Graph, labels: &[usize], modularity: f64) -> Partition {
+        let labels = compact_labels(labels);
+        let num_labels = labels.iter().map(|l| {l + 1}).max().unwrap_or(0);
+        let mut communities: Vec<Vec<i32>> = vec![Vec::new(); num_labels];
+        for (i, l) in labels.iter().enumerate() {
+            communities[*l].push(graph.vertices[i].id);
+        }
+        communities.iter_mut().for_each(|c| {c.sort_unstable()});
+        communities.sort();
+
+        Partition {
+            communities,
+            modularity
+        }
+    }
+
+    /**
+     * Modularity measures how much more weight falls inside communities than expected
+     * if edges were placed at random while keeping every vertex's degree:
+     *
+     *  Q = 1/2m * sum over i, j in the same community of (A_ij - k_i * k_j / 2m)
+     *
+     * where A is the weighted adjacency matrix, k_i the weighted degree of i and m the
+     * total edge weight. Q lies in [-1/2, 1); values above about 0.3 indicate clear clusters.
+     *
+     * The graph is treated as undirected with non-negative weights.
+     * Vertices that are not in any community are placed in a community of their own.
+     */
+    pub fn modularity(graph: &Graph, communities: &[Vec<i32>]) -> f64 {
+        let n = graph.vertices.len();
+        let mut labels: Vec<usize> = (0..n).map(|i| {i + communities.len()}).collect();
+        for (c, community) in communities.iter().enumerate() {
+            for id in community {
+                let i = graph.index_of(*id).expect("Community member is not a vertex of the graph");
+                labels[i] = c;
+            }
+        }
+
+        WeightedGraph::from_graph(graph).modularity(&compact_labels(&labels), 1.0)
+    }
+
+    /**
+     * The Louvain method greedily optimizes modularity in two repeated phases.
+     *
+     * 1. Local moving: every vertex starts in its own community. Vertices are visited in a
+     *    random order and each is moved to the neighboring community with the largest
+     *    modularity gain, until no move improves the score.
+     * 2. Aggregation: each community is collapsed into a single vertex, with edge weights
+     *    summed, and phase 1 is repeated on the smaller graph.
+     *
+     * The process stops when phase 1 moves nothing. The resolution parameter (1.0 for
+     * standard modularity) trades off between many small (>1) and few large (<1) communities.
+     * The visiting order is drawn from the seed, so equal seeds give equal partitions.
+     *
+     * Runtime: roughly O(m log n) in practice
+     */
+    pub fn louvain(graph: &Graph, resolution: f64, seed: u64) -> Partition {
+        let original = WeightedGraph::from_graph(graph);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut labels: Vec<usize> = (0..original.len()).collect();   //community of each original vertex
+        let mut level = WeightedGraph::from_graph(graph);
+
+        loop {
+            let (moved, level_labels) = louvain_local_moving(&level, resolution, &mut rng);
+            if !moved {
+                break;
+            }
+            let level_labels = compact_labels(&level_labels);
+            labels = labels.iter().map(|&l| {level_labels[l]}).collect();
+            let next_level = aggregate(&level, &level_labels);
+            if next_level.len() == level.len() {
+                break;
+            }
+            level = next_level;
+        }
+
+        let score = original.modularity(&labels, resolution);
+        to_partition(graph, &labels, score)
+    }
+
+    //phase 1 of Louvain. Returns whether any vertex changed community
+    fn louvain_local_moving(g: &WeightedGraph, resolution: f64, rng: &mut StdRng) -> (bool, Vec<usize>) {
+        let n = g.len();
+        let m = g.total_weight();
+        let mut labels: Vec<usize> = (0..n).collect();
+        if m == 0.0 {
+            return (false, labels);
+        }
+
+        let degrees: Vec<f64> = (0..n).map(|u| {g.degree(u)}).collect();
+        let mut totals = degrees.clone();                       //total degree of each community
+        let mut order: Vec<usize> = (0..n).collect();
+        order.shuffle(rng);
+
+        //weight from the current vertex into each neighboring community. The touched
+        //communities are kept in a list so the choice doesn't depend on hash ordering
+        let mut links = vec![0.0; n];
+        let mut is_touched = vec![false; n];
+        let mut touched: Vec<usize> = Vec::new();
+        let mut any_moved = false;
+        let mut improved = true;
+
+        while improved {
+            improved = false;
+            for &u in order.iter() {
+                let current = labels[u];
+                for &(v, w) in g.adj[u].iter() {
+                    let c = labels[v];
+                    if !is_touched[c] {
+                        is_touched[c] = true;
+                        touched.push(c);
+                    }
+                    links[c] += w;
+                }
+
+                //take u out of its community, then find the best community to put it in
+                totals[current] -= degrees[u];
+                let gain = |c: usize, links: &[f64], totals: &[f64]| -> f64 {
+                    links[c] - resolution * totals[c] * degrees[u] / (2.0 * m)
+                };
+                let mut best = current;
+                let mut best_gain = gain(current, &links, &totals);
+                for &c in touched.iter() {
+                    let g_c = gain(c, &links, &totals);
+                    if g_c > best_gain + 1e-12 {
+                        best = c;
+                        best_gain = g_c;
+                    }
+                }
+                totals[best] += degrees[u];
+                labels[u] = best;
+
+                if best != current {
+                    improved = true;
+                    any_moved = true;
+                }
+                for &c in touched.iter() {
+                    links[c] = 0.0;
+                    is_touched[c] = false;
+                }
+                touched.clear();
+            }
+        }
+
+        (any_moved, labels)
+    }
+
+    //phase 2 of Louvain. Collapses each community into one vertex
+    fn aggregate(g: &WeightedGraph, labels: &[usize]) -> WeightedGraph {
+        let k = labels.iter().map(|l| {l + 1}).max().unwrap_or(0);
+        let mut loops = vec![0.0; k];
+        let mut edges: Vec<(usize, usize, f64)> = Vec::new();
+
+        for u in 0..g.len() {
+            loops[labels[u]] += g.loops[u];
+            for &(v, w) in g.adj[u].iter() {
+                if u < v {
+                    if labels[u] == labels[v] {
+                        loops[labels[u]] += w;
+                    } else {
+                        edges.push((labels[u], labels[v], w));
+                    }
+                }
+            }
+        }
+
+        WeightedGraph::from_edges(k, edges, loops)
+    }
+
+    /**
+     * Label propagation finds communities without optimizing any objective.
+     * Every vertex starts with a unique label. In each round the vertices are visited in a
+     * random order and each takes the label with the largest total edge weight among its
+     * neighbors, with ties broken at random (keeping the current label if it is among them).
+     * Densely connected groups quickly agree on a single label.
+     *
+     * Stops when a round changes no labels or after max_rounds rounds.
+     * The random choices are drawn from the seed, so equal seeds give equal partitions.
+     *
+     * Runtime: O(m) per round
+     */
+    pub fn label_propagation(graph: &Graph, max_rounds: usize, seed: u64) -> Partition {
+        let g = WeightedGraph::from_graph(graph);
+        let n = g.len();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut labels: Vec<usize> = (0..n).collect();
+        let mut order: Vec<usize> = (0..n).collect();
+        let mut weights = vec![0.0; n];
+        let mut is_touched = vec![false; n];
+        let mut touched: Vec<usize> = Vec::new();
+
+        for _round in 0..max_rounds {
+            let mut changed = false;
+            order.shuffle(&mut rng);
+
+            for &u in order.iter() {
+                if g.adj[u].is_empty() {
+                    continue;
+                }
+                for &(v, w) in g.adj[u].iter() {
+                    if !is_touched[labels[v]] {
+                        is_touched[labels[v]] = true;
+                        touched.push(labels[v]);
+                    }
+                    weights[labels[v]] += w;
+                }
+
+                let max_weight = touched.iter().map(|&l| {weights[l]}).fold(f64::MIN, f64::max);
+                let candidates: Vec<usize> = touched.iter().cloned().filter(|&l| {
+                    weights[l] >= max_weight - 1e-12
+                }).collect();
+
+                if !candidates.contains(&labels[u]) {
+                    labels[u] = candidates[rng.gen_range(0..candidates.len())];
+                    changed = true;
+                }
+
+                for &l in touched.iter() {
+                    weights[l] = 0.0;
+                    is_touched[l] = false;
+                }
+                touched.clear();
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        let score = g.modularity(&compact_labels(&labels), 1.0);
+        to_partition(graph, &labels, score)
+    }
+
+    /**
+     * The k-core of a graph is the largest subgraph where every vertex has degree at least k.
+     * The core number of a vertex is the largest k for which it belongs to the k-core.
+     *
+     * This is the Batagelj-Zaversnik algorithm: vertices are kept in buckets by degree and
+     * repeatedly the vertex of smallest remaining degree is removed, lowering its neighbors' degrees.
+     *
+     * The graph is treated as undirected, ignoring weights, self loops and parallel edges.
+     * Returns the core numbers, with indices matching graph.vertices.
+     * Runtime: O(n + m)
+     */
+    pub fn core_numbers(graph: &Graph) -> Vec<usize> {
+        let n = graph.vertices.len();
+        let adj: Vec<Vec<usize>> = graph.adjacency_list(false).into_iter().enumerate().map(|(u, neighbors)| {
+            let mut list: Vec<usize> = neighbors.iter().map(|e| {e.0}).filter(|&v| {v != u}).collect();
+            list.sort_unstable();
+            list.dedup();
+            list
+        }).collect();
+
+        let mut degree: Vec<usize> = adj.iter().map(|a| {a.len()}).collect();
+        let max_degree = degree.iter().cloned().max().unwrap_or(0);
+
+        //sort vertices by degree with a bucket sort
+        //bucket_start[d] is the position of the first vertex of degree d in order
+        let mut bucket_start = vec![0; max_degree + 1];
+        for &d in degree.iter() {
+            bucket_start[d] += 1;
+        }
+        let mut start = 0;
+        for count in bucket_start.iter_mut() {
+            let size = *count;
+            *count = start;
+            start += size;
+        }
+        let mut order = vec![0; n];
+        let mut position = vec![0; n];
+        let mut next_slot = bucket_start.clone();
+        for v in 0..n {
+            position[v] = next_slot[degree[v]];
+            order[position[v]] = v;
+            next_slot[degree[v]] += 1;
+        }
+
+        //peel vertices in order of current degree
+        for i in 0..n {
+            let v = order[i];
+            for &u in adj[v].iter() {
+                if degree[u] > degree[v] {
+                    //move u to the front of its bucket, then shrink its degree by one
+                    let du = degree[u];
+                    let front = bucket_start[du];
+                    let w = order[front];
+                    if u != w {
+                        order.swap(position[u], front);
+                        position[w] = position[u];
+                        position[u] = front;
+                    }
+                    bucket_start[du] += 1;
+                    degree[u] -= 1;
+                }
+            }
+        }
+
+        degree
+    }
+
+    /**
+     * Returns the sorted ids of the vertices in the k-core of the graph
+     */
+    pub fn k_core(graph: &Graph, k: usize) -> Vec<i32> {
+        let mut ids: Vec<i32> = core_numbers(graph).iter().enumerate()
+            .filter(|(_, &c)| {c >= k})
+            .map(|(i, _)| {graph.vertices[i].id})
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::generators::graph_generators::gnp;
+
+        //an undirected graph on the ids 1..=n
+        fn graph_of(n: i32, pairs: &[(i32, i32)]) -> Graph {
+            let vertices: Vec<Vertex> = (1..=n).map(Vertex::new).collect();
+            let edges = pairs.iter().map(|&(u, v)| {Edge::new(Vertex::new(u), Vertex::new(v), 1)}).collect();
+            Graph::new(edges, vertices)
+        }
+
+        //triangles 1 2 3 and 4 5 6 joined by the bridge 3 - 4
+        fn barbell() -> Graph {
+            graph_of(6, &[(1, 2), (1, 3), (2, 3), (3, 4), (4, 5), (4, 6), (5, 6)])
+        }
+
+        #[test]
+        fn modularity_of_two_triangles() {
+            let graph = barbell();
+            //each triangle holds 3 of the 7 edges and half of the degree
+            let split = modularity(&graph, &[vec![1, 2, 3], vec![4, 5, 6]]);
+            assert!((split - (2.0 * (3.0 / 7.0 - 0.25))).abs() < 1e-12);
+            assert!(modularity(&graph, &[vec![1, 2, 3, 4, 5, 6]]).abs() < 1e-12);
+            assert!((modularity(&graph, &[]) + 34.0 / 196.0).abs() < 1e-12);
+            assert!(modularity(&graph, &[vec![1, 4], vec![2, 5], vec![3, 6]]) < split);
+        }
+
+        #[test]
+        fn louvain_and_label_propagation_split_the_triangles() {
+            let graph = barbell();
+            let expected = vec![vec![1, 2, 3], vec![4, 5, 6]];
+            for seed in 0..10 {
+                let partition = louvain(&graph, 1.0, seed);
+                assert_eq!(partition.communities, expected);
+                assert!((partition.modularity - 5.0 / 14.0).abs() < 1e-12);
+                let partition = label_propagation(&graph, 100, seed);
+                assert_eq!(partition.communities, expected, "{}", seed);
+                assert!((partition.modularity - 5.0 / 14.0).abs() < 1e-12);
+            }
+        }
+
+        #[test]
+        fn equal_seeds_give_equal_partitions() {
+            let graph = gnp(60, 0.1, false, 27);
+            for seed in 0..5 {
+                let (first, second) = (louvain(&graph, 1.0, seed), louvain(&graph, 1.0, seed));
+                assert_eq!(first.communities, second.communities);
+                assert_eq!(first.modularity, second.modularity);
+                assert!((first.modularity - modularity(&graph, &first.communities)).abs() < 1e-9);
+                let (first, second) = (label_propagation(&graph, 50, seed), label_propagation(&graph, 50, seed));
+                assert_eq!(first.communities, second.communities);
+                assert_eq!(first.communities.iter().map(|community| {community.len()}).sum::<usize>(), 60);
+            }
+        }
+
+        #[test]
+        fn core_numbers_of_a_small_graph() {
+            //the K4 1 2 3 4, 5 joined to 1, 2 and 6, and an isolated 7
+            let graph = graph_of(7, &[(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4), (1, 5), (2, 5), (5, 6)]);
+            assert_eq!(core_numbers(&graph), vec![3, 3, 3, 3, 2, 1, 0]);
+            assert_eq!(k_core(&graph, 2), vec![1, 2, 3, 4, 5]);
+            assert_eq!(k_core(&graph, 3), vec![1, 2, 3, 4]);
+            assert!(k_core(&graph, 4).is_empty());
+            assert_eq!(core_numbers(&barbell()), vec![2; 6]);
+        }
+    }
+}
@@ -0,0 +1,430 @@
+extern crate rand;
+extern crate num;
+use rand::{CryptoRng, RngCore};
+use num::{BigUint, Num, One};
+use std::sync::OnceLock;
+use crate::elliptic_curve::*;
+use crate::hashing::hash_algos::*;
+
+/**
+ * The u coordinate 9 of the base point of Curve25519, as X25519 encodes it
+ */
+pub const X25519_BASEPOINT: [u8; 32] = [
+    9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0
+];
+
+//the field modulo 2^255 - 19
+fn field() -> PrimeField {
+    PrimeField::new(&((BigUint::one() << 255u32) - 19u32))
+}
+
+/**
+ * The X25519 function of RFC 7748: the u coordinate of kP on the Montgomery curve
+ * Curve25519, v^2 = u^3 + 486662u^2 + u, for the point P with coordinate u.
+ *
+ * The scalar is clamped first: the low 3 bits are cleared so that k is a multiple of the
+ * cofactor 8, and bit 254 is set so that every scalar takes the same number of steps.
+ * The Montgomery ladder then works on u alone, in projective form (X : Z), with a
+ * differential addition needing the fixed difference u, and swaps its two registers with
+ * masks whenever the next bit of k differs from the previous one.
+ */
+pub fn x25519(scalar: &[u8; 32], u: &[u8; 32]) -> [u8; 32] {
+    let f = field();
+    let mut k = *scalar;
+    k[0] &= 248;
+    k[31] &= 127;
+    k[31] |= 64;
+    let mut u_bytes = *u;
+    u_bytes[31] &= 127;
+
+    let x1 = f.from_le_bytes(&u_bytes);
+    let a24 = f.from_u64(121665);
+    let (mut x2, mut z2) = (f.one(), f.zero());
+    let (mut x3, mut z3) = (x1, f.one());
+    let mut swap: u64 = 0;
+    for t in (0..255).rev() {
+        let bit = ((k[t / 8] >> (t % 8)) & 1) as u64;
+        swap ^= bit;
+        conditional_swap(&mut x2, &mut x3, swap);
+        conditional_swap(&mut z2, &mut z3, swap);
+        swap = bit;
+
+        let a = f.add(&x2, &z2);
+        let aa = f.square(&a);
+        let b = f.sub(&x2, &z2);
+        let bb = f.square(&b);
+        let e = f.sub(&aa, &bb);
+        let c = f.add(&x3, &z3);
+        let d = f.sub(&x3, &z3);
+        let da = f.mul(&d, &a);
+        let cb = f.mul(&c, &b);
+        x3 = f.square(&f.add(&da, &cb));
+        z3 = f.mul(&x1, &f.square(&f.sub(&da, &cb)));
+        x2 = f.mul(&aa, &bb);
+        z2 = f.mul(&e, &f.add(&aa, &f.mul(&a24, &e)));
+    }
+    conditional_swap(&mut x2, &mut x3, swap);
+    conditional_swap(&mut z2, &mut z3, swap);
+    f.to_le_bytes(&f.mul(&x2, &f.inv(&z2)))
+}
+
+/**
+ * A new X25519 private key, 32 random bytes (clamped when used)
+ */
+pub fn x25519_private_key<R: RngCore + CryptoRng + ?Sized>(rng: &mut R) -> [u8; 32] {
+    let mut key = [0u8; 32];
+    rng.fill_bytes(&mut key);
+    key
+}
+
+/**
+ * The public key for an X25519 private key, the multiple of the base point
+ */
+pub fn x25519_public_key(private_key: &[u8; 32]) -> [u8; 32] {
+    x25519(private_key, &X25519_BASEPOINT)
+}
+
+/**
+ * X25519 key agreement: the shared secret with the other party's public key.
+ * Returns None if it is all zeros, which happens for public keys of small order and
+ * means the other party could have forced the result (RFC 7748, 6.1).
+ */
+pub fn x25519_agree(private_key: &[u8; 32], public_key: &[u8; 32]) -> Option<[u8; 32]> {
+    let shared = x25519(private_key, public_key);
+    if shared == [0u8; 32] {
+        None
+    } else {
+        Some(shared)
+    }
+}
+
+//a point (X/Z, Y/Z) of the Edwards curve with T = XY/Z
+#[derive(Clone, Copy, Debug)]
+struct ExtendedPoint {
+    x: FieldElement,
+    y: FieldElement,
+    z: FieldElement,
+    t: FieldElement
+}
+
+//the constants of edwards25519, -x^2 + y^2 = 1 + dx^2y^2, and its scalars modulo
+//the prime order L = 2^252 + 27742317777372353535851937790883648493 of the base point
+struct Edwards25519 {
+    field: PrimeField,
+    scalars: PrimeField,
+    d: FieldElement,
+    d2: FieldElement,
+    sqrt_m1: FieldElement,
+    base: ExtendedPoint
+}
+
+impl Edwards25519 {
+    fn new() -> Self {
+        let f = field();
+        let p = f.modulus().clone();
+        let d = f.mul(&f.neg(&f.from_u64(121665)), &f.inv(&f.from_u64(121666)));
+        let sqrt_m1 = f.pow(&f.from_u64(2), &((&p - 1u32) >> 2));
+        let order = BigUint::from_str_radix("1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed", 16).unwrap();
+        let mut curve = Edwards25519 {
+            scalars: PrimeField::new(&order),
+            d2: f.add(&d, &d),
+            d,
+            sqrt_m1,
+            base: ExtendedPoint{x: f.zero(), y: f.one(), z: f.one(), t: f.zero()},
+            field: f
+        };
+        //B has y = 4/5 and an even x
+        let y = curve.field.mul(&curve.field.from_u64(4), &curve.field.inv(&curve.field.from_u64(5)));
+        let x = curve.recover_x(&y, 0).unwrap();
+        curve.base = ExtendedPoint{x, y, z: curve.field.one(), t: curve.field.mul(&x, &y)};
+        curve
+    }
+
+    //built once and shared, since the constants cost a few field inversions
+    fn get() -> &'static Self {
+        static CURVE: OnceLock<Edwards25519> = OnceLock::new();
+        CURVE.get_or_init(Edwards25519::new)
+    }
+
+    fn identity(&self) -> ExtendedPoint {
+        ExtendedPoint{x: self.field.zero(), y: self.field.one(), z: self.field.one(), t: self.field.zero()}
+    }
+
+    //the unified addition of RFC 8032, 5.1.4; complete, so it also doubles
+    fn add(&self, p: &ExtendedPoint, q: &ExtendedPoint) -> ExtendedPoint {
+        let f = &self.field;
+        let a = f.mul(&f.sub(&p.y, &p.x), &f.sub(&q.y, &q.x));
+        let b = f.mul(&f.add(&p.y, &p.x), &f.add(&q.y, &q.x));
+        let c = f.mul(&f.mul(&p.t, &self.d2), &q.t);
+        let d = f.mul(&p.z, &f.add(&q.z, &q.z));
+        let e = f.sub(&b, &a);
+        let ff = f.sub(&d, &c);
+        let g = f.add(&d, &c);
+        let h = f.add(&b, &a);
+        ExtendedPoint{x: f.mul(&e, &ff), y: f.mul(&g, &h), t: f.mul(&e, &h), z: f.mul(&ff, &g)}
+    }
+
+    //[k]P for a 256-bit little-endian k, with the same masked ladder as x25519
+    fn multiply(&self, k: &[u8; 32], p: &ExtendedPoint) -> ExtendedPoint {
+        let mut r0 = self.identity();
+        let mut r1 = *p;
+        for i in (0..256).rev() {
+            let bit = ((k[i / 8] >> (i % 8)) & 1) as u64;
+            for (a, b) in [(&mut r0.x, &mut r1.x), (&mut r0.y, &mut r1.y), (&mut r0.z, &mut r1.z), (&mut r0.t, &mut r1.t)] {
+                conditional_swap(a, b, bit);
+            }
+            r1 = self.add(&r0, &r1);
+            r0 = self.add(&r0, &r0);
+            for (a, b) in [(&mut r0.x, &mut r1.x), (&mut r0.y, &mut r1.y), (&mut r0.z, &mut r1.z), (&mut r0.t, &mut r1.t)] {
+                conditional_swap(a, b, bit);
+            }
+        }
+        r0
+    }
+
+    fn equal(&self, p: &ExtendedPoint, q: &ExtendedPoint) -> bool {
+        let f = &self.field;
+        f.mul(&p.x, &q.z) == f.mul(&q.x, &p.z) && f.mul(&p.y, &q.z) == f.mul(&q.y, &p.z)
+    }
+
+    //y in little-endian with the low bit of x in the top bit
+    fn encode(&self, p: &ExtendedPoint) -> [u8; 32] {
+        let f = &self.field;
+        let z_inv = f.inv(&p.z);
+        let x = f.to_le_bytes(&f.mul(&p.x, &z_inv));
+        let mut out = f.to_le_bytes(&f.mul(&p.y, &z_inv));
+        out[31] |= (x[0] & 1) << 7;
+        out
+    }
+
+    //x from y: x^2 = (y^2 - 1) / (dy^2 + 1), the root with the given low bit (RFC 8032, 5.1.3)
+    fn recover_x(&self, y: &FieldElement, sign: u8) -> Option<FieldElement> {
+        let f = &self.field;
+        let y2 = f.square(y);
+        let u = f.sub(&y2, &f.one());
+        let v = f.add(&f.mul(&self.d, &y2), &f.one());
+        let v3 = f.mul(&f.square(&v), &v);
+        let v7 = f.mul(&f.square(&v3), &v);
+        let exponent = (f.modulus() - 5u32) >> 3;
+        let mut x = f.mul(&f.mul(&u, &v3), &f.pow(&f.mul(&u, &v7), &exponent));
+        let vx2 = f.mul(&v, &f.square(&x));
+        if vx2 == f.neg(&u) {
+            x = f.mul(&x, &self.sqrt_m1);
+        } else if vx2 != u {
+            return None;
+        }
+        if x.is_zero() && sign == 1 {
+            return None;
+        }
+        if f.to_le_bytes(&x)[0] & 1 != sign {
+            x = f.neg(&x);
+        }
+        Some(x)
+    }
+
+    fn decode(&self, bytes: &[u8; 32]) -> Option<ExtendedPoint> {
+        let f = &self.field;
+        let mut y_bytes = *bytes;
+        let sign = y_bytes[31] >> 7;
+        y_bytes[31] &= 127;
+        if &BigUint::from_bytes_le(&y_bytes) >= f.modulus() {
+            return None;
+        }
+        let y = f.from_le_bytes(&y_bytes);
+        let x = self.recover_x(&y, sign)?;
+        Some(ExtendedPoint{x, y, z: f.one(), t: f.mul(&x, &y)})
+    }
+
+    //SHA-512 of the parts, reduced modulo L
+    fn hash_to_scalar(&self, parts: &[&[u8]]) -> FieldElement {
+        let mut hasher = Sha512::default();
+        for part in parts {
+            hasher.update(part);
+        }
+        let mut wide = [0u8; 64];
+        wide.copy_from_slice(&hasher.finalize());
+        self.scalars.from_wide_le_bytes(&wide)
+    }
+}
+
+/**
+ * An Ed25519 private key (RFC 8032): a 32-byte seed. Its SHA-512 hash gives the secret
+ * scalar s (the first half, clamped like an X25519 key) and a prefix (the second half)
+ * that makes the signing nonces deterministic.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ed25519PrivateKey {
+    seed: [u8; 32]
+}
+
+/**
+ * An Ed25519 public key, the encoding of the point A = [s]B
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ed25519PublicKey {
+    bytes: [u8; 32]
+}
+
+impl Ed25519PrivateKey {
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Ed25519PrivateKey{seed}
+    }
+
+    pub fn generate<R: RngCore + CryptoRng + ?Sized>(rng: &mut R) -> Self {
+        let mut seed = [0u8; 32];
+        rng.fill_bytes(&mut seed);
+        Ed25519PrivateKey{seed}
+    }
+
+    pub fn seed(&self) -> &[u8; 32] {
+        &self.seed
+    }
+
+    //the clamped secret scalar and the nonce prefix
+    fn expand(&self) -> ([u8; 32], [u8; 32]) {
+        let hash = Sha512::digest(&self.seed);
+        let mut scalar = [0u8; 32];
+        let mut prefix = [0u8; 32];
+        scalar.copy_from_slice(&hash[..32]);
+        prefix.copy_from_slice(&hash[32..]);
+        scalar[0] &= 248;
+        scalar[31] &= 127;
+        scalar[31] |= 64;
+        (scalar, prefix)
+    }
+
+    pub fn public_key(&self) -> Ed25519PublicKey {
+        let curve = Edwards25519::get();
+        let (scalar, _) = self.expand();
+        Ed25519PublicKey{bytes: curve.encode(&curve.multiply(&scalar, &curve.base))}
+    }
+
+    /**
+     * The 64-byte signature R || S: with r = SHA-512(prefix || message) mod L,
+     * R = [r]B and S = r + SHA-512(R || A || message) * s mod L. The nonce r comes from
+     * the key and the message, so signing needs no randomness.
+     */
+    pub fn sign(&self, message: &[u8]) -> [u8; 64] {
+        let curve = Edwards25519::get();
+        let scalars = &curve.scalars;
+        let (scalar, prefix) = self.expand();
+        let public_key = curve.encode(&curve.multiply(&scalar, &curve.base));
+
+        let r = curve.hash_to_scalar(&[&prefix, message]);
+        let big_r = curve.encode(&curve.multiply(&scalars.to_le_bytes(&r), &curve.base));
+        let k = curve.hash_to_scalar(&[&big_r, &public_key, message]);
+        let s = scalars.add(&r, &scalars.mul(&k, &scalars.from_le_bytes(&scalar)));
+
+        let mut signature = [0u8; 64];
+        signature[..32].copy_from_slice(&big_r);
+        signature[32..].copy_from_slice(&scalars.to_le_bytes(&s));
+        signature
+    }
+}
+
+impl Ed25519PublicKey {
+    /**
+     * The key from its encoding, None if that isn't a point of the curve
+     */
+    pub fn from_bytes(bytes: &[u8; 32]) -> Option<Self> {
+        Edwards25519::get().decode(bytes)?;
+        Some(Ed25519PublicKey{bytes: *bytes})
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.bytes
+    }
+
+    /**
+     * Checks the group equation [8][S]B = [8]R + [8][k]A with k = SHA-512(R || A || message)
+     * mod L, after checking that R decodes and S < L. Multiplying by the cofactor 8 makes
+     * the answer the same as that of batch verification.
+     */
+    pub fn verify(&self, message: &[u8], signature: &[u8; 64]) -> bool {
+        let curve = Edwards25519::get();
+        let mut r_bytes = [0u8; 32];
+        let mut s_bytes = [0u8; 32];
+        r_bytes.copy_from_slice(&signature[..32]);
+        s_bytes.copy_from_slice(&signature[32..]);
+        if &BigUint::from_bytes_le(&s_bytes) >= curve.scalars.modulus() {
+            return false;
+        }
+        let (a, r) = match (curve.decode(&self.bytes), curve.decode(&r_bytes)) {
+            (Some(a), Some(r)) => (a, r),
+            _ => return false
+        };
+        let k = curve.hash_to_scalar(&[&r_bytes, &self.bytes, message]);
+
+        let mut left = curve.multiply(&s_bytes, &curve.base);
+        let mut right = curve.add(&r, &curve.multiply(&curve.scalars.to_le_bytes(&k), &a));
+        for _i in 0..3 {
+            left = curve.add(&left, &left);
+            right = curve.add(&right, &right);
+        }
+        curve.equal(&left, &right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes<const N: usize>(hex: &str) -> [u8; N] {
+        let mut out = [0u8; N];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    //RFC 7748, 5.2
+    #[test]
+    fn x25519_vectors() {
+        let vectors = [
+            (
+                "a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4",
+                "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c",
+                "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552"
+            ),
+            (
+                "4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d",
+                "e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493",
+                "95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957"
+            )
+        ];
+        for (scalar, u, expected) in vectors.iter() {
+            assert_eq!(x25519(&bytes(scalar), &bytes(u)), bytes::<32>(expected));
+        }
+    }
+
+    //RFC 7748, 6.1
+    #[test]
+    fn x25519_alice_and_bob() {
+        let alice = bytes("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a");
+        let bob = bytes("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb");
+        let alice_public = x25519_public_key(&alice);
+        let bob_public = x25519_public_key(&bob);
+        assert_eq!(alice_public, bytes::<32>("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"));
+        assert_eq!(bob_public, bytes::<32>("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"));
+        let shared = bytes::<32>("4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742");
+        assert_eq!(x25519_agree(&alice, &bob_public), Some(shared));
+        assert_eq!(x25519_agree(&bob, &alice_public), Some(shared));
+        //u = 0 has small order, so the all-zero result is refused
+        assert_eq!(x25519_agree(&alice, &[0u8; 32]), None);
+    }
+
+    //RFC 8032, 7.1, TEST 1
+    #[test]
+    fn ed25519_test_1() {
+        let key = Ed25519PrivateKey::from_seed(bytes("9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60"));
+        let public_key = key.public_key();
+        assert_eq!(public_key.to_bytes(), bytes::<32>("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"));
+        let signature = key.sign(b"");
+        assert_eq!(signature, bytes::<64>(
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+        ));
+        assert!(public_key.verify(b"", &signature));
+        assert!(!public_key.verify(b"x", &signature));
+        assert_eq!(Ed25519PublicKey::from_bytes(&public_key.to_bytes()), Some(public_key));
+    }
+}
@@ -0,0 +1,487 @@
+pub mod connectivity {
+    use crate::graph::*;
+    use std::collections::HashMap;
+
+    /**
+     * Connected components of an undirected graph that only grows.
+     *
+     * This is a union-find (disjoint set) structure keyed by vertex id. Every component
+     * is a tree whose root represents it; adding an edge links the roots of its two endpoints.
+     * With union by size and path compression, each operation runs in
+     * amortized O(α(n)) time, where α is the inverse Ackermann function.
+     *
+     * Vertices can be added at any time, and edges to unknown ids add those vertices.
+     * Edges can't be removed; use OfflineConnectivity when deletions are needed.
+     */
+    #[derive(Clone, Debug, Default)]
+    pub struct IncrementalConnectivity {
+        index: HashMap<i32, usize>,
+        parent: Vec<usize>,
+        size: Vec<usize>,
+        components: usize
+    }
+
+    impl IncrementalConnectivity {
+        pub fn new() -> Self {
+            IncrementalConnectivity::default()
+        }
+
+        /**
+         * Starts from the components of a graph, treating its edges as undirected
+         */
+        pub fn from_graph(graph: &Graph) -> Self {
+            let mut connectivity = IncrementalConnectivity::new();
+            for v in graph.vertices.iter() {
+                connectivity.add_node(v.id);
+            }
+            for e in graph.edges.iter() {
+                connectivity.add_edge(e.from.id, e.to.id);
+            }
+            connectivity
+        }
+
+        /**
+         * Adds a vertex as a component of its own. Returns false if it was already present.
+         */
+        pub fn add_node(&mut self, id: i32) -> bool {
+            if self.index.contains_key(&id) {
+                return false;
+            }
+            self.index.insert(id, self.parent.len());
+            self.parent.push(self.parent.len());
+            self.size.push(1);
+            self.components += 1;
+            true
+        }
+
+        /**
+         * Adds an edge between two vertices.
+         * Returns true if it joined two components that were separate before.
+         */
+        pub fn add_edge(&mut self, u: i32, v: i32) -> bool {
+            self.add_node(u);
+            self.add_node(v);
+            let root_u = self.find(self.index[&u]);
+            let root_v = self.find(self.index[&v]);
+            if root_u == root_v {
+                return false;
+            }
+
+            //hang the smaller tree under the larger one
+            let (big, small) = if self.size[root_u] >= self.size[root_v] {(root_u, root_v)} else {(root_v, root_u)};
+            self.parent[small] = big;
+            self.size[big] += self.size[small];
+            self.components -= 1;
+            true
+        }
+
+        /**
+         * Returns true if there is a path between the two vertices
+         */
+        pub fn connected(&mut self, u: i32, v: i32) -> bool {
+            match (self.index.get(&u).cloned(), self.index.get(&v).cloned()) {
+                (Some(a), Some(b)) => self.find(a) == self.find(b),
+                _ => false
+            }
+        }
+
+        /**
+         * Returns the number of vertices in the component containing id
+         */
+        pub fn component_size(&mut self, id: i32) -> usize {
+            match self.index.get(&id).cloned() {
+                Some(i) => {
+                    let root = self.find(i);
+                    self.size[root]
+                },
+                None => 0
+            }
+        }
+
+        pub fn num_components(&self) -> usize {
+            self.components
+        }
+
+        //root of the tree holding i, pointing every vertex on the way straight at it
+        fn find(&mut self, i: usize) -> usize {
+            let mut root = i;
+            while self.parent[root] != root {
+                root = self.parent[root];
+            }
+            let mut current = i;
+            while self.parent[current] != root {
+                let next = self.parent[current];
+                self.parent[current] = root;
+                current = next;
+            }
+            root
+        }
+    }
+
+    /**
+     * One update or query in a stream of topology changes
+     */
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum ConnectivityOp {
+        AddNode(i32),
+        RemoveNode(i32),            //also removes every edge touching the vertex
+        AddEdge(i32, i32),          //adds missing endpoints
+        RemoveEdge(i32, i32),
+        Connected(i32, i32),
+        CountComponents
+    }
+
+    /**
+     * Answer to a query, in the order the queries appear in the stream
+     */
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum ConnectivityAnswer {
+        Connected(bool),
+        Components(usize)
+    }
+
+    /**
+     * Connectivity under both edge insertions and deletions, answered offline:
+     * the whole stream of operations is recorded first and then solved at once.
+     *
+     * Every edge is present during one or more time intervals [added, removed). The intervals
+     * are stored in a segment tree over time, so each lands in O(log T) tree nodes.
+     * A depth-first walk of the tree unions the edges of each node on entry and rolls the
+     * unions back on exit, so at each leaf (a point in time) the union-find holds exactly
+     * the edges present then. Rollback requires a union-find without path compression,
+     * which is kept shallow by union by size.
+     *
+     * Runtime: O(T log T log n) for T operations
+     */
+    #[derive(Clone, Debug, Default)]
+    pub struct OfflineConnectivity {
+        ops: Vec<ConnectivityOp>
+    }
+
+    //union-find whose unions can be undone in reverse order
+    struct RollbackUnionFind {
+        parent: Vec<usize>,
+        size: Vec<usize>,
+        history: Vec<Option<(usize, usize)>>,        //(child root, parent root) of each union
+        merges: usize
+    }
+
+    impl RollbackUnionFind {
+        fn new(n: usize) -> Self {
+            RollbackUnionFind {
+                parent: (0..n).collect(),
+                size: vec![1; n],
+                history: Vec::new(),
+                merges: 0
+            }
+        }
+
+        fn find(&self, mut i: usize) -> usize {
+            while self.parent[i] != i {
+                i = self.parent[i];
+            }
+            i
+        }
+
+        fn union(&mut self, u: usize, v: usize) {
+            let (mut a, mut b) = (self.find(u), self.find(v));
+            if a == b {
+                self.history.push(None);
+                return;
+            }
+            if self.size[a] < self.size[b] {
+                std::mem::swap(&mut a, &mut b);
+            }
+            self.parent[b] = a;
+            self.size[a] += self.size[b];
+            self.merges += 1;
+            self.history.push(Some((b, a)));
+        }
+
+        fn rollback(&mut self) {
+            if let Some(Some((child, root))) = self.history.pop() {
+                self.parent[child] = child;
+                self.size[root] -= self.size[child];
+                self.merges -= 1;
+            }
+        }
+    }
+
+    impl OfflineConnectivity {
+        pub fn new() -> Self {
+            OfflineConnectivity::default()
+        }
+
+        /**
+         * Starts the stream with the vertices and (undirected) edges of a graph
+         */
+        pub fn from_graph(graph: &Graph) -> Self {
+            let mut offline = OfflineConnectivity::new();
+            for v in graph.vertices.iter() {
+                offline.push(ConnectivityOp::AddNode(v.id));
+            }
+            for e in graph.edges.iter() {
+                offline.push(ConnectivityOp::AddEdge(e.from.id, e.to.id));
+            }
+            offline
+        }
+
+        pub fn push(&mut self, op: ConnectivityOp) {
+            self.ops.push(op);
+        }
+
+        /**
+         * Replays the stream and returns the answers to its queries in order
+         */
+        pub fn solve(&self) -> Vec<ConnectivityAnswer> {
+            let t = self.ops.len();
+            let mut index: HashMap<i32, usize> = HashMap::new();
+            let mut alive: Vec<bool> = Vec::new();
+            let mut num_alive = 0;
+            let mut open_edges: HashMap<(usize, usize), Vec<usize>> = HashMap::new();   //start times of present edges
+            let mut intervals: Vec<(usize, usize, usize, usize)> = Vec::new();           //(start, end, u, v)
+            let mut queries: Vec<Option<Query>> = vec![None; t];
+
+            //turns an id into a union-find position, adding the vertex if needed
+            let position = |id: i32, index: &mut HashMap<i32, usize>, alive: &mut Vec<bool>, num_alive: &mut usize| -> usize {
+                let next = index.len();
+                let i = *index.entry(id).or_insert(next);
+                if i == alive.len() {
+                    alive.push(false);
+                }
+                if !alive[i] {
+                    alive[i] = true;
+                    *num_alive += 1;
+                }
+                i
+            };
+
+            //first pass: turn the stream into edge lifetimes and queries
+            for (time, op) in self.ops.iter().enumerate() {
+                match *op {
+                    ConnectivityOp::AddNode(id) => {
+                        position(id, &mut index, &mut alive, &mut num_alive);
+                    },
+                    ConnectivityOp::RemoveNode(id) => {
+                        if let Some(&i) = index.get(&id) {
+                            if alive[i] {
+                                alive[i] = false;
+                                num_alive -= 1;
+                            }
+                            let touching: Vec<(usize, usize)> = open_edges.keys().cloned().filter(|&(a, b)| {a == i || b == i}).collect();
+                            for key in touching {
+                                for start in open_edges.remove(&key).unwrap() {
+                                    intervals.push((start, time, key.0, key.1));
+                                }
+                            }
+                        }
+                    },
+                    ConnectivityOp::AddEdge(u, v) => {
+                        let a = position(u, &mut index, &mut alive, &mut num_alive);
+                        let b = position(v, &mut index, &mut alive, &mut num_alive);
+                        open_edges.entry((a.min(b), a.max(b))).or_default().push(time);
+                    },
+                    ConnectivityOp::RemoveEdge(u, v) => {
+                        if let (Some(&a), Some(&b)) = (index.get(&u), index.get(&v)) {
+                            let key = (a.min(b), a.max(b));
+                            if let Some(starts) = open_edges.get_mut(&key) {
+                                let start = starts.pop().unwrap();
+                                intervals.push((start, time, key.0, key.1));
+                                if starts.is_empty() {
+                                    open_edges.remove(&key);
+                                }
+                            }
+                        }
+                    },
+                    ConnectivityOp::Connected(u, v) => {
+                        let present = |id: i32| {index.get(&id).cloned().filter(|&i| {alive[i]})};
+                        queries[time] = Some(Query::Connected(present(u), present(v)));
+                    },
+                    ConnectivityOp::CountComponents => {
+                        queries[time] = Some(Query::Components(num_alive));
+                    }
+                }
+            }
+            for (key, starts) in open_edges {
+                for start in starts {
+                    intervals.push((start, t, key.0, key.1));
+                }
+            }
+
+            //place every lifetime in the segment tree over times 0..t
+            let mut tree: Vec<Vec<(usize, usize)>> = vec![Vec::new(); 4 * t.max(1)];
+            for &(start, end, u, v) in intervals.iter() {
+                if start < end {
+                    insert_interval(&mut tree, 1, 0, t, start, end, (u, v));
+                }
+            }
+
+            //second pass: walk the tree, answering each query at its leaf
+            let mut uf = RollbackUnionFind::new(index.len());
+            let mut answers: Vec<Option<ConnectivityAnswer>> = vec![None; t];
+            if t > 0 {
+                walk_tree(&tree, 1, 0, t, &mut uf, &queries, &mut answers);
+            }
+
+            answers.into_iter().flatten().collect()
+        }
+    }
+
+    //a query along with what it needs to know about the time it was asked
+    #[derive(Clone, Copy)]
+    enum Query {
+        Connected(Option<usize>, Option<usize>),        //positions of the two vertices, if present
+        Components(usize)                               //number of vertices present
+    }
+
+    //adds an edge to every tree node whose time range lies inside [start, end)
+    fn insert_interval(tree: &mut Vec<Vec<(usize, usize)>>, node: usize, lo: usize, hi: usize, start: usize, end: usize, edge: (usize, usize)) {
+        if end <= lo || hi <= start {
+            return;
+        }
+        if start <= lo && hi <= end {
+            tree[node].push(edge);
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        insert_interval(tree, 2 * node, lo, mid, start, end, edge);
+        insert_interval(tree, 2 * node + 1, mid, hi, start, end, edge);
+    }
+
+    fn walk_tree(
+        tree: &[Vec<(usize, usize)>],
+        node: usize,
+        lo: usize,
+        hi: usize,
+        uf: &mut RollbackUnionFind,
+        queries: &[Option<Query>],
+        answers: &mut Vec<Option<ConnectivityAnswer>>
+    ) {
+        for &(u, v) in tree[node].iter() {
+            uf.union(u, v);
+        }
+
+        if hi - lo == 1 {
+            answers[lo] = match queries[lo] {
+                Some(Query::Connected(Some(a), Some(b))) => Some(ConnectivityAnswer::Connected(uf.find(a) == uf.find(b))),
+                Some(Query::Connected(_, _)) => Some(ConnectivityAnswer::Connected(false)),
+                Some(Query::Components(present)) => Some(ConnectivityAnswer::Components(present - uf.merges)),
+                None => None
+            };
+        } else {
+            let mid = (lo + hi) / 2;
+            walk_tree(tree, 2 * node, lo, mid, uf, queries, answers);
+            walk_tree(tree, 2 * node + 1, mid, hi, uf, queries, answers);
+        }
+
+        for _edge in tree[node].iter() {
+            uf.rollback();
+        }
+    }
+
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use rand::{Rng, SeedableRng};
+        use rand::rngs::StdRng;
+        use std::collections::HashSet;
+
+        #[test]
+        fn offline_connectivity_across_a_split() {
+            //the path 1 - 2 - 3 - 4, cut in the middle and joined again elsewhere
+            let mut offline = OfflineConnectivity::new();
+            for &(u, v) in [(1, 2), (2, 3), (3, 4)].iter() {
+                offline.push(ConnectivityOp::AddEdge(u, v));
+            }
+            offline.push(ConnectivityOp::Connected(1, 4));
+            offline.push(ConnectivityOp::CountComponents);
+            offline.push(ConnectivityOp::RemoveEdge(2, 3));
+            offline.push(ConnectivityOp::Connected(1, 4));
+            offline.push(ConnectivityOp::Connected(1, 2));
+            offline.push(ConnectivityOp::CountComponents);
+            offline.push(ConnectivityOp::AddEdge(4, 1));
+            offline.push(ConnectivityOp::Connected(2, 3));
+            offline.push(ConnectivityOp::RemoveNode(1));
+            offline.push(ConnectivityOp::Connected(2, 3));
+            offline.push(ConnectivityOp::Connected(1, 1));
+            offline.push(ConnectivityOp::CountComponents);
+            assert_eq!(offline.solve(), vec![
+                ConnectivityAnswer::Connected(true),
+                ConnectivityAnswer::Components(1),
+                ConnectivityAnswer::Connected(false),
+                ConnectivityAnswer::Connected(true),
+                ConnectivityAnswer::Components(2),
+                ConnectivityAnswer::Connected(true),
+                ConnectivityAnswer::Connected(false),
+                ConnectivityAnswer::Connected(false),
+                ConnectivityAnswer::Components(2)
+            ]);
+            assert!(OfflineConnectivity::new().solve().is_empty());
+        }
+
+        #[test]
+        fn offline_connectivity_matches_rebuilding() {
+            let mut rng = StdRng::seed_from_u64(29);
+            for _stream in 0..50 {
+                let mut offline = OfflineConnectivity::new();
+                let mut nodes: HashSet<i32> = HashSet::new();
+                let mut edges: Vec<(i32, i32)> = Vec::new();
+                let mut expected = Vec::new();
+                for _step in 0..60 {
+                    let (u, v) = (rng.gen_range(1..9), rng.gen_range(1..9));
+                    let op = match rng.gen_range(0..7) {
+                        0 => ConnectivityOp::AddNode(u),
+                        1 => ConnectivityOp::RemoveNode(u),
+                        2 | 3 => ConnectivityOp::AddEdge(u, v),
+                        4 => ConnectivityOp::RemoveEdge(u, v),
+                        5 => ConnectivityOp::Connected(u, v),
+                        _ => ConnectivityOp::CountComponents
+                    };
+                    offline.push(op);
+                    match op {
+                        ConnectivityOp::AddNode(u) => {nodes.insert(u);},
+                        ConnectivityOp::RemoveNode(u) => {
+                            nodes.remove(&u);
+                            edges.retain(|&(a, b)| {a != u && b != u});
+                        },
+                        ConnectivityOp::AddEdge(u, v) => {
+                            nodes.insert(u);
+                            nodes.insert(v);
+                            edges.push((u, v));
+                        },
+                        ConnectivityOp::RemoveEdge(u, v) => {
+                            if let Some(i) = edges.iter().rposition(|&e| {e == (u, v) || e == (v, u)}) {
+                                edges.remove(i);
+                            }
+                        },
+                        //the components from scratch with the edges present now
+                        query => {
+                            let mut current = IncrementalConnectivity::new();
+                            nodes.iter().for_each(|&id| {current.add_node(id);});
+                            edges.iter().for_each(|&(a, b)| {current.add_edge(a, b);});
+                            expected.push(match query {
+                                ConnectivityOp::Connected(u, v) => ConnectivityAnswer::Connected(current.connected(u, v)),
+                                _ => ConnectivityAnswer::Components(current.num_components())
+                            });
+                        }
+                    }
+                }
+                assert_eq!(offline.solve(), expected);
+            }
+        }
+
+        #[test]
+        fn incremental_components() {
+            let mut connectivity = IncrementalConnectivity::new();
+            assert!(connectivity.add_edge(1, 2));
+            assert!(!connectivity.add_edge(2, 1));
+            assert!(connectivity.add_node(3));
+            assert!(!connectivity.add_node(3));
+            assert_eq!((connectivity.num_components(), connectivity.component_size(1), connectivity.component_size(9)), (2, 2, 0));
+            assert!(connectivity.add_edge(3, 1));
+            assert!(connectivity.connected(2, 3) && !connectivity.connected(2, 9));
+            assert_eq!(connectivity.component_size(3), 3);
+        }
+    }
+}
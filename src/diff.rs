@@ -0,0 +1,277 @@
+pub mod diff_algos {
+    use std::ops::Range;
+
+    /**
+     * What a hunk of a diff does
+     */
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum DiffTag {
+        Equal,
+        Delete,
+        Insert
+    }
+
+    /**
+     * A run of elems that are kept, deleted from old or inserted from new.
+     * Both ranges are given: a deletion has an empty new range at the position it happens,
+     * and an insertion an empty old range.
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct DiffHunk {
+        pub tag: DiffTag,
+        pub old: Range<usize>,
+        pub new: Range<usize>
+    }
+
+    /**
+     * Myers' diff: the shortest edit script turning old into new, as hunks in order.
+     *
+     * The edit graph has a point (x, y) for every x elems of old and y of new done; going
+     * right deletes old[x], going down inserts new[y] and a diagonal keeps an elem they share.
+     * V[k] is the furthest x reached on diagonal k = x - y with d edits. Each V[k] for d + 1
+     * edits extends the better of V[k - 1] and V[k + 1] by one edit, then follows the
+     * diagonal ("snake") as far as the elems match. The first d to reach (n, m) is the
+     * shortest edit distance D, and the path is traced back through the saved V's.
+     *
+     * Takes O((n + m) D) time and O(D^2) space, so it is fast on similar inputs.
+     * Deletions come before insertions where both are possible, as diff prints them.
+     */
+    pub fn myers_diff<T: Eq>(old: &[T], new: &[T]) -> Vec<DiffHunk> {
+        let (n, m) = (old.len() as isize, new.len() as isize);
+        let max = (n + m) as usize;
+        let offset = max as isize + 1;
+        let mut v = vec![0isize; 2 * max + 3];
+        let mut trace: Vec<Vec<isize>> = Vec::new();   //V[-d..=d] before step d
+
+        'search: for d in 0..=max as isize {
+            trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+            for k in (-d..=d).step_by(2) {
+                let index = (offset + k) as usize;
+                let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                    v[index + 1]
+                } else {
+                    v[index - 1] + 1
+                };
+                let mut y = x - k;
+                while x < n && y < m && old[x as usize] == new[y as usize] {
+                    x += 1;
+                    y += 1;
+                }
+                v[index] = x;
+                if x >= n && y >= m {
+                    break 'search;
+                }
+            }
+        }
+
+        //walk back from (n, m), one edit and its snake per step
+        let mut steps: Vec<(DiffTag, usize, usize)> = Vec::new();
+        let (mut x, mut y) = (n, m);
+        for (d, previous) in trace.iter().enumerate().rev() {
+            let d = d as isize;
+            let k = x - y;
+            let (previous_x, previous_y) = if d == 0 {
+                (0, 0)
+            } else {
+                let at = |k: isize| {previous[(k + d) as usize]};
+                let previous_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {k + 1} else {k - 1};
+                (at(previous_k), at(previous_k) - previous_k)
+            };
+            while x > previous_x && y > previous_y {
+                x -= 1;
+                y -= 1;
+                steps.push((DiffTag::Equal, x as usize, y as usize));
+            }
+            if d > 0 {
+                if x == previous_x {
+                    steps.push((DiffTag::Insert, x as usize, previous_y as usize));
+                } else {
+                    steps.push((DiffTag::Delete, previous_x as usize, y as usize));
+                }
+            }
+            x = previous_x;
+            y = previous_y;
+        }
+
+        //merge the single steps into hunks
+        let mut hunks: Vec<DiffHunk> = Vec::new();
+        for (tag, x, y) in steps.into_iter().rev() {
+            let (old_end, new_end) = match tag {
+                DiffTag::Equal => (x + 1, y + 1),
+                DiffTag::Delete => (x + 1, y),
+                DiffTag::Insert => (x, y + 1)
+            };
+            match hunks.last_mut() {
+                Some(hunk) if hunk.tag == tag => {
+                    hunk.old.end = old_end;
+                    hunk.new.end = new_end;
+                },
+                _ => hunks.push(DiffHunk{tag, old: x..old_end, new: y..new_end})
+            }
+        }
+        hunks
+    }
+
+    /**
+     * Groups the hunks of a diff into the blocks a unified diff shows: each change with up
+     * to context elems kept around it, changes closer than 2 * context sharing a block.
+     */
+    pub fn group_hunks(hunks: &[DiffHunk], context: usize) -> Vec<Vec<DiffHunk>> {
+        let mut hunks = hunks.to_vec();
+        //trim the unchanged ends to the context
+        if let Some(first) = hunks.first_mut() {
+            if first.tag == DiffTag::Equal {
+                let cut = first.old.len().saturating_sub(context);
+                first.old.start += cut;
+                first.new.start += cut;
+            }
+        }
+        if let Some(last) = hunks.last_mut() {
+            if last.tag == DiffTag::Equal {
+                let cut = last.old.len().saturating_sub(context);
+                last.old.end -= cut;
+                last.new.end -= cut;
+            }
+        }
+
+        let mut groups = Vec::new();
+        let mut group = Vec::new();
+        for hunk in hunks {
+            if hunk.tag == DiffTag::Equal && hunk.old.len() > 2 * context {
+                //close the block with the start of this run and open the next with its end
+                group.push(DiffHunk {
+                    tag: DiffTag::Equal,
+                    old: hunk.old.start..hunk.old.start + context,
+                    new: hunk.new.start..hunk.new.start + context
+                });
+                groups.push(std::mem::take(&mut group));
+                group.push(DiffHunk {
+                    tag: DiffTag::Equal,
+                    old: hunk.old.end - context..hunk.old.end,
+                    new: hunk.new.end - context..hunk.new.end
+                });
+            } else {
+                group.push(hunk);
+            }
+        }
+        groups.push(group);
+        groups.retain(|group| {group.iter().any(|hunk| {hunk.tag != DiffTag::Equal})});
+        groups
+    }
+
+    //a range in a @@ line: 1-based start and length, the length left out when it is 1
+    fn unified_range(range: &Range<usize>) -> String {
+        match range.len() {
+            0 => format!("{},0", range.start),
+            1 => format!("{}", range.start + 1),
+            len => format!("{},{}", range.start + 1, len)
+        }
+    }
+
+    /**
+     * The line by line diff of two texts in the unified format of diff -u and patch,
+     * with context lines of context around each change and old_name and new_name in
+     * the header. A last line without a newline is marked as such.
+     * Returns an empty string if the texts are the same.
+     */
+    pub fn unified_diff(old: &str, new: &str, old_name: &str, new_name: &str, context: usize) -> String {
+        let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+        let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+        let groups = group_hunks(&myers_diff(&old_lines, &new_lines), context);
+        if groups.is_empty() {
+            return String::new();
+        }
+
+        let mut out = format!("--- {}\n+++ {}\n", old_name, new_name);
+        for group in groups.iter() {
+            let first = group.first().unwrap();
+            let last = group.last().unwrap();
+            out += &format!("@@ -{} +{} @@\n", unified_range(&(first.old.start..last.old.end)), unified_range(&(first.new.start..last.new.end)));
+            for hunk in group.iter() {
+                let (prefix, lines) = match hunk.tag {
+                    DiffTag::Equal => (' ', &old_lines[hunk.old.clone()]),
+                    DiffTag::Delete => ('-', &old_lines[hunk.old.clone()]),
+                    DiffTag::Insert => ('+', &new_lines[hunk.new.clone()])
+                };
+                for line in lines.iter() {
+                    out.push(prefix);
+                    out += line;
+                    if !line.ends_with('\n') {
+                        out += "\n\\ No newline at end of file\n";
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn text(lines: &[&str]) -> String {
+            lines.iter().map(|line| {format!("{}\n", line)}).collect()
+        }
+
+        #[test]
+        fn unified_diff_of_an_insertion() {
+            let diff = unified_diff("a\nb\nc\n", "a\nb\nx\ny\nc\n", "old", "new", 3);
+            assert_eq!(diff, "--- old\n+++ new\n@@ -1,3 +1,5 @@\n a\n b\n+x\n+y\n c\n");
+            assert_eq!(unified_diff("", "a\n", "old", "new", 3), "--- old\n+++ new\n@@ -0,0 +1 @@\n+a\n");
+        }
+
+        #[test]
+        fn unified_diff_of_a_deletion() {
+            let diff = unified_diff("a\nb\nc\nd\n", "a\nd\n", "old", "new", 3);
+            assert_eq!(diff, "--- old\n+++ new\n@@ -1,4 +1,2 @@\n a\n-b\n-c\n d\n");
+            assert_eq!(unified_diff("a\nb\n", "a\nb\n", "old", "new", 3), "");
+        }
+
+        #[test]
+        fn unified_diff_merges_close_changes() {
+            let old: Vec<String> = (1..=20).map(|i| {i.to_string()}).collect();
+            let mut new = old.clone();
+            new[4] = "five".to_string();
+            new[10] = "eleven".to_string();
+            new[18] = "nineteen".to_string();
+            let old: Vec<&str> = old.iter().map(|line| {line.as_str()}).collect();
+            let new: Vec<&str> = new.iter().map(|line| {line.as_str()}).collect();
+
+            //5 lines apart share a block, 7 apart don't
+            let expected = concat!(
+                "--- old\n+++ new\n",
+                "@@ -2,13 +2,13 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n 9\n 10\n-11\n+eleven\n 12\n 13\n 14\n",
+                "@@ -16,5 +16,5 @@\n 16\n 17\n 18\n-19\n+nineteen\n 20\n"
+            );
+            assert_eq!(unified_diff(&text(&old), &text(&new), "old", "new", 3), expected);
+        }
+
+        #[test]
+        fn unified_diff_marks_a_missing_newline() {
+            let diff = unified_diff("a\nb", "a\nc", "old", "new", 3);
+            assert_eq!(diff, "--- old\n+++ new\n@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+c\n\\ No newline at end of file\n");
+            let diff = unified_diff("a\nb\n", "a\nb", "old", "new", 3);
+            assert_eq!(diff, "--- old\n+++ new\n@@ -1,2 +1,2 @@\n a\n-b\n+b\n\\ No newline at end of file\n");
+        }
+
+        #[test]
+        fn myers_diff_hunks() {
+            let hunks = myers_diff(b"abcabba", b"cbabac");
+            //the hunks cover both sequences in order
+            let (mut x, mut y) = (0, 0);
+            let mut distance = 0;
+            for hunk in hunks.iter() {
+                assert_eq!((hunk.old.start, hunk.new.start), (x, y));
+                match hunk.tag {
+                    DiffTag::Equal => assert_eq!(b"abcabba"[hunk.old.clone()], b"cbabac"[hunk.new.clone()]),
+                    DiffTag::Delete => distance += hunk.old.len(),
+                    DiffTag::Insert => distance += hunk.new.len()
+                }
+                x = hunk.old.end;
+                y = hunk.new.end;
+            }
+            assert_eq!((x, y, distance), (7, 6, 5));
+            assert!(myers_diff::<u8>(&[], &[]).is_empty());
+        }
+    }
+}
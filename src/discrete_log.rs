@@ -0,0 +1,504 @@
+extern crate rand;
+extern crate num;
+use rand::{CryptoRng, RngCore};
+use num::{BigUint, Num, One, ToPrimitive, Zero};
+use crate::modular::modular_arith::*;
+use crate::primes::prime_sieves::primes_up_to;
+use crate::randomized::randomized_algos::*;
+use crate::hashing::hash_algos::*;
+use crate::rsa::generate_rand_prime;
+
+//the safe primes of the RFC 3526 MODP groups, p = 2^n - 2^(n-64) - 1 + 2^64 * (floor(2^(n-130) * pi) + k)
+const MODP_1536: &str = concat!(
+    "FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74",
+    "020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437",
+    "4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED",
+    "EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05",
+    "98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB",
+    "9ED529077096966D670C354E4ABC9804F1746C08CA237327FFFFFFFFFFFFFFFF"
+);
+
+const MODP_2048: &str = concat!(
+    "FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74",
+    "020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437",
+    "4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED",
+    "EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05",
+    "98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB",
+    "9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B",
+    "E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718",
+    "3995497CEA956AE515D2261898FA051015728E5A8AACAA68FFFFFFFFFFFFFFFF"
+);
+
+const MODP_3072: &str = concat!(
+    "FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74",
+    "020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437",
+    "4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED",
+    "EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05",
+    "98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB",
+    "9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B",
+    "E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718",
+    "3995497CEA956AE515D2261898FA051015728E5A8AAAC42DAD33170D04507A33",
+    "A85521ABDF1CBA64ECFB850458DBEF0A8AEA71575D060C7DB3970F85A6E1E4C7",
+    "ABF5AE8CDB0933D71E8C94E04A25619DCEE3D2261AD2EE6BF12FFA06D98A0864",
+    "D87602733EC86A64521F2B18177B200CBBE117577A615D6C770988C0BAD946E2",
+    "08E24FA074E5AB3143DB5BFCE0FD108E4B82D120A93AD2CAFFFFFFFFFFFFFFFF"
+);
+
+const MODP_4096: &str = concat!(
+    "FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74",
+    "020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437",
+    "4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED",
+    "EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05",
+    "98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB",
+    "9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B",
+    "E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718",
+    "3995497CEA956AE515D2261898FA051015728E5A8AAAC42DAD33170D04507A33",
+    "A85521ABDF1CBA64ECFB850458DBEF0A8AEA71575D060C7DB3970F85A6E1E4C7",
+    "ABF5AE8CDB0933D71E8C94E04A25619DCEE3D2261AD2EE6BF12FFA06D98A0864",
+    "D87602733EC86A64521F2B18177B200CBBE117577A615D6C770988C0BAD946E2",
+    "08E24FA074E5AB3143DB5BFCE0FD108E4B82D120A92108011A723C12A787E6D7",
+    "88719A10BDBA5B2699C327186AF4E23C1A946834B6150BDA2583E9CA2AD44CE8",
+    "DBBBC2DB04DE8EF92E8EFC141FBECAA6287C59474E6BC05D99B2964FA090C3A2",
+    "233BA186515BE7ED1F612970CEE2D7AFB81BDD762170481CD0069127D5B05AA9",
+    "93B4EA988D8FDDC186FFB7DC90A6C08F4DF435C934063199FFFFFFFFFFFFFFFF"
+);
+
+/**
+ * A subgroup of prime order q of the multiplicative group modulo a prime p, generated by g.
+ * Diffie-Hellman, ElGamal and DSA all work in such a group, and are secure as long as
+ * discrete logarithms in it are hard: given g^x mod p, finding x.
+ *
+ * For a safe prime p = 2q + 1 the subgroup is the quadratic residues, half of the group.
+ * DSA uses a much smaller q dividing p - 1, which makes the exponents and signatures short.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModpGroup {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint
+}
+
+/**
+ * A random safe prime p = 2q + 1 (q prime too) of exactly the given number of bits.
+ *
+ * A candidate q is dropped if q or 2q + 1 has a factor below 1000, which is the case when
+ * q mod r is 0 or (r - 1) / 2 for a small prime r, and the survivors must pass the
+ * Baillie-PSW test and 4 Miller-Rabin rounds from rng for both q and p.
+ * Safe primes are rare, about one in (ln p)^2 numbers, so this takes much longer than
+ * finding a prime of the same size: seconds for 512 bits, minutes for 2048.
+ */
+pub fn generate_safe_prime<R: RngCore + CryptoRng + ?Sized>(bits: u64, rng: &mut R) -> BigUint {
+    if bits < 16 {
+        panic!("Safe primes need at least 16 bits.");
+    }
+    let odd_primes: Vec<u64> = primes_up_to(1000).into_iter().skip(1).collect();
+    let top_bit = BigUint::one() << (bits - 2);
+    let two = BigUint::from(2u32);
+
+    loop {
+        let q = random_big_below(&(BigUint::one() << (bits - 1)), rng) | &top_bit | BigUint::one();
+        if odd_primes.iter().any(|&r| {
+            let residue = (&q % r).to_u64().unwrap();
+            residue == 0 || residue == (r - 1) / 2
+        }) {
+            continue;
+        }
+        let p = &q * 2u32 + 1u32;
+        //a cheap Fermat test rules out most candidates before the full tests
+        if !pow_mod_big(&two, &(&p - 1u32), &p).is_one() {
+            continue;
+        }
+        if baillie_psw(&q).is_probably_prime() && miller_rabin_big(&q, 4, rng).is_probably_prime()
+            && baillie_psw(&p).is_probably_prime() && miller_rabin_big(&p, 4, rng).is_probably_prime() {
+            return p;
+        }
+    }
+}
+
+/**
+ * The generator of the subgroup of order q: g = h^((p-1)/q) mod p for the smallest h >= 2
+ * where that isn't 1 (FIPS 186-4, A.2.1). Every element raised to (p-1)/q lands in the
+ * subgroup, and since q is prime anything there other than 1 generates all of it.
+ */
+pub fn subgroup_generator(p: &BigUint, q: &BigUint) -> BigUint {
+    let cofactor = (p - 1u32) / q;
+    let mut h = BigUint::from(2u32);
+    loop {
+        let g = pow_mod_big(&h, &cofactor, p);
+        if !g.is_one() {
+            return g;
+        }
+        h += 1u32;
+    }
+}
+
+impl ModpGroup {
+    //an RFC 3526 group: the safe prime p, q = (p-1)/2 and g = 2
+    fn rfc3526(hex: &str) -> Self {
+        let p = BigUint::from_str_radix(hex, 16).unwrap();
+        let q = (&p - 1u32) >> 1;
+        ModpGroup{p, q, g: BigUint::from(2u32)}
+    }
+
+    /**
+     * RFC 3526 group 5, 1536 bits. Too small for new uses, kept for compatibility.
+     */
+    pub fn modp_1536() -> Self {
+        ModpGroup::rfc3526(MODP_1536)
+    }
+
+    /**
+     * RFC 3526 group 14, 2048 bits, about as strong as 2048-bit RSA
+     */
+    pub fn modp_2048() -> Self {
+        ModpGroup::rfc3526(MODP_2048)
+    }
+
+    /**
+     * RFC 3526 group 15, 3072 bits, about 128-bit security
+     */
+    pub fn modp_3072() -> Self {
+        ModpGroup::rfc3526(MODP_3072)
+    }
+
+    /**
+     * RFC 3526 group 16, 4096 bits
+     */
+    pub fn modp_4096() -> Self {
+        ModpGroup::rfc3526(MODP_4096)
+    }
+
+    /**
+     * A new group modulo a random safe prime of the given number of bits, see
+     * generate_safe_prime. The generator is that of the quadratic residues.
+     */
+    pub fn generate<R: RngCore + CryptoRng + ?Sized>(bits: u64, rng: &mut R) -> Self {
+        let p = generate_safe_prime(bits, rng);
+        let q = (&p - 1u32) >> 1;
+        let g = subgroup_generator(&p, &q);
+        ModpGroup{p, q, g}
+    }
+
+    /**
+     * New DSA domain parameters (FIPS 186-4) with a prime p of l_bits and a prime q of
+     * n_bits dividing p - 1. The standard sizes are (2048, 224), (2048, 256) and (3072, 256).
+     *
+     * After drawing q, random l_bits numbers X are rounded down to p = X - (X mod 2q) + 1,
+     * so that p - 1 is a multiple of 2q, until p is prime; a new q is drawn after
+     * 4 * l_bits misses, as the standard does.
+     */
+    pub fn generate_dsa<R: RngCore + CryptoRng + ?Sized>(l_bits: u64, n_bits: u64, rng: &mut R) -> Self {
+        if n_bits < 16 || l_bits <= n_bits {
+            panic!("DSA needs q of at least 16 bits and p larger than q.");
+        }
+        let top_bit = BigUint::one() << (l_bits - 1);
+        loop {
+            let q = generate_rand_prime(n_bits, &BigUint::one(), rng);
+            let two_q = &q * 2u32;
+            for _i in 0..4 * l_bits {
+                let x = random_big_below(&(BigUint::one() << l_bits), rng) | &top_bit;
+                let p = &x - (&x % &two_q) + 1u32;
+                if p.bits() != l_bits {
+                    continue;
+                }
+                if baillie_psw(&p).is_probably_prime() && miller_rabin_big(&p, 4, rng).is_probably_prime() {
+                    let g = subgroup_generator(&p, &q);
+                    return ModpGroup{p, q, g};
+                }
+            }
+        }
+    }
+
+    /**
+     * Checks parameters received from elsewhere: p and q prime (by Baillie-PSW),
+     * q dividing p - 1 and g an element of order q.
+     */
+    pub fn is_valid(&self) -> bool {
+        if self.q < BigUint::from(2u32) || self.p <= self.q {
+            return false;
+        }
+        if !((&self.p - 1u32) % &self.q).is_zero() {
+            return false;
+        }
+        if !baillie_psw(&self.p).is_probably_prime() || !baillie_psw(&self.q).is_probably_prime() {
+            return false;
+        }
+        self.g > BigUint::one() && self.contains(&self.g)
+    }
+
+    /**
+     * Whether y is in the subgroup: 0 < y < p and y^q = 1 (mod p).
+     * Values from the other party must be checked this way, since one outside the subgroup
+     * can make a shared secret that leaks bits of the private key.
+     */
+    pub fn contains(&self, y: &BigUint) -> bool {
+        !y.is_zero() && y < &self.p && pow_mod_big(y, &self.q, &self.p).is_one()
+    }
+
+    /**
+     * The size of p in bytes
+     */
+    pub fn size(&self) -> usize {
+        self.p.bits().div_ceil(8) as usize
+    }
+}
+
+/**
+ * A public key y = g^x mod p, shared by Diffie-Hellman, ElGamal and DSA
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DlogPublicKey {
+    pub group: ModpGroup,
+    pub y: BigUint
+}
+
+/**
+ * A private key: an exponent x in 1..q
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DlogPrivateKey {
+    pub group: ModpGroup,
+    pub x: BigUint
+}
+
+/**
+ * An ElGamal ciphertext (g^k, m * y^k) for a random k
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElGamalCiphertext {
+    pub c1: BigUint,
+    pub c2: BigUint
+}
+
+/**
+ * A DSA signature (r, s), both in 1..q
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DsaSignature {
+    pub r: BigUint,
+    pub s: BigUint
+}
+
+//a random number in 1..q
+fn random_exponent<R: RngCore + CryptoRng + ?Sized>(q: &BigUint, rng: &mut R) -> BigUint {
+    random_big_below(&(q - 1u32), rng) + 1u32
+}
+
+//the leftmost min(N, outlen) bits of the hash, N being the size of q (FIPS 186-4, 4.6)
+fn dsa_hash<H: Digest>(message: &[u8], q: &BigUint) -> BigUint {
+    let hash = H::digest(message);
+    let hash_bits = hash.len() as u64 * 8;
+    let z = BigUint::from_bytes_be(&hash);
+    if hash_bits > q.bits() {
+        z >> (hash_bits - q.bits())
+    } else {
+        z
+    }
+}
+
+impl DlogPrivateKey {
+    /**
+     * A new key with x drawn uniformly from 1..q
+     */
+    pub fn generate<R: RngCore + CryptoRng + ?Sized>(group: &ModpGroup, rng: &mut R) -> Self {
+        DlogPrivateKey{group: group.clone(), x: random_exponent(&group.q, rng)}
+    }
+
+    pub fn public_key(&self) -> DlogPublicKey {
+        DlogPublicKey{group: self.group.clone(), y: pow_mod_big(&self.group.g, &self.x, &self.group.p)}
+    }
+
+    /**
+     * The Diffie-Hellman shared secret with the other party's public key, g^(xy) mod p,
+     * as bytes padded to the size of p. Both sides get the same value, which should go
+     * through a key derivation function like hkdf before being used as a key.
+     *
+     * Returns None if the keys are in different groups or the other key isn't in the
+     * subgroup.
+     */
+    pub fn diffie_hellman(&self, other: &DlogPublicKey) -> Option<Vec<u8>> {
+        if other.group != self.group || !self.group.contains(&other.y) || other.y.is_one() {
+            return None;
+        }
+        let shared = pow_mod_big(&other.y, &self.x, &self.group.p).to_bytes_be();
+        let mut out = vec![0u8; self.group.size() - shared.len()];
+        out.extend(shared);
+        Some(out)
+    }
+
+    /**
+     * ElGamal decryption: m = c2 / c1^x, computed as c2 * c1^(p-1-x) mod p.
+     * Returns None if c1 or c2 isn't in 1..p.
+     */
+    pub fn decrypt_elgamal(&self, ciphertext: &ElGamalCiphertext) -> Option<BigUint> {
+        let p = &self.group.p;
+        if ciphertext.c1.is_zero() || &ciphertext.c1 >= p || ciphertext.c2.is_zero() || &ciphertext.c2 >= p {
+            return None;
+        }
+        let exponent = p - 1u32 - &self.x;
+        Some(pow_mod_big(&ciphertext.c1, &exponent, p) * &ciphertext.c2 % p)
+    }
+
+    /**
+     * DSA signing (FIPS 186-4) with hash H: for a random k in 1..q,
+     * r = (g^k mod p) mod q and s = k^-1 * (H(message) + x * r) mod q, again with a new k
+     * if either is 0. k must never repeat or leak: two signatures with the same k give
+     * away x, so rng has to be a cryptographic generator.
+     */
+    pub fn sign_dsa<H: Digest, R: RngCore + CryptoRng + ?Sized>(&self, message: &[u8], rng: &mut R) -> DsaSignature {
+        let ModpGroup{p, q, g} = &self.group;
+        let z = dsa_hash::<H>(message, q);
+        loop {
+            let k = random_exponent(q, rng);
+            let r = pow_mod_big(g, &k, p) % q;
+            if r.is_zero() {
+                continue;
+            }
+            let k_inv = inv_mod_big(&k, q).unwrap();
+            let s = k_inv * ((&z + &self.x * &r) % q) % q;
+            if !s.is_zero() {
+                return DsaSignature{r, s};
+            }
+        }
+    }
+}
+
+impl DlogPublicKey {
+    /**
+     * Whether y is a valid element of the subgroup, see ModpGroup::contains
+     */
+    pub fn is_valid(&self) -> bool {
+        self.group.contains(&self.y) && !self.y.is_one()
+    }
+
+    /**
+     * ElGamal encryption of m in 1..p: (g^k, m * y^k) for a random k in 1..q. The same
+     * message encrypts differently every time, and anyone can multiply the hidden message
+     * by a known factor, so it is meant for random values like keys rather than raw data.
+     * The message should be in the subgroup (a quadratic residue, for a safe prime) for
+     * the ciphertext to reveal nothing about it.
+     *
+     * Panics if m isn't in 1..p.
+     */
+    pub fn encrypt_elgamal<R: RngCore + CryptoRng + ?Sized>(&self, m: &BigUint, rng: &mut R) -> ElGamalCiphertext {
+        let ModpGroup{p, q, g} = &self.group;
+        if m.is_zero() || m >= p {
+            panic!("The message must be in 1..p.");
+        }
+        let k = random_exponent(q, rng);
+        ElGamalCiphertext{c1: pow_mod_big(g, &k, p), c2: pow_mod_big(&self.y, &k, p) * m % p}
+    }
+
+    /**
+     * DSA verification: with w = s^-1 mod q, the signature is valid if
+     * (g^(H(message) * w) * y^(r * w) mod p) mod q = r.
+     */
+    pub fn verify_dsa<H: Digest>(&self, message: &[u8], signature: &DsaSignature) -> bool {
+        let ModpGroup{p, q, g} = &self.group;
+        let DsaSignature{r, s} = signature;
+        if r.is_zero() || r >= q || s.is_zero() || s >= q {
+            return false;
+        }
+        let z = dsa_hash::<H>(message, q);
+        let w = match inv_mod_big(s, q) {
+            Some(w) => w,
+            None => return false
+        };
+        let u1 = z * &w % q;
+        let u2 = r * &w % q;
+        let v = pow_mod_big(g, &u1, p) * pow_mod_big(&self.y, &u2, p) % p % q;
+        &v == r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::drbg::chacha_drbg::ChaChaDrbg;
+
+    #[test]
+    fn generated_dsa_parameters_are_valid() {
+        let mut rng = ChaChaDrbg::seed_from_u64(42);
+        for &(l_bits, n_bits) in [(256, 64), (512, 160)].iter() {
+            let group = ModpGroup::generate_dsa(l_bits, n_bits, &mut rng);
+            assert_eq!(group.p.bits(), l_bits);
+            assert_eq!(group.q.bits(), n_bits);
+            assert!(group.is_valid());
+        }
+    }
+
+    #[test]
+    fn built_in_groups_are_valid() {
+        for group in [ModpGroup::modp_1536(), ModpGroup::modp_2048(), ModpGroup::modp_3072(), ModpGroup::modp_4096()].iter() {
+            assert!(group.is_valid());
+            assert_eq!(group.size() * 8, group.p.bits() as usize);
+        }
+        let mut broken = ModpGroup::modp_1536();
+        //p - 1 has order 2
+        broken.g = &broken.p - 1u32;
+        assert!(!broken.is_valid());
+        broken = ModpGroup::modp_1536();
+        broken.q += 2u32;
+        assert!(!broken.is_valid());
+    }
+
+    #[test]
+    fn diffie_hellman_agreement() {
+        let mut rng = ChaChaDrbg::seed_from_u64(420);
+        let group = ModpGroup::modp_2048();
+        let alice = DlogPrivateKey::generate(&group, &mut rng);
+        let bob = DlogPrivateKey::generate(&group, &mut rng);
+        assert!(alice.public_key().is_valid());
+        let shared = alice.diffie_hellman(&bob.public_key()).unwrap();
+        assert_eq!(shared.len(), 256);
+        assert_eq!(Some(shared), bob.diffie_hellman(&alice.public_key()));
+
+        //public keys outside the subgroup, or from another group, are refused
+        for y in [BigUint::one(), &group.p - 1u32, group.p.clone(), BigUint::zero()].iter() {
+            let bad = DlogPublicKey{group: group.clone(), y: y.clone()};
+            assert!(!bad.is_valid());
+            assert_eq!(alice.diffie_hellman(&bad), None);
+        }
+        let other = DlogPrivateKey::generate(&ModpGroup::modp_1536(), &mut rng);
+        assert_eq!(alice.diffie_hellman(&other.public_key()), None);
+    }
+
+    #[test]
+    fn elgamal_round_trip() {
+        let mut rng = ChaChaDrbg::seed_from_u64(421);
+        let key = DlogPrivateKey::generate(&ModpGroup::modp_1536(), &mut rng);
+        let public = key.public_key();
+        for m in [BigUint::one(), BigUint::from(0xdead_beefu32), &key.group.p - 1u32].iter() {
+            let ciphertext = public.encrypt_elgamal(m, &mut rng);
+            assert_ne!(ciphertext, public.encrypt_elgamal(m, &mut rng));
+            assert_eq!(key.decrypt_elgamal(&ciphertext).as_ref(), Some(m));
+        }
+        let ciphertext = ElGamalCiphertext{c1: BigUint::zero(), c2: BigUint::one()};
+        assert_eq!(key.decrypt_elgamal(&ciphertext), None);
+    }
+
+    #[test]
+    fn dsa_signatures() {
+        let mut rng = ChaChaDrbg::seed_from_u64(422);
+        let group = ModpGroup::generate_dsa(1024, 160, &mut rng);
+        let key = DlogPrivateKey::generate(&group, &mut rng);
+        let public = key.public_key();
+        let signature = key.sign_dsa::<Sha256, _>(b"sample", &mut rng);
+        assert!(public.verify_dsa::<Sha256>(b"sample", &signature));
+        assert!(!public.verify_dsa::<Sha256>(b"samplf", &signature));
+        assert!(!public.verify_dsa::<Sha512>(b"sample", &signature));
+
+        let tampered = [
+            DsaSignature{r: &signature.r ^ BigUint::one(), s: signature.s.clone()},
+            DsaSignature{r: signature.r.clone(), s: &signature.s ^ BigUint::one()},
+            DsaSignature{r: signature.r.clone(), s: &signature.s + &group.q},
+            DsaSignature{r: BigUint::zero(), s: signature.s.clone()}
+        ];
+        for signature in tampered.iter() {
+            assert!(!public.verify_dsa::<Sha256>(b"sample", signature));
+        }
+        let other = DlogPrivateKey::generate(&group, &mut rng).public_key();
+        assert!(!other.verify_dsa::<Sha256>(b"sample", &signature));
+    }
+}
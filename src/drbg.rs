@@ -0,0 +1,224 @@
+pub mod chacha_drbg {
+    extern crate rand;
+    use rand::{CryptoRng, Error, RngCore, SeedableRng};
+    use rand::rngs::OsRng;
+    use crate::hashing::hash_algos::*;
+
+    //"expand 32-byte k" as little-endian words
+    const CHACHA_CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+    //blocks of keystream made at a time by the DRBG
+    const BLOCKS_PER_REFILL: usize = 4;
+
+    fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+        state[a] = state[a].wrapping_add(state[b]);
+        state[d] = (state[d] ^ state[a]).rotate_left(16);
+        state[c] = state[c].wrapping_add(state[d]);
+        state[b] = (state[b] ^ state[c]).rotate_left(12);
+        state[a] = state[a].wrapping_add(state[b]);
+        state[d] = (state[d] ^ state[a]).rotate_left(8);
+        state[c] = state[c].wrapping_add(state[d]);
+        state[b] = (state[b] ^ state[c]).rotate_left(7);
+    }
+
+    /**
+     * The ChaCha20 block function (RFC 8439): 64 bytes of keystream for a 256-bit key,
+     * a 32-bit block counter and a 96-bit nonce.
+     *
+     * The 16-word state is the constants, the key, the counter and the nonce. It goes
+     * through 20 rounds, alternating quarter rounds on the columns and on the diagonals,
+     * and the input state is added back at the end so the rounds can't be run backwards.
+     */
+    pub fn chacha20_block(key: &[u8; 32], counter: u32, nonce: &[u8; 12]) -> [u8; 64] {
+        let mut input = [0u32; 16];
+        input[..4].copy_from_slice(&CHACHA_CONSTANTS);
+        for (i, word) in key.chunks(4).enumerate() {
+            input[4 + i] = u32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        input[12] = counter;
+        for (i, word) in nonce.chunks(4).enumerate() {
+            input[13 + i] = u32::from_le_bytes([word[0], word[1], word[2], word[3]]);
+        }
+
+        let mut state = input;
+        for _i in 0..10 {
+            quarter_round(&mut state, 0, 4, 8, 12);
+            quarter_round(&mut state, 1, 5, 9, 13);
+            quarter_round(&mut state, 2, 6, 10, 14);
+            quarter_round(&mut state, 3, 7, 11, 15);
+            quarter_round(&mut state, 0, 5, 10, 15);
+            quarter_round(&mut state, 1, 6, 11, 12);
+            quarter_round(&mut state, 2, 7, 8, 13);
+            quarter_round(&mut state, 3, 4, 9, 14);
+        }
+
+        let mut out = [0u8; 64];
+        for (i, (word, added)) in state.iter().zip(input.iter()).enumerate() {
+            out[4 * i..4 * i + 4].copy_from_slice(&word.wrapping_add(*added).to_le_bytes());
+        }
+        out
+    }
+
+    /**
+     * A deterministic random bit generator built on ChaCha20 with fast key erasure:
+     * each refill runs ChaCha20 under the current key for a few blocks, the first 32 bytes
+     * of that keystream become the next key and the rest is handed out, being zeroed as
+     * it goes. Once a refill is done the old key is gone, so capturing the state later
+     * doesn't reveal earlier output.
+     *
+     * The whole output is a function of the 32-byte seed: from_seed (or seed_from_u64 in
+     * tests) replays it exactly, and new() seeds it from the operating system.
+     * Being an RngCore + CryptoRng, it can be passed to everything in the crate that
+     * takes a generator, like RsaPrivateKey::generate.
+     */
+    #[derive(Clone)]
+    pub struct ChaChaDrbg {
+        key: [u8; 32],
+        buffer: [u8; 64 * BLOCKS_PER_REFILL - 32],     //output not handed out yet
+        position: usize                                 //start of the unused output in buffer
+    }
+
+    impl ChaChaDrbg {
+        /**
+         * A generator seeded with 32 bytes from the operating system
+         */
+        pub fn new() -> Self {
+            let mut seed = [0u8; 32];
+            OsRng.fill_bytes(&mut seed);
+            ChaChaDrbg::from_seed(seed)
+        }
+
+        /**
+         * Mixes more entropy into the key: key = SHA-256(key || entropy).
+         * Output already buffered under the old key is discarded.
+         */
+        pub fn reseed(&mut self, entropy: &[u8]) {
+            let mut hasher = Sha256::default();
+            hasher.update(&self.key);
+            hasher.update(entropy);
+            self.key.copy_from_slice(&hasher.finalize());
+            self.buffer = [0; 64 * BLOCKS_PER_REFILL - 32];
+            self.position = self.buffer.len();
+        }
+
+        fn refill(&mut self) {
+            let mut keystream = Vec::with_capacity(64 * BLOCKS_PER_REFILL);
+            for counter in 0..BLOCKS_PER_REFILL {
+                keystream.extend_from_slice(&chacha20_block(&self.key, counter as u32, &[0; 12]));
+            }
+            self.key.copy_from_slice(&keystream[..32]);
+            self.buffer.copy_from_slice(&keystream[32..]);
+            self.position = 0;
+        }
+    }
+
+    impl Default for ChaChaDrbg {
+        fn default() -> Self {
+            ChaChaDrbg::new()
+        }
+    }
+
+    impl SeedableRng for ChaChaDrbg {
+        type Seed = [u8; 32];
+
+        fn from_seed(seed: [u8; 32]) -> Self {
+            ChaChaDrbg{key: seed, buffer: [0; 64 * BLOCKS_PER_REFILL - 32], position: 64 * BLOCKS_PER_REFILL - 32}
+        }
+    }
+
+    impl RngCore for ChaChaDrbg {
+        fn next_u32(&mut self) -> u32 {
+            let mut bytes = [0u8; 4];
+            self.fill_bytes(&mut bytes);
+            u32::from_le_bytes(bytes)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut bytes = [0u8; 8];
+            self.fill_bytes(&mut bytes);
+            u64::from_le_bytes(bytes)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            let mut filled = 0;
+            while filled < dest.len() {
+                if self.position == self.buffer.len() {
+                    self.refill();
+                }
+                let count = (dest.len() - filled).min(self.buffer.len() - self.position);
+                let output = &mut self.buffer[self.position..self.position + count];
+                dest[filled..filled + count].copy_from_slice(output);
+                output.iter_mut().for_each(|b| {*b = 0});
+                self.position += count;
+                filled += count;
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for ChaChaDrbg {}
+
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn hex(s: &str) -> Vec<u8> {
+            (0..s.len()).step_by(2).map(|i| {u8::from_str_radix(&s[i..i + 2], 16).unwrap()}).collect()
+        }
+
+        #[test]
+        fn chacha20_block_rfc8439() {
+            //RFC 8439, 2.3.2
+            let mut key = [0u8; 32];
+            key.iter_mut().enumerate().for_each(|(i, b)| {*b = i as u8});
+            let nonce = [0, 0, 0, 0x09, 0, 0, 0, 0x4a, 0, 0, 0, 0];
+            let expected = hex(concat!(
+                "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4e",
+                "d2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e"
+            ));
+            assert_eq!(chacha20_block(&key, 1, &nonce).to_vec(), expected);
+        }
+
+        #[test]
+        fn equal_seeds_give_equal_streams() {
+            let mut first = ChaChaDrbg::seed_from_u64(41);
+            let mut second = ChaChaDrbg::seed_from_u64(41);
+            let mut long = vec![0u8; 1000];
+            first.fill_bytes(&mut long);
+            //the same bytes whatever the sizes of the requests
+            let mut pieces = Vec::new();
+            for &len in [1usize, 7, 200, 24, 500, 268].iter() {
+                let mut piece = vec![0u8; len];
+                second.fill_bytes(&mut piece);
+                pieces.extend(piece);
+            }
+            assert_eq!(pieces, long);
+            assert_eq!(first.clone().next_u64(), first.next_u64());
+
+            let mut other = ChaChaDrbg::seed_from_u64(42);
+            let mut other_bytes = vec![0u8; 1000];
+            other.fill_bytes(&mut other_bytes);
+            assert_ne!(other_bytes, long);
+
+            let mut reseeded = ChaChaDrbg::seed_from_u64(41);
+            reseeded.reseed(b"more entropy");
+            let mut reseeded_bytes = vec![0u8; 1000];
+            reseeded.fill_bytes(&mut reseeded_bytes);
+            assert_ne!(reseeded_bytes, long);
+        }
+
+        #[test]
+        fn output_after_the_new_key() {
+            //the keystream of the all-zero key past the 32 bytes taken for the next key
+            let mut drbg = ChaChaDrbg::from_seed([0; 32]);
+            let mut out = [0u8; 32];
+            drbg.fill_bytes(&mut out);
+            assert_eq!(out.to_vec(), hex("da41597c5157488d7724e03fb8d84a376a43b8f41518a11cc387b669b2ee6586"));
+        }
+    }
+}
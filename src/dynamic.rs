@@ -1,5 +1,9 @@
 
 pub mod dynamic_algos {
+    extern crate num;
+    use num::{BigUint, One, Zero};
+    use std::collections::HashMap;
+    use std::hash::Hash;
 
     /**
      * Items for the Knapsack problem
@@ -18,6 +22,15 @@ pub mod dynamic_algos {
         }
     }
 
+    /**
+     * A longest increasing subsequence: its length and the indexes of its elems
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct LisSolution {
+        pub length: usize,
+        pub indices: Vec<usize>
+    }
+
     /**
     * Calculates the longest increasing subsequence (lis) of arg arr
     * 
@@ -27,124 +40,618 @@ pub mod dynamic_algos {
     * - that need not be consecutive (intermediate elems in original array can be dropped)
     * 
     * Consumes an array of itegers
-    * Returns the length of the longest lis in the array
+    * Returns the length of the longest lis in the array and the indexes of one such lis,
+    * found by following from its last elem the predecessor that gave each elem its length
     * Runs in O(n^2) time
     */
-    pub fn lis (arr: Vec<i32>) -> i32 {
-        //edge cases
-        if arr.len() == 1 {
-            return 1;
+    pub fn lis (arr: Vec<i32>) -> LisSolution {
+        let mut lis_arr = vec![1; arr.len()];              // list of largest lis lengths at arr[i]
+        let mut previous: Vec<Option<usize>> = vec![None; arr.len()]; // elem before arr[i] in that lis
+
+        //iterate through all elems of arr
+        //arr[i] = current arr value iterated over
+        //arr[j] = current subarray value iterated over, in arr[a0..a(i-1)]
+        for i in 1..arr.len() {
+            for j in 0..i {
+                if arr[i] > arr[j] && lis_arr[i] < lis_arr[j] + 1 {
+                    lis_arr[i] = lis_arr[j] + 1;
+                    previous[i] = Some(j);
+                }
+            }
         }
 
-        if arr.len() == 0 {
-            return 0;
+        //find the largest length in lis_arr, then walk back from where it ends
+        let mut end = None;
+        for (i, length) in lis_arr.iter().enumerate() {
+            if end.is_none_or(|e: usize| {*length > lis_arr[e]}) {
+                end = Some(i);
+            }
+        }
+        let mut indices = Vec::new();
+        while let Some(i) = end {
+            indices.push(i);
+            end = previous[i];
         }
+        indices.reverse();
 
-        let mut i: usize = 0; //index for arr
-        let mut j: usize = 0; //index for lis_arr
-        let mut lis_arr = Vec::new(); // list of largest lis lengths at arr[i]
+        LisSolution {
+            length: indices.len(),
+            indices
+        }
+    }
+
+    /**
+     * The order a subsequence must follow, between each elem and the next
+     */
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Monotonicity {
+        StrictlyIncreasing,
+        NonDecreasing,
+        StrictlyDecreasing,
+        NonIncreasing
+    }
 
-        //populate lis_arr with with same number of elems as arr
-        // each elem = 1
-        while i < arr.len() {
-            lis_arr.push(1);
-            i += 1;
+    impl Monotonicity {
+        //whether an elem with key b can follow one with key a
+        fn extends<K: Ord>(&self, a: &K, b: &K) -> bool {
+            match self {
+                Monotonicity::StrictlyIncreasing => a < b,
+                Monotonicity::NonDecreasing => a <= b,
+                Monotonicity::StrictlyDecreasing => a > b,
+                Monotonicity::NonIncreasing => a >= b
+            }
         }
+    }
 
-        i = 1; //reset arr index
+    /**
+     * Patience sorting: elems are dealt left to right onto piles, each onto the leftmost pile
+     * whose top it can't follow, and a new pile if it can follow all of them. The tops stay
+     * sorted, so the pile is found by binary search, and the elem on pile k ends a
+     * subsequence of length k + 1 whose previous elem is the top of pile k - 1 at that time.
+     * Returns the length of the longest subsequence ending at each elem, and that elem before it.
+     */
+    fn patience<K: Ord>(keys: &[K], order: Monotonicity) -> (Vec<usize>, Vec<Option<usize>>) {
+        let mut tops: Vec<usize> = Vec::new();      //index of the elem on top of each pile
+        let mut lengths = Vec::with_capacity(keys.len());
+        let mut previous = Vec::with_capacity(keys.len());
+        for (i, key) in keys.iter().enumerate() {
+            let pile = tops.partition_point(|&top| {order.extends(&keys[top], key)});
+            previous.push(if pile > 0 {Some(tops[pile - 1])} else {None});
+            lengths.push(pile + 1);
+            if pile == tops.len() {
+                tops.push(i);
+            } else {
+                tops[pile] = i;
+            }
+        }
+        (lengths, previous)
+    }
 
-        //iterate through all elems of arr
-        while i < arr.len() {
-            j = 0; //reset lis_arr index
-
-            //iterate through subarray arr[a0..a(i-1)]
-            //arr[i] = current arr value iterated over
-            //arr[j] = current subarray value iterated over
-            //lis_arr[i] = lis length value corresponding with arr[i]
-            //lis_arr[j] = lis length value corresponding with arr[j]
-            while j < i {
-                if arr[i] > arr[j] && lis_arr[i] < lis_arr[j] + 1 {
-                    lis_arr[i] = lis_arr[j] + 1;
+    //the indexes of the subsequence ending at end, following previous
+    fn unwind(previous: &[Option<usize>], end: Option<usize>) -> Vec<usize> {
+        let mut indices = Vec::new();
+        let mut at = end;
+        while let Some(i) = at {
+            indices.push(i);
+            at = previous[i];
+        }
+        indices.reverse();
+        indices
+    }
+
+    /**
+     * The longest subsequence of arr whose keys follow the given order, by patience sorting.
+     * key(elem) is what is compared, so structs can be ordered by a field and
+     * Monotonicity::StrictlyIncreasing with the identity key gives the lis above.
+     * Runs in O(n log n) time
+     */
+    pub fn lis_by_key<'a, T, K: Ord, F: Fn(&'a T) -> K>(arr: &'a [T], order: Monotonicity, key: F) -> LisSolution {
+        let keys: Vec<K> = arr.iter().map(key).collect();
+        let (lengths, previous) = patience(&keys, order);
+        let end = (0..arr.len()).rev().max_by_key(|&i| {lengths[i]});
+        let indices = unwind(&previous, end);
+        LisSolution {
+            length: indices.len(),
+            indices
+        }
+    }
+
+    /**
+     * lis_by_key comparing the elems themselves
+     */
+    pub fn lis_patience<T: Ord>(arr: &[T], order: Monotonicity) -> LisSolution {
+        lis_by_key(arr, order, |elem| {elem})
+    }
+
+    //(length, count) of the longest subsequences seen, keeping the longer or adding the counts of equal ones
+    fn combine_counts(a: &mut (usize, BigUint), b: &(usize, BigUint)) {
+        if b.0 > a.0 {
+            *a = b.clone();
+        } else if b.0 == a.0 {
+            a.1 += &b.1;
+        }
+    }
+
+    /**
+     * The number of longest subsequences of arr whose keys follow the given order,
+     * counting subsequences made of different indexes as different even if their values match
+     * (0 for an empty arr).
+     *
+     * The keys are ranked so that an elem can follow exactly the elems of lower rank (or
+     * lower or equal rank, for a non-strict order). A Fenwick tree over the ranks then gives,
+     * for each elem, the longest length and the number of subsequences of that length
+     * ending at an elem it can follow, in O(log n).
+     * Runs in O(n log n) time; the count can be exponential in n, so it is a BigUint.
+     */
+    pub fn count_lis_by_key<'a, T, K: Ord, F: Fn(&'a T) -> K>(arr: &'a [T], order: Monotonicity, key: F) -> BigUint {
+        let keys: Vec<K> = arr.iter().map(key).collect();
+        let mut sorted: Vec<&K> = keys.iter().collect();
+        sorted.sort();
+        sorted.dedup();
+        let decreasing = matches!(order, Monotonicity::StrictlyDecreasing | Monotonicity::NonIncreasing);
+        let strict = matches!(order, Monotonicity::StrictlyIncreasing | Monotonicity::StrictlyDecreasing);
+
+        //tree[r] covers the ranks (r - (r & -r), r], from 1
+        let mut tree = vec![(0usize, BigUint::zero()); sorted.len() + 1];
+        let mut best = (0usize, BigUint::zero());
+        for key in keys.iter() {
+            let position = sorted.binary_search(&key).unwrap();
+            let rank = if decreasing {sorted.len() - position} else {position + 1};
+
+            //the best over the ranks this elem can follow, 1..rank or 1..=rank
+            let mut found = (0usize, BigUint::zero());
+            let mut r = if strict {rank - 1} else {rank};
+            while r > 0 {
+                combine_counts(&mut found, &tree[r]);
+                r -= r & r.wrapping_neg();
+            }
+            let ending_here = if found.0 == 0 {(1, BigUint::one())} else {(found.0 + 1, found.1)};
+
+            let mut r = rank;
+            while r < tree.len() {
+                combine_counts(&mut tree[r], &ending_here);
+                r += r & r.wrapping_neg();
+            }
+            combine_counts(&mut best, &ending_here);
+        }
+        best.1
+    }
+
+    /**
+     * count_lis_by_key comparing the elems themselves
+     */
+    pub fn count_lis<T: Ord>(arr: &[T], order: Monotonicity) -> BigUint {
+        count_lis_by_key(arr, order, |elem| {elem})
+    }
+
+    /**
+     * The longest bitonic subsequence: strictly increasing keys, then strictly decreasing ones
+     * (either part may be empty). Patience sorting gives the longest increasing subsequence
+     * ending at each elem, and run on the reversed array the longest decreasing one starting
+     * there; the peak is the elem where the two add up to the most.
+     * Runs in O(n log n) time
+     */
+    pub fn longest_bitonic_by_key<'a, T, K: Ord, F: Fn(&'a T) -> K>(arr: &'a [T], key: F) -> LisSolution {
+        let keys: Vec<K> = arr.iter().map(key).collect();
+        let (rising, rising_previous) = patience(&keys, Monotonicity::StrictlyIncreasing);
+        let reversed: Vec<&K> = keys.iter().rev().collect();
+        let (falling, falling_previous) = patience(&reversed, Monotonicity::StrictlyIncreasing);
+        let n = arr.len();
+
+        let peak = (0..n).rev().max_by_key(|&i| {rising[i] + falling[n - 1 - i]});
+        let mut indices = unwind(&rising_previous, peak);
+        if let Some(peak) = peak {
+            //the decreasing part, as indexes of the reversed array from its end back to the peak
+            let tail = unwind(&falling_previous, Some(n - 1 - peak));
+            indices.extend(tail.iter().rev().skip(1).map(|&i| {n - 1 - i}));
+        }
+        LisSolution {
+            length: indices.len(),
+            indices
+        }
+    }
+
+    /**
+     * longest_bitonic_by_key comparing the elems themselves
+     */
+    pub fn longest_bitonic<T: Ord>(arr: &[T]) -> LisSolution {
+        longest_bitonic_by_key(arr, |elem| {elem})
+    }
+
+    /**
+     * A longest common subsequence of two sequences, and the alignment it gives them:
+     * pairs of indexes where matched elems are paired and every other elem is paired
+     * with None (a gap in the other sequence)
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct LcsSolution<T> {
+        pub length: usize,
+        pub subsequence: Vec<T>,
+        pub alignment: Vec<(Option<usize>, Option<usize>)>
+    }
+
+    impl<T: Clone> LcsSolution<T> {
+        fn from_alignment(seq_1: &[T], alignment: Vec<(Option<usize>, Option<usize>)>) -> Self {
+            let subsequence: Vec<T> = alignment.iter().filter_map(|pair| {
+                match pair {
+                    (Some(i), Some(_j)) => Some(seq_1[*i].clone()),
+                    _ => None
                 }
+            }).collect();
+            LcsSolution {
+                length: subsequence.len(),
+                subsequence,
+                alignment
+            }
+        }
+    }
+
+    /**
+     * Returns the longest common subsequence (LCS) of two sequences of any comparable elems:
+     * chars, graphemes or words as &str, lines of a file...
+     * LCS - The longest shared series of elems between the two sequences
+     * Output: its length, the subsequence and the alignment of the sequences along it
+     *
+     * L(i, j) is the LCS length of the first i elems of seq_1 and the first j of seq_2.
+     * The solution is rebuilt by walking back from L(n, m) along the choices that made each square.
+     * Takes O(nm) time and space, see lcs_hirschberg for linear space.
+     */
+    pub fn lcs_of<T: Eq + Clone>(seq_1: &[T], seq_2: &[T]) -> LcsSolution<T> {
+        let mut lcs_table = vec![vec![0usize; seq_2.len() + 1]; seq_1.len() + 1]; //a 2D array for vals, with an empty row and column
+
+        //iterate across the 2D array
+        for i in 1..=seq_1.len() {
+            for j in 1..=seq_2.len() {
+                //if the elems match, assign value of square L(i, j) = 1 + value of the upper left diagonal square
+                //if no match, assign L(i, j) = to either the value of the square above or the square to the left, whichever is greater
+                lcs_table[i][j] = if seq_1[i - 1] == seq_2[j - 1] {
+                    1 + lcs_table[i - 1][j - 1]
+                } else {
+                    lcs_table[i - 1][j].max(lcs_table[i][j - 1])
+                };
+            }
+        }
+
+        //walk back from the lower right square
+        let (mut i, mut j) = (seq_1.len(), seq_2.len());
+        let mut alignment = Vec::new();
+        while i > 0 || j > 0 {
+            if i > 0 && j > 0 && seq_1[i - 1] == seq_2[j - 1] {
+                alignment.push((Some(i - 1), Some(j - 1)));
+                i -= 1;
+                j -= 1;
+            } else if j == 0 || (i > 0 && lcs_table[i - 1][j] >= lcs_table[i][j - 1]) {
+                alignment.push((Some(i - 1), None));
+                i -= 1;
+            } else {
+                alignment.push((None, Some(j - 1)));
+                j -= 1;
+            }
+        }
+        alignment.reverse();
+        LcsSolution::from_alignment(seq_1, alignment)
+    }
+
+    /**
+     * The LCS of two strings, compared char by char (so multi-byte UTF-8 chars count once).
+     * To compare graphemes, words or lines, split the strings and use lcs_of.
+     */
+    pub fn lcs(string_1: String, string_2: String) -> LcsSolution<char> {
+        let chars_1: Vec<char> = string_1.chars().collect();
+        let chars_2: Vec<char> = string_2.chars().collect();
+        lcs_of(&chars_1, &chars_2)
+    }
 
-                j += 1;
+    //the last row of the lcs table, L(n, j) for every j, keeping only two rows
+    fn lcs_row<T: Eq>(seq_1: &[T], seq_2: &[T]) -> Vec<usize> {
+        let mut row = vec![0usize; seq_2.len() + 1];
+        let mut next = vec![0usize; seq_2.len() + 1];
+        for elem in seq_1.iter() {
+            for j in 1..=seq_2.len() {
+                next[j] = if *elem == seq_2[j - 1] {
+                    row[j - 1] + 1
+                } else {
+                    row[j].max(next[j - 1])
+                };
             }
+            std::mem::swap(&mut row, &mut next);
+        }
+        row
+    }
 
-            i += 1;
+    /**
+     * Only the length of the LCS, in O(nm) time but O(m) space
+     */
+    pub fn lcs_length<T: Eq>(seq_1: &[T], seq_2: &[T]) -> usize {
+        lcs_row(seq_1, seq_2)[seq_2.len()]
+    }
+
+    /**
+     * The LCS in linear space, by Hirschberg's divide and conquer.
+     *
+     * seq_1 is cut in half. The last lcs row of the first half against seq_2, and of the
+     * reversed second half against the reversed seq_2, give for every cut j of seq_2 the
+     * LCS lengths of the two halves on either side of it; an LCS passes through the j with
+     * the largest sum, so the two halves are solved on their own around it.
+     * Takes O(nm) time, about twice lcs_of, and O(n + m) space.
+     */
+    pub fn lcs_hirschberg<T: Eq + Clone>(seq_1: &[T], seq_2: &[T]) -> LcsSolution<T> {
+        let mut alignment = Vec::with_capacity(seq_1.len() + seq_2.len());
+        hirschberg(seq_1, seq_2, 0, 0, &mut alignment);
+        LcsSolution::from_alignment(seq_1, alignment)
+    }
+
+    //appends the alignment of seq_1 and seq_2, whose first elems are at offset_1 and offset_2
+    fn hirschberg<T: Eq>(seq_1: &[T], seq_2: &[T], offset_1: usize, offset_2: usize, alignment: &mut Vec<(Option<usize>, Option<usize>)>) {
+        if seq_1.is_empty() {
+            alignment.extend((0..seq_2.len()).map(|j| {(None, Some(offset_2 + j))}));
+            return;
+        }
+        if seq_1.len() == 1 {
+            match seq_2.iter().position(|elem| {*elem == seq_1[0]}) {
+                Some(at) => {
+                    alignment.extend((0..at).map(|j| {(None, Some(offset_2 + j))}));
+                    alignment.push((Some(offset_1), Some(offset_2 + at)));
+                    alignment.extend((at + 1..seq_2.len()).map(|j| {(None, Some(offset_2 + j))}));
+                },
+                None => {
+                    alignment.push((Some(offset_1), None));
+                    alignment.extend((0..seq_2.len()).map(|j| {(None, Some(offset_2 + j))}));
+                }
+            }
+            return;
         }
 
-        //find the largest length in lis_arr
-        let mut solution = 1;
-        for elem in lis_arr.iter() {
-            if *elem > solution {
-                solution = *elem;
+        let mid = seq_1.len() / 2;
+        let forward = lcs_row(&seq_1[..mid], seq_2);
+        let reversed_1: Vec<&T> = seq_1[mid..].iter().rev().collect();
+        let reversed_2: Vec<&T> = seq_2.iter().rev().collect();
+        let backward = lcs_row(&reversed_1, &reversed_2);
+        let cut = (0..=seq_2.len()).max_by_key(|&j| {(forward[j] + backward[seq_2.len() - j], std::cmp::Reverse(j))}).unwrap();
+
+        hirschberg(&seq_1[..mid], &seq_2[..cut], offset_1, offset_2, alignment);
+        hirschberg(&seq_1[mid..], &seq_2[cut..], offset_1 + mid, offset_2 + cut, alignment);
+    }
+
+    /**
+     * The costs of the edits of an edit distance. The default is 1 for each, Levenshtein's.
+     */
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct EditCosts {
+        pub insert: usize,
+        pub delete: usize,
+        pub substitute: usize
+    }
+
+    impl Default for EditCosts {
+        fn default() -> Self {
+            EditCosts {
+                insert: 1,
+                delete: 1,
+                substitute: 1
             }
         }
+    }
+
+    /**
+     * A step of an edit script turning seq_1 into seq_2, with the indexes of the elems involved
+     */
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum EditOperation {
+        Keep(usize, usize),         //seq_1[i] stays as seq_2[j]
+        Substitute(usize, usize),   //seq_1[i] is replaced by seq_2[j]
+        Insert(usize),              //seq_2[j] is inserted
+        Delete(usize)               //seq_1[i] is deleted
+    }
 
-        solution
+    /**
+     * An edit distance and a cheapest script of edits reaching it
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct EditSolution {
+        pub distance: usize,
+        pub operations: Vec<EditOperation>
     }
 
+    /**
+     * The edit distance with the given costs: the cheapest way to turn seq_1 into seq_2
+     * by inserting, deleting and substituting elems.
+     *
+     * E(i, j) is the distance between the first i elems of seq_1 and the first j of seq_2,
+     * the cheapest of deleting seq_1[i-1] after E(i-1, j), inserting seq_2[j-1] after E(i, j-1),
+     * and keeping or substituting seq_1[i-1] for seq_2[j-1] after E(i-1, j-1).
+     * Takes O(nm) time and O(m) space, keeping two rows.
+     */
+    pub fn weighted_edit_distance<T: Eq>(seq_1: &[T], seq_2: &[T], costs: &EditCosts) -> usize {
+        let mut row: Vec<usize> = (0..=seq_2.len()).map(|j| {j * costs.insert}).collect();
+        let mut next = vec![0usize; seq_2.len() + 1];
+        for (i, elem) in seq_1.iter().enumerate() {
+            next[0] = (i + 1) * costs.delete;
+            for j in 1..=seq_2.len() {
+                let diagonal = row[j - 1] + if *elem == seq_2[j - 1] {0} else {costs.substitute};
+                next[j] = diagonal.min(row[j] + costs.delete).min(next[j - 1] + costs.insert);
+            }
+            std::mem::swap(&mut row, &mut next);
+        }
+        row[seq_2.len()]
+    }
 
     /**
-     * Returns the length of the longest common subsequence (LCS)
-     * LCS - The longest shared series of characters between the two strings
-     * Input: Two strings
-     * Output: u32 int
+     * The Levenshtein distance, the least number of single elem insertions, deletions and
+     * substitutions turning seq_1 into seq_2
      */
-    pub fn lcs(string_1: String, string_2: String) -> u32 {
-        let mut lcs_table: Vec<Vec<u32>> = Vec::new(); //a 2D array for vals
-        let mut i_arr: Vec<u32> = Vec::new(); //a row
-        let mut i = 0; // str1 index
-        let mut j = 0; // str2 index
+    pub fn levenshtein<T: Eq>(seq_1: &[T], seq_2: &[T]) -> usize {
+        weighted_edit_distance(seq_1, seq_2, &EditCosts::default())
+    }
 
-        //populate 2D array with zeros
-        while i < string_1.len() {
-            i_arr.push(0);
-            i += 1;
+    /**
+     * The edit distance with the given costs and the edits of one cheapest script, rebuilt
+     * by walking back from E(n, m) along the choices that made each square.
+     * Takes O(nm) time and space.
+     */
+    pub fn edit_script<T: Eq>(seq_1: &[T], seq_2: &[T], costs: &EditCosts) -> EditSolution {
+        let (n, m) = (seq_1.len(), seq_2.len());
+        let mut table = vec![vec![0usize; m + 1]; n + 1];
+        for (i, row) in table.iter_mut().enumerate() {
+            row[0] = i * costs.delete;
+        }
+        table[0] = (0..=m).map(|j| {j * costs.insert}).collect();
+        for i in 1..=n {
+            for j in 1..=m {
+                let diagonal = table[i - 1][j - 1] + if seq_1[i - 1] == seq_2[j - 1] {0} else {costs.substitute};
+                table[i][j] = diagonal.min(table[i - 1][j] + costs.delete).min(table[i][j - 1] + costs.insert);
+            }
         }
 
-        while j < string_2.len() {
-            let list = &i_arr;
-            let v = list.to_vec();
-            lcs_table.push(v);
-            j += 1;
+        let (mut i, mut j) = (n, m);
+        let mut operations = Vec::new();
+        while i > 0 || j > 0 {
+            let same = i > 0 && j > 0 && seq_1[i - 1] == seq_2[j - 1];
+            if i > 0 && j > 0 && table[i][j] == table[i - 1][j - 1] + if same {0} else {costs.substitute} {
+                operations.push(if same {EditOperation::Keep(i - 1, j - 1)} else {EditOperation::Substitute(i - 1, j - 1)});
+                i -= 1;
+                j -= 1;
+            } else if i > 0 && table[i][j] == table[i - 1][j] + costs.delete {
+                operations.push(EditOperation::Delete(i - 1));
+                i -= 1;
+            } else {
+                operations.push(EditOperation::Insert(j - 1));
+                j -= 1;
+            }
         }
+        operations.reverse();
 
-        //reset indexes
-        i = 0;
-        j = 0;
+        EditSolution {
+            distance: table[n][m],
+            operations
+        }
+    }
 
-        //iterate across the 2D array
-        while i < string_1.len() {
-            j = 0;  
-            while j < string_2.len() {
-                //convert str indexes to chars
-                let b1 = string_1.as_bytes()[i];
-                let ch1 = b1 as char;
-                let b2 = string_2.as_bytes()[j];
-                let ch2 = b2 as char;
-
-                if j != 0 && i != 0 {
-                    //if the chars match, assign value of square L(i, j) = 1 + value of the upper left diagonal square
-                    //if no match, assign L(i, j) = to either the value of the square above or the square to the left, whichever is greater
-                    if ch1 == ch2 {
-                        lcs_table[i][j] = 1 + lcs_table[i - 1][j - 1];
-                    } else if lcs_table[i - 1][j] > lcs_table[i][j - 1] {
-                                lcs_table[i][j] = lcs_table[i - 1][j];
-                            } else {
-                                lcs_table[i][j] = lcs_table[i][j - 1];
-                            }
-                } else if ch1 == ch2 {
-                    lcs_table[i][j] += 1;
+    /**
+     * The optimal string alignment (restricted Damerau-Levenshtein) distance: Levenshtein
+     * with swaps of two adjacent elems as one more edit, E(i, j) = E(i-2, j-2) + 1 when
+     * the last two elems are swapped. No elem is edited twice, so "ca" -> "abc" is 3
+     * (and the distance breaks the triangle inequality).
+     */
+    pub fn osa_distance<T: Eq>(seq_1: &[T], seq_2: &[T]) -> usize {
+        let (n, m) = (seq_1.len(), seq_2.len());
+        let mut table = vec![vec![0usize; m + 1]; n + 1];
+        for (i, row) in table.iter_mut().enumerate() {
+            row[0] = i;
+        }
+        table[0] = (0..=m).collect();
+        for i in 1..=n {
+            for j in 1..=m {
+                let cost = if seq_1[i - 1] == seq_2[j - 1] {0} else {1};
+                table[i][j] = (table[i - 1][j - 1] + cost).min(table[i - 1][j] + 1).min(table[i][j - 1] + 1);
+                if i > 1 && j > 1 && seq_1[i - 1] == seq_2[j - 2] && seq_1[i - 2] == seq_2[j - 1] {
+                    table[i][j] = table[i][j].min(table[i - 2][j - 2] + 1);
                 }
+            }
+        }
+        table[n][m]
+    }
 
-                j += 1;
+    /**
+     * The Damerau-Levenshtein distance, with adjacent swaps and no restriction on editing
+     * swapped elems again (Lowrance and Wagner), so "ca" -> "abc" is 2: swap, then insert.
+     *
+     * A swap can now have elems inserted between the swapped pair and deleted from between
+     * them: for seq_1[i-1] = seq_2[j-1], the last occurrence k of seq_2[j-1] in seq_1 before
+     * row i and the last column l before j where seq_2[l-1] = seq_1[i-1] give
+     * E(i, j) = E(k-1, l-1) + (i-k-1) + 1 + (j-l-1). The last row of each elem is kept in a map.
+     * Takes O(nm) time and space.
+     */
+    pub fn damerau_levenshtein<T: Eq + Hash>(seq_1: &[T], seq_2: &[T]) -> usize {
+        let (n, m) = (seq_1.len(), seq_2.len());
+        let infinity = n + m;
+        //the table is shifted by one so row and column 0 can hold infinity
+        let mut table = vec![vec![0usize; m + 2]; n + 2];
+        table[0][0] = infinity;
+        for i in 0..=n {
+            table[i + 1][0] = infinity;
+            table[i + 1][1] = i;
+        }
+        for j in 0..=m {
+            table[0][j + 1] = infinity;
+            table[1][j + 1] = j;
+        }
+
+        let mut last_row: HashMap<&T, usize> = HashMap::new();
+        for i in 1..=n {
+            let mut last_column = 0;
+            for j in 1..=m {
+                let k = last_row.get(&seq_2[j - 1]).copied().unwrap_or(0);
+                let l = last_column;
+                let cost = if seq_1[i - 1] == seq_2[j - 1] {
+                    last_column = j;
+                    0
+                } else {
+                    1
+                };
+                table[i + 1][j + 1] = (table[i][j] + cost)
+                    .min(table[i + 1][j] + 1)
+                    .min(table[i][j + 1] + 1)
+                    .min(table[k][l] + (i - k - 1) + 1 + (j - l - 1));
+            }
+            last_row.insert(&seq_1[i - 1], i);
+        }
+        table[n + 1][m + 1]
+    }
+
+    /**
+     * The Levenshtein distance if it is at most max_distance, else None (Ukkonen).
+     *
+     * A path through the table that strays more than max_distance from the diagonal costs
+     * more than max_distance, so only the band of squares with |i - j| <= max_distance is
+     * filled, and values above max_distance are capped. The search stops as soon as a whole
+     * row is above the bound, which is what makes it fast for spell checking and fuzzy search.
+     * Takes O(n * max_distance) time and O(m) space.
+     */
+    pub fn levenshtein_bounded<T: Eq>(seq_1: &[T], seq_2: &[T], max_distance: usize) -> Option<usize> {
+        let (n, m) = (seq_1.len(), seq_2.len());
+        if n.abs_diff(m) > max_distance {
+            return None;
+        }
+        let over = max_distance + 1;
+        let mut row: Vec<usize> = (0..=m).map(|j| {j.min(over)}).collect();
+        let mut next = vec![over; m + 1];
+        for i in 1..=n {
+            let first = i.saturating_sub(max_distance).max(1);
+            let last = (i + max_distance).min(m);
+            //next still holds row i - 2: only the squares just outside the band are read later
+            let mut row_min = over;
+            if first == 1 {
+                next[0] = i.min(over);
+                row_min = next[0];
+            } else {
+                next[first - 1] = over;
+            }
+            if last < m {
+                next[last + 1] = over;
+            }
+            for j in first..=last {
+                let diagonal = row[j - 1] + if seq_1[i - 1] == seq_2[j - 1] {0} else {1};
+                next[j] = diagonal.min(row[j] + 1).min(next[j - 1] + 1).min(over);
+                row_min = row_min.min(next[j]);
             }
-            i += 1;
+            if row_min > max_distance {
+                return None;
+            }
+            std::mem::swap(&mut row, &mut next);
         }
+        if row[m] <= max_distance {Some(row[m])} else {None}
+    }
 
-        //The last square on the lower right of the table (2D array) will have the highest value
-        lcs_table[string_1.len() - 1][string_2.len() - 1]
+    /**
+     * A knapsack filling: its total value and weight, and the chosen items
+     * as (index in the item array, number of copies)
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct KnapsackSolution {
+        pub value: i32,
+        pub weight: i32,
+        pub items: Vec<(usize, u32)>
     }
 
     /**
@@ -157,82 +664,133 @@ pub mod dynamic_algos {
      * This is an implementation for a dynamic programming solution to the Knapsack Problem
      * where there is no repetition. In other words, the item array contains items that
      * can be used only once. 
+     *
+     * K(i, b) is the best value with the first i items and capacity b. Item i was taken
+     * exactly when K(i, b) differs from K(i - 1, b), which is how the items are recovered.
      * 
      * The runtime for this solution is O(nB) where:
      * - n is the number of items in the item array
      * - B is the available weight
      * 
     */
-    pub fn knapsack_no_rep(items: &[Item<i32>], capacity: i32) -> i32 {
-        let mut solution_space: Vec<Vec<i32>> = Vec::new();
-        let mut row: Vec<i32> = Vec::new();
+    pub fn knapsack_no_rep(items: &[Item<i32>], capacity: i32) -> KnapsackSolution {
+        let capacity = capacity.max(0) as usize;
+        let mut solution_space = vec![vec![0; capacity + 1]; items.len() + 1];
 
-        //populate solution space
-        for _i in 0..items.len() {
-            row.push(0);
-        }
+        for i in 1..=items.len() {
+            let item = &items[i - 1];
+            for b in 0..=capacity {
+                let current_max = solution_space[i - 1][b]; //current largest value, without the item
 
-        for _b in 0..capacity {
-            let row_ref = &row;
-            let v = row_ref.to_vec();
-            solution_space.push(v);
-        }
-
-
-        for i in 0..items.len() {
-            for b in 0..capacity {
-                let u1: usize = i -1;
-                let current_max = solution_space[u1][b as usize]; //current largest value
-                
                 //if current object can "fit" in b
-                if items[i].weight <= b {
-                    let u2: usize = (b - items[i].weight) as usize;
-                    let new_val = items[i].value + solution_space[u1][u2]; //new value items[i].value + last object at b - object weight 
-                    if new_val > current_max {
-                        solution_space[i as usize][b as usize] = new_val;
-                    } else {
-                        solution_space[i as usize][b as usize] = current_max;
-                    }
+                solution_space[i][b] = if item.weight >= 0 && item.weight as usize <= b {
+                    let new_val = item.value + solution_space[i - 1][b - item.weight as usize];
+                    new_val.max(current_max)
                 } else {
-                    solution_space[i as usize][b as usize] = current_max;
-                }
+                    current_max
+                };
+            }
+        }
+
+        let mut chosen = Vec::new();
+        let mut b = capacity;
+        for i in (1..=items.len()).rev() {
+            if solution_space[i][b] != solution_space[i - 1][b] {
+                chosen.push((i - 1, 1));
+                b -= items[i - 1].weight as usize;
             }
         }
+        chosen.reverse();
 
-        solution_space[items.len() as usize][capacity as usize]
+        KnapsackSolution {
+            value: solution_space[items.len()][capacity],
+            weight: chosen.iter().map(|&(i, _count)| {items[i].weight}).sum(),
+            items: chosen
+        }
     }
 
     /**
      * This is an implementation for a dynamic programming solution to the Knapsack Problem
      * where there is repetition. In other words, the item array contains items that
      * can be used an unlimited number of times. 
+     *
+     * K(b) is the best value with capacity b, and the last item added to reach it is kept
+     * so the fill can be unwound from K(B). Weights must be positive.
      * 
      * The runtime for this solution is O(nB) where:
      * - n is the number of items in the item array
      * - B is the available weight
      * 
     */
-    pub fn knapsack_with_rep(items: &[Item<i32>], capacity: i32) -> i32 {
-        let mut solution_space: Vec<i32> = Vec::new();
-        
-        //populate solution space
-        for _b in 0..capacity {
-            solution_space.push(0);
-        }
-
-        for b in 0..capacity {
-            for i in 0..items.len() {
-                let new_val = items[i].value + solution_space[(b - items[i].weight) as usize];
-                let weight_low_enough: bool = items[i].weight < b;
-                let new_val_higher: bool = solution_space[i] < new_val;
-
-                if weight_low_enough && new_val_higher {
-                    solution_space[b as usize] = new_val;
+    pub fn knapsack_with_rep(items: &[Item<i32>], capacity: i32) -> KnapsackSolution {
+        if items.iter().any(|item| {item.weight <= 0}) {
+            panic!("Items that can be repeated need a positive weight.");
+        }
+        let capacity = capacity.max(0) as usize;
+        let mut solution_space = vec![0; capacity + 1];
+        let mut last_item: Vec<Option<usize>> = vec![None; capacity + 1];
+
+        for b in 1..=capacity {
+            //an unfilled unit of capacity is as good as the fill one smaller
+            solution_space[b] = solution_space[b - 1];
+            for (i, item) in items.iter().enumerate() {
+                let weight = item.weight as usize;
+                if weight <= b && solution_space[b - weight] + item.value > solution_space[b] {
+                    solution_space[b] = solution_space[b - weight] + item.value;
+                    last_item[b] = Some(i);
                 }
             }
         }
 
-        solution_space[capacity as usize]
+        let mut counts = vec![0u32; items.len()];
+        let mut b = capacity;
+        while b > 0 {
+            match last_item[b] {
+                Some(i) => {
+                    counts[i] += 1;
+                    b -= items[i].weight as usize;
+                },
+                None => b -= 1
+            }
+        }
+        let chosen: Vec<(usize, u32)> = counts.into_iter().enumerate().filter(|&(_i, count)| {count > 0}).collect();
+
+        KnapsackSolution {
+            value: solution_space[capacity],
+            weight: chosen.iter().map(|&(i, count)| {items[i].weight * count as i32}).sum(),
+            items: chosen
+        }
+    }
+
+    /**
+     * An order of multiplying a chain of matrices: a single matrix Ai (numbered from 1),
+     * or the product of two sub-chains
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum Parenthesization {
+        Matrix(usize),
+        Product(Box<Parenthesization>, Box<Parenthesization>)
+    }
+
+    impl std::fmt::Display for Parenthesization {
+        /**
+         * Renders the order like ((A1A2)(A3A4))
+         */
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            match self {
+                Parenthesization::Matrix(i) => write!(f, "A{}", i),
+                Parenthesization::Product(left, right) => write!(f, "({}{})", left, right)
+            }
+        }
+    }
+
+    /**
+     * The cheapest order of a chain matrix multiplication and its cost
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct ChainSolution {
+        pub cost: i32,
+        pub order: Parenthesization
     }
 
     /**
@@ -246,35 +804,415 @@ pub mod dynamic_algos {
      *  M2 = m2 x m3
      *  ...
      *  Mn = m(n-1) x mn
+     *
+     * C(i, j) is the cost of multiplying Mi..Mj. The split that gave each C(i, j) is kept,
+     * and the order is built from the splits of C(1, n-1) down.
     */
-    pub fn chain_matrix_multiply(m_arr: &[i32]) -> i32{
-        let mut solution_space: Vec<Vec<i32>> = Vec::new();
-
-        //populate solution space
-        for i in 0..m_arr.len(){
-            for j in 0..m_arr.len() {
-                solution_space[i][j] = i32::MAX;
-            }
+    pub fn chain_matrix_multiply(m_arr: &[i32]) -> ChainSolution {
+        if m_arr.len() < 2 {
+            panic!("The chain needs at least one matrix.");
         }
+        let n = m_arr.len() - 1; //number of matrices
+        let mut solution_space = vec![vec![0; n + 1]; n + 1];
+        let mut split = vec![vec![0; n + 1]; n + 1];
 
         //Iterations move diagonally 
         //from top to botton and from left to right
-        for s in 1..(m_arr.len() - 1) {                         //width iterator
-            for i in 1..(m_arr.len() - s) {                     //depth iterator
+        for s in 1..n {                                         //width iterator
+            for i in 1..=(n - s) {                              //depth iterator
                 let j = i + s;
-                for l in i..(j-1) {                             //partition iterator
+                solution_space[i][j] = i32::MAX;
+                for l in i..j {                                 //partition iterator
                     let current_val = 
-                        (m_arr[(i-1)] * m_arr[l] * m_arr[j]) +  //combine left and right subtree
+                        (m_arr[i - 1] * m_arr[l] * m_arr[j]) +  //combine left and right subtree
                         solution_space[i][l] +                  //left subtree
                         solution_space[l + 1][j];               //right subtree
 
                     if solution_space[i][j] > current_val {
                         solution_space[i][j] = current_val;
+                        split[i][j] = l;
+                    }
+                }
+            }
+        }
+
+        ChainSolution {
+            cost: solution_space[1][n],                         //last entry
+            order: chain_order(&split, 1, n)
+        }
+    }
+
+    //the order of Mi..Mj from the best splits
+    fn chain_order(split: &[Vec<usize>], i: usize, j: usize) -> Parenthesization {
+        if i == j {
+            Parenthesization::Matrix(i)
+        } else {
+            let l = split[i][j];
+            Parenthesization::Product(Box::new(chain_order(split, i, l)), Box::new(chain_order(split, l + 1, j)))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use rand::{Rng, SeedableRng};
+        use crate::drbg::chacha_drbg::ChaChaDrbg;
+
+        fn random_sequence(rng: &mut ChaChaDrbg, max_len: usize, alphabet: i32) -> Vec<i32> {
+            let len = rng.gen_range(0..=max_len);
+            (0..len).map(|_i| {rng.gen_range(0..alphabet)}).collect()
+        }
+
+        const ORDERS: [Monotonicity; 4] = [
+            Monotonicity::StrictlyIncreasing,
+            Monotonicity::NonDecreasing,
+            Monotonicity::StrictlyDecreasing,
+            Monotonicity::NonIncreasing
+        ];
+
+        //whether the elems at indices follow the test, each against the one before
+        fn follows<F: Fn(i32, i32) -> bool>(arr: &[i32], indices: &[usize], test: F) -> bool {
+            indices.windows(2).all(|pair| {pair[0] < pair[1] && test(arr[pair[0]], arr[pair[1]])})
+        }
+
+        //the length of the longest subsequences passing check and how many there are, over all 2^n of them
+        fn brute_force<F: Fn(&[usize]) -> bool>(len: usize, check: F) -> (usize, u64) {
+            let mut best = (0, 0);
+            for mask in 0..1u32 << len {
+                let indices: Vec<usize> = (0..len).filter(|&i| {mask & 1 << i != 0}).collect();
+                if !check(&indices) {
+                    continue;
+                }
+                if indices.len() > best.0 {
+                    best = (indices.len(), 1);
+                } else if indices.len() == best.0 {
+                    best.1 += 1;
+                }
+            }
+            best
+        }
+
+        //whether the elems at indices strictly rise and then strictly fall
+        fn is_bitonic(arr: &[i32], indices: &[usize]) -> bool {
+            let peak = (0..indices.len()).take_while(|&i| {i == 0 || arr[indices[i - 1]] < arr[indices[i]]}).count();
+            follows(arr, indices, |_a, _b| {true}) && follows(arr, &indices[peak.saturating_sub(1)..], |a, b| {a > b})
+        }
+
+        #[test]
+        fn patience_lis_matches_brute_force() {
+            let mut rng = ChaChaDrbg::seed_from_u64(47);
+            for _i in 0..300 {
+                let arr = random_sequence(&mut rng, 11, 6);
+                for &order in ORDERS.iter() {
+                    let test = |a: i32, b: i32| {order.extends(&a, &b)};
+                    let (length, count) = brute_force(arr.len(), |indices| {follows(&arr, indices, test)});
+
+                    let solution = lis_patience(&arr, order);
+                    assert_eq!(solution.length, length, "{:?} {:?}", order, arr);
+                    assert_eq!(solution.indices.len(), length);
+                    assert!(follows(&arr, &solution.indices, test));
+
+                    //the same order on negated keys is the opposite order on the elems
+                    let by_key = lis_by_key(&arr, order, |&elem| {-elem});
+                    assert!(follows(&arr, &by_key.indices, |a, b| {order.extends(&-a, &-b)}));
+                    assert_eq!(by_key.length, by_key.indices.len());
+
+                    let expected_count = if arr.is_empty() {0} else {count};
+                    assert_eq!(count_lis_by_key(&arr, order, |elem| {elem}), BigUint::from(expected_count), "{:?} {:?}", order, arr);
+                    assert_eq!(count_lis(&arr, order), BigUint::from(expected_count));
+                }
+                assert_eq!(lis(arr.clone()).length, lis_patience(&arr, Monotonicity::StrictlyIncreasing).length);
+                assert_eq!(
+                    lis_by_key(&arr, Monotonicity::StrictlyIncreasing, |&elem| {-elem}).length,
+                    lis_patience(&arr, Monotonicity::StrictlyDecreasing).length
+                );
+            }
+        }
+
+        #[test]
+        fn quadratic_lis_is_strictly_increasing() {
+            let mut rng = ChaChaDrbg::seed_from_u64(470);
+            for _i in 0..300 {
+                let arr = random_sequence(&mut rng, 11, 6);
+                let solution = lis(arr.clone());
+                let (length, _count) = brute_force(arr.len(), |indices| {follows(&arr, indices, |a, b| {a < b})});
+                assert_eq!(solution.length, length);
+                assert_eq!(solution.indices.len(), length);
+                assert!(follows(&arr, &solution.indices, |a, b| {a < b}));
+            }
+        }
+
+        #[test]
+        fn bitonic_matches_brute_force() {
+            let mut rng = ChaChaDrbg::seed_from_u64(4700);
+            for _i in 0..300 {
+                let arr = random_sequence(&mut rng, 11, 6);
+                let (length, _count) = brute_force(arr.len(), |indices| {is_bitonic(&arr, indices)});
+                for solution in [longest_bitonic(&arr), longest_bitonic_by_key(&arr, |elem| {elem})].iter() {
+                    assert_eq!(solution.length, length, "{:?}", arr);
+                    assert_eq!(solution.indices.len(), length);
+                    assert!(is_bitonic(&arr, &solution.indices), "{:?} {:?}", arr, solution.indices);
+                }
+            }
+        }
+
+        #[test]
+        fn count_of_many_longest_subsequences() {
+            //pairs (2i + 1, 2i): 2^20 increasing subsequences of length 20
+            let arr: Vec<i32> = (0..20).flat_map(|i| {vec![2 * i + 1, 2 * i]}).collect();
+            assert_eq!(count_lis(&arr, Monotonicity::StrictlyIncreasing), BigUint::from(1u32 << 20));
+            assert_eq!(count_lis(&arr, Monotonicity::StrictlyDecreasing), BigUint::from(20u32));
+            assert_eq!(count_lis::<i32>(&[], Monotonicity::NonDecreasing), BigUint::zero());
+        }
+
+        //whether the alignment walks both sequences in order, pairing only equal elems, along the subsequence
+        fn is_lcs_alignment<T: Eq + Clone + std::fmt::Debug>(seq_1: &[T], seq_2: &[T], solution: &LcsSolution<T>) -> bool {
+            let (mut i, mut j) = (0, 0);
+            let mut matched = Vec::new();
+            for pair in solution.alignment.iter() {
+                match *pair {
+                    (Some(a), Some(b)) if a == i && b == j && seq_1[a] == seq_2[b] => {
+                        matched.push(seq_1[a].clone());
+                        i += 1;
+                        j += 1;
+                    },
+                    (Some(a), None) if a == i => i += 1,
+                    (None, Some(b)) if b == j => j += 1,
+                    _ => return false
+                }
+            }
+            i == seq_1.len() && j == seq_2.len() && matched == solution.subsequence && solution.length == matched.len()
+        }
+
+        #[test]
+        fn hirschberg_lcs_matches_the_table() {
+            let mut rng = ChaChaDrbg::seed_from_u64(48);
+            for _i in 0..1000 {
+                let seq_1 = random_sequence(&mut rng, 30, 4);
+                let seq_2 = random_sequence(&mut rng, 30, 4);
+                let table = lcs_of(&seq_1, &seq_2);
+                let hirschberg = lcs_hirschberg(&seq_1, &seq_2);
+                assert_eq!(hirschberg.length, table.length, "{:?} {:?}", seq_1, seq_2);
+                assert_eq!(lcs_length(&seq_1, &seq_2), table.length);
+                assert!(is_lcs_alignment(&seq_1, &seq_2, &table));
+                assert!(is_lcs_alignment(&seq_1, &seq_2, &hirschberg), "{:?} {:?}", seq_1, seq_2);
+            }
+        }
+
+        #[test]
+        fn lcs_of_empty_sequences() {
+            let empty: Vec<char> = Vec::new();
+            let abc: Vec<char> = "abc".chars().collect();
+            for solution in [lcs_of(&empty, &abc), lcs_hirschberg(&empty, &abc)].iter() {
+                assert_eq!(solution.length, 0);
+                assert_eq!(solution.alignment, vec![(None, Some(0)), (None, Some(1)), (None, Some(2))]);
+            }
+            for solution in [lcs_of(&abc, &empty), lcs_hirschberg(&abc, &empty)].iter() {
+                assert_eq!(solution.alignment, vec![(Some(0), None), (Some(1), None), (Some(2), None)]);
+            }
+            assert_eq!(lcs(String::new(), String::new()), LcsSolution{length: 0, subsequence: vec![], alignment: vec![]});
+            assert_eq!(lcs_length::<char>(&[], &[]), 0);
+        }
+
+        #[test]
+        fn lcs_of_multi_byte_chars() {
+            //compared as chars, not bytes
+            let solution = lcs("日本語のテキスト".to_string(), "日本のテスト".to_string());
+            assert_eq!(solution.length, 6);
+            assert_eq!(solution.subsequence.iter().collect::<String>(), "日本のテスト");
+
+            let solution = lcs("naïve café 🦀".to_string(), "naive cafe 🦀".to_string());
+            assert_eq!(solution.subsequence.iter().collect::<String>(), "nave caf 🦀");
+            let chars_1: Vec<char> = "naïve café 🦀".chars().collect();
+            let chars_2: Vec<char> = "naive cafe 🦀".chars().collect();
+            assert!(is_lcs_alignment(&chars_1, &chars_2, &solution));
+            assert_eq!(lcs_hirschberg(&chars_1, &chars_2).length, 10);
+        }
+
+        //seq_1 with the operations done, and what they cost
+        fn apply(seq_1: &[i32], seq_2: &[i32], operations: &[EditOperation], costs: &EditCosts) -> (Vec<i32>, usize) {
+            let mut result = Vec::new();
+            let mut cost = 0;
+            let (mut i, mut j) = (0, 0);
+            for operation in operations.iter() {
+                match *operation {
+                    EditOperation::Keep(a, b) => {
+                        assert_eq!((a, b, seq_1[a]), (i, j, seq_2[b]));
+                        result.push(seq_1[a]);
+                        i += 1;
+                        j += 1;
+                    },
+                    EditOperation::Substitute(a, b) => {
+                        assert_eq!((a, b), (i, j));
+                        assert_ne!(seq_1[a], seq_2[b]);
+                        result.push(seq_2[b]);
+                        cost += costs.substitute;
+                        i += 1;
+                        j += 1;
+                    },
+                    EditOperation::Insert(b) => {
+                        assert_eq!(b, j);
+                        result.push(seq_2[b]);
+                        cost += costs.insert;
+                        j += 1;
+                    },
+                    EditOperation::Delete(a) => {
+                        assert_eq!(a, i);
+                        cost += costs.delete;
+                        i += 1;
                     }
                 }
             }
+            assert_eq!((i, j), (seq_1.len(), seq_2.len()));
+            (result, cost)
+        }
+
+        #[test]
+        fn edit_script_turns_one_sequence_into_the_other() {
+            let mut rng = ChaChaDrbg::seed_from_u64(490);
+            let all_costs = [
+                EditCosts::default(),
+                EditCosts{insert: 2, delete: 3, substitute: 1},
+                EditCosts{insert: 1, delete: 1, substitute: 3},
+                EditCosts{insert: 4, delete: 1, substitute: 2}
+            ];
+            for _i in 0..500 {
+                let seq_1 = random_sequence(&mut rng, 12, 4);
+                let seq_2 = random_sequence(&mut rng, 12, 4);
+                for costs in all_costs.iter() {
+                    let solution = edit_script(&seq_1, &seq_2, costs);
+                    assert_eq!(solution.distance, weighted_edit_distance(&seq_1, &seq_2, costs));
+                    assert_eq!(apply(&seq_1, &seq_2, &solution.operations, costs), (seq_2.clone(), solution.distance), "{:?} {:?}", seq_1, seq_2);
+                }
+            }
+        }
+
+        #[test]
+        fn weighted_edit_distances() {
+            let kitten: Vec<char> = "kitten".chars().collect();
+            let sitting: Vec<char> = "sitting".chars().collect();
+            assert_eq!(levenshtein(&kitten, &sitting), 3);
+            //with substitutions dearer than a delete and an insert, only "ittn" is kept
+            assert_eq!(weighted_edit_distance(&kitten, &sitting, &EditCosts{insert: 1, delete: 1, substitute: 3}), 5);
+            assert_eq!(weighted_edit_distance(&kitten, &sitting, &EditCosts{insert: 2, delete: 3, substitute: 1}), 4);
+            let costs = EditCosts{insert: 2, delete: 3, substitute: 1};
+            assert_eq!(weighted_edit_distance(&['a', 'b', 'c'], &[], &costs), 9);
+            assert_eq!(weighted_edit_distance(&[], &['a', 'b', 'c'], &costs), 6);
+            assert_eq!(weighted_edit_distance(&['a', 'b', 'c'], &['x', 'y', 'z'], &costs), 3);
+
+            //then the distance is the elems outside a longest common subsequence
+            let mut rng = ChaChaDrbg::seed_from_u64(491);
+            let costs = EditCosts{insert: 2, delete: 5, substitute: 7};
+            for _i in 0..300 {
+                let seq_1 = random_sequence(&mut rng, 15, 3);
+                let seq_2 = random_sequence(&mut rng, 15, 3);
+                let kept = lcs_length(&seq_1, &seq_2);
+                assert_eq!(weighted_edit_distance(&seq_1, &seq_2, &costs), (seq_2.len() - kept) * 2 + (seq_1.len() - kept) * 5);
+            }
+        }
+
+        #[test]
+        fn osa_and_damerau_levenshtein_differ_on_edited_swaps() {
+            let ca: Vec<char> = "ca".chars().collect();
+            let abc: Vec<char> = "abc".chars().collect();
+            assert_eq!(osa_distance(&ca, &abc), 3);
+            assert_eq!(damerau_levenshtein(&ca, &abc), 2);
+            assert_eq!(levenshtein(&ca, &abc), 3);
+
+            let (ab, ba): (Vec<char>, Vec<char>) = ("ab".chars().collect(), "ba".chars().collect());
+            assert_eq!((osa_distance(&ab, &ba), damerau_levenshtein(&ab, &ba), levenshtein(&ab, &ba)), (1, 1, 2));
+
+            let mut rng = ChaChaDrbg::seed_from_u64(492);
+            for _i in 0..1000 {
+                let seq_1 = random_sequence(&mut rng, 10, 3);
+                let seq_2 = random_sequence(&mut rng, 10, 3);
+                let (osa, dl) = (osa_distance(&seq_1, &seq_2), damerau_levenshtein(&seq_1, &seq_2));
+                assert!(dl <= osa && osa <= levenshtein(&seq_1, &seq_2), "{:?} {:?}", seq_1, seq_2);
+                assert_eq!(dl, damerau_levenshtein(&seq_2, &seq_1));
+            }
+        }
+
+        #[test]
+        fn lcs_alignment_of_the_clrs_example() {
+            let solution = lcs("ABCBDAB".to_string(), "BDCABA".to_string());
+            assert_eq!(solution.length, 4);
+            assert_eq!(solution.subsequence, vec!['B', 'C', 'B', 'A']);
+            assert_eq!(solution.alignment, vec![
+                (Some(0), None), (Some(1), Some(0)), (None, Some(1)), (Some(2), Some(2)), (None, Some(3)),
+                (Some(3), Some(4)), (Some(4), None), (Some(5), Some(5)), (Some(6), None)
+            ]);
+        }
+
+        //the items of the DPV example, (value, weight) = (30, 6), (14, 3), (16, 4), (9, 2)
+        fn dpv_items() -> Vec<Item<i32>> {
+            vec![Item::new(30, 6), Item::new(14, 3), Item::new(16, 4), Item::new(9, 2)]
+        }
+
+        #[test]
+        fn knapsack_chosen_items() {
+            let items = dpv_items();
+            assert_eq!(knapsack_no_rep(&items, 10), KnapsackSolution{value: 46, weight: 10, items: vec![(0, 1), (2, 1)]});
+            assert_eq!(knapsack_with_rep(&items, 10), KnapsackSolution{value: 48, weight: 10, items: vec![(0, 1), (3, 2)]});
+            assert_eq!(knapsack_with_rep(&items, 1), KnapsackSolution{value: 0, weight: 0, items: vec![]});
+            assert_eq!(knapsack_no_rep(&items, 0).items, vec![]);
+
+            //every subset of random items, against the table
+            let mut rng = ChaChaDrbg::seed_from_u64(46);
+            for _i in 0..200 {
+                let pairs: Vec<(i32, i32)> = (0..rng.gen_range(0..9)).map(|_j| {(rng.gen_range(0..20), rng.gen_range(1..10))}).collect();
+                let items: Vec<Item<i32>> = pairs.iter().map(|&(value, weight)| {Item::new(value, weight)}).collect();
+                let capacity = rng.gen_range(0..30);
+                let best = (0..1u32 << pairs.len()).filter_map(|mask| {
+                    let chosen = (0..pairs.len()).filter(|&i| {mask & 1 << i != 0});
+                    let (value, weight) = chosen.fold((0, 0), |(v, w), i| {(v + pairs[i].0, w + pairs[i].1)});
+                    if weight <= capacity {Some(value)} else {None}
+                }).max().unwrap();
+                let solution = knapsack_no_rep(&items, capacity);
+                assert_eq!(solution.value, best);
+                assert!(solution.items.iter().all(|&(_i, count)| {count == 1}));
+                assert_eq!(solution.items.iter().map(|&(i, _count)| {pairs[i].0}).sum::<i32>(), best);
+                assert_eq!(solution.items.iter().map(|&(i, _count)| {pairs[i].1}).sum::<i32>(), solution.weight);
+                assert!(solution.weight <= capacity);
+            }
         }
 
-        solution_space[1][m_arr.len()]                          //last entry
+        #[test]
+        fn knapsack_multiplicities() {
+            //3 of the (5, 3) item, none of the others
+            let items = vec![Item::new(5, 3), Item::new(6, 4), Item::new(1, 2)];
+            assert_eq!(knapsack_with_rep(&items, 9), KnapsackSolution{value: 15, weight: 9, items: vec![(0, 3)]});
+            //two (6, 4) fit where a (5, 3) leaves a gap
+            assert_eq!(knapsack_with_rep(&items, 8), KnapsackSolution{value: 12, weight: 8, items: vec![(1, 2)]});
+            let solution = knapsack_with_rep(&items, 11);
+            assert_eq!((solution.value, solution.weight), (17, 11));
+            assert_eq!(solution.items.iter().map(|&(i, count)| {items[i].value * count as i32}).sum::<i32>(), 17);
+        }
+
+        #[test]
+        fn chain_matrix_orders() {
+            let solution = chain_matrix_multiply(&[10, 30, 5, 60]);
+            assert_eq!(solution.cost, 4500);
+            assert_eq!(solution.order.to_string(), "((A1A2)A3)");
+
+            //the example of CLRS 15.2
+            let solution = chain_matrix_multiply(&[30, 35, 15, 5, 10, 20, 25]);
+            assert_eq!(solution.cost, 15125);
+            assert_eq!(solution.order.to_string(), "((A1(A2A3))((A4A5)A6))");
+            assert_eq!(chain_matrix_multiply(&[3, 4]), ChainSolution{cost: 0, order: Parenthesization::Matrix(1)});
+        }
+
+        #[test]
+        fn levenshtein_bounded_agrees_with_levenshtein() {
+            let mut rng = ChaChaDrbg::seed_from_u64(49);
+            for _i in 0..2000 {
+                let seq_1 = random_sequence(&mut rng, 12, 3);
+                let seq_2 = random_sequence(&mut rng, 12, 3);
+                let distance = levenshtein(&seq_1, &seq_2);
+                for max_distance in 0..=13 {
+                    let expected = if distance <= max_distance {Some(distance)} else {None};
+                    assert_eq!(levenshtein_bounded(&seq_1, &seq_2, max_distance), expected, "{:?} {:?}", seq_1, seq_2);
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,943 @@
+extern crate rand;
+extern crate num;
+use std::marker::PhantomData;
+use rand::{CryptoRng, RngCore};
+use num::{BigUint, Integer, Num, One, Zero};
+use crate::modular::modular_arith::{adc, limb_mask, montgomery_multiply_limbs, sbb};
+use crate::randomized::randomized_algos::random_big_below;
+use crate::hashing::hash_algos::*;
+use crate::encoding::der_pem::*;
+
+fn select_limbs(choice: u64, a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+    let m = limb_mask(choice);
+    [(a[0] & m) | (b[0] & !m), (a[1] & m) | (b[1] & !m), (a[2] & m) | (b[2] & !m), (a[3] & m) | (b[3] & !m)]
+}
+
+fn add_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut out = [0u64; 4];
+    let mut carry = 0;
+    for i in 0..4 {
+        let (limb, c) = adc(a[i], b[i], carry);
+        out[i] = limb;
+        carry = c;
+    }
+    (out, carry)
+}
+
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut out = [0u64; 4];
+    let mut borrow = 0;
+    for i in 0..4 {
+        let (limb, b_out) = sbb(a[i], b[i], borrow);
+        out[i] = limb;
+        borrow = b_out;
+    }
+    (out, borrow)
+}
+
+fn to_limbs(x: &BigUint) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for (limb, digit) in limbs.iter_mut().zip(x.to_u64_digits()) {
+        *limb = digit;
+    }
+    limbs
+}
+
+/**
+ * An element of a PrimeField, kept in Montgomery form (x * 2^256 mod p) as four 64-bit
+ * limbs, least significant first. The form is always fully reduced, so equal elements
+ * have equal limbs.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldElement([u64; 4]);
+
+impl FieldElement {
+    /**
+     * Whether the element is 0, found without branching on its limbs
+     */
+    pub fn is_zero(&self) -> bool {
+        let x = self.0[0] | self.0[1] | self.0[2] | self.0[3];
+        ((x | x.wrapping_neg()) >> 63) == 0
+    }
+}
+
+/**
+ * Swaps a and b if swap is 1 and leaves them if it is 0, with the same memory accesses
+ * and no branches either way, so the choice can be a secret bit.
+ */
+pub fn conditional_swap(a: &mut FieldElement, b: &mut FieldElement, swap: u64) {
+    let m = limb_mask(swap);
+    for i in 0..4 {
+        let t = m & (a.0[i] ^ b.0[i]);
+        a.0[i] ^= t;
+        b.0[i] ^= t;
+    }
+}
+
+/**
+ * a if choice is 1, b if it is 0, without branches
+ */
+pub fn conditional_select(a: &FieldElement, b: &FieldElement, choice: u64) -> FieldElement {
+    FieldElement(select_limbs(choice, &a.0, &b.0))
+}
+
+/**
+ * Arithmetic modulo an odd prime p below 2^256 on fixed-width numbers, for elliptic curves.
+ *
+ * Elements are four 64-bit limbs in Montgomery form, and multiplication is Montgomery's
+ * (CIOS): the product is reduced by adding the multiple of p that clears the low limb, one
+ * limb at a time, and shifting, which divides by 2^256 instead of by p. Every operation runs
+ * the same instructions whatever the values, with the final corrections done by masking
+ * instead of branching, so timing doesn't depend on secrets.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrimeField {
+    modulus: BigUint,
+    p: [u64; 4],
+    p_inv: u64,                 //-p^-1 mod 2^64
+    r2: [u64; 4]                //2^512 mod p, which takes a number into Montgomery form
+}
+
+impl PrimeField {
+    /**
+     * Panics if p is even, below 3 or longer than 256 bits. p is not checked to be prime,
+     * but inv and the curves rely on it.
+     */
+    pub fn new(p: &BigUint) -> Self {
+        if p.is_even() || p < &BigUint::from(3u32) || p.bits() > 256 {
+            panic!("The modulus must be an odd number from 3 to 2^256.");
+        }
+        let limbs = to_limbs(p);
+        //Newton's iteration doubles the correct low bits of the inverse each time
+        let mut inv: u64 = 1;
+        for _i in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(limbs[0].wrapping_mul(inv)));
+        }
+        let r2 = (BigUint::one() << 512u32) % p;
+        PrimeField{modulus: p.clone(), p: limbs, p_inv: inv.wrapping_neg(), r2: to_limbs(&r2)}
+    }
+
+    pub fn modulus(&self) -> &BigUint {
+        &self.modulus
+    }
+
+    /**
+     * The size of the modulus in bytes
+     */
+    pub fn size(&self) -> usize {
+        self.modulus.bits().div_ceil(8) as usize
+    }
+
+    //a * b / 2^256 mod p, for a * b < p * 2^256
+    fn montgomery_multiply(&self, a: &[u64; 4], b: &[u64; 4]) -> [u64; 4] {
+        let mut t = [0u64; 6];
+        let mut out = [0u64; 4];
+        montgomery_multiply_limbs(a, b, &self.p, self.p_inv, &mut t, &mut out);
+        out
+    }
+
+    //any x below 2^256, reduced and in Montgomery form
+    fn reduce_limbs(&self, x: &[u64; 4]) -> FieldElement {
+        FieldElement(self.montgomery_multiply(x, &self.r2))
+    }
+
+    pub fn zero(&self) -> FieldElement {
+        FieldElement([0; 4])
+    }
+
+    pub fn one(&self) -> FieldElement {
+        self.reduce_limbs(&[1, 0, 0, 0])
+    }
+
+    pub fn from_u64(&self, x: u64) -> FieldElement {
+        self.reduce_limbs(&[x, 0, 0, 0])
+    }
+
+    /**
+     * x mod p. The reduction goes through BigUint, so this is for public values;
+     * from_be_bytes and from_le_bytes reduce in constant time.
+     */
+    pub fn element(&self, x: &BigUint) -> FieldElement {
+        self.reduce_limbs(&to_limbs(&(x % &self.modulus)))
+    }
+
+    /**
+     * A 256-bit little-endian number mod p
+     */
+    pub fn from_le_bytes(&self, bytes: &[u8; 32]) -> FieldElement {
+        let mut limbs = [0u64; 4];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+            *limb = u64::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6], chunk[7]]);
+        }
+        self.reduce_limbs(&limbs)
+    }
+
+    /**
+     * A 256-bit big-endian number mod p
+     */
+    pub fn from_be_bytes(&self, bytes: &[u8; 32]) -> FieldElement {
+        let mut reversed = *bytes;
+        reversed.reverse();
+        self.from_le_bytes(&reversed)
+    }
+
+    /**
+     * A 512-bit little-endian number mod p, such as a hash reduced to a scalar:
+     * high * 2^256 + low, where 2^256 in Montgomery form is 2^512 mod p.
+     */
+    pub fn from_wide_le_bytes(&self, bytes: &[u8; 64]) -> FieldElement {
+        let mut low = [0u8; 32];
+        let mut high = [0u8; 32];
+        low.copy_from_slice(&bytes[..32]);
+        high.copy_from_slice(&bytes[32..]);
+        let high = self.mul(&self.from_le_bytes(&high), &FieldElement(self.r2));
+        self.add(&high, &self.from_le_bytes(&low))
+    }
+
+    pub fn to_le_bytes(&self, a: &FieldElement) -> [u8; 32] {
+        let limbs = self.montgomery_multiply(&a.0, &[1, 0, 0, 0]);
+        let mut bytes = [0u8; 32];
+        for (chunk, limb) in bytes.chunks_mut(8).zip(limbs.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn to_be_bytes(&self, a: &FieldElement) -> [u8; 32] {
+        let mut bytes = self.to_le_bytes(a);
+        bytes.reverse();
+        bytes
+    }
+
+    pub fn to_biguint(&self, a: &FieldElement) -> BigUint {
+        BigUint::from_bytes_le(&self.to_le_bytes(a))
+    }
+
+    pub fn add(&self, a: &FieldElement, b: &FieldElement) -> FieldElement {
+        let (sum, carry) = add_limbs(&a.0, &b.0);
+        let (reduced, borrow) = sub_limbs(&sum, &self.p);
+        FieldElement(select_limbs(carry | (borrow ^ 1), &reduced, &sum))
+    }
+
+    pub fn sub(&self, a: &FieldElement, b: &FieldElement) -> FieldElement {
+        let (difference, borrow) = sub_limbs(&a.0, &b.0);
+        let (corrected, _) = add_limbs(&difference, &select_limbs(borrow, &self.p, &[0; 4]));
+        FieldElement(corrected)
+    }
+
+    pub fn neg(&self, a: &FieldElement) -> FieldElement {
+        self.sub(&self.zero(), a)
+    }
+
+    pub fn mul(&self, a: &FieldElement, b: &FieldElement) -> FieldElement {
+        FieldElement(self.montgomery_multiply(&a.0, &b.0))
+    }
+
+    pub fn square(&self, a: &FieldElement) -> FieldElement {
+        self.mul(a, a)
+    }
+
+    /**
+     * a^exp by square-and-multiply. The multiplications depend on the bits of exp,
+     * so exp must be public, as it is for inversion and square roots.
+     */
+    pub fn pow(&self, a: &FieldElement, exp: &BigUint) -> FieldElement {
+        let mut result = self.one();
+        for i in (0..exp.bits()).rev() {
+            result = self.square(&result);
+            if ((exp >> i) & BigUint::one()).is_one() {
+                result = self.mul(&result, a);
+            }
+        }
+        result
+    }
+
+    /**
+     * a^-1 = a^(p-2) by Fermat's little theorem, and 0 for 0
+     */
+    pub fn inv(&self, a: &FieldElement) -> FieldElement {
+        self.pow(a, &(&self.modulus - 2u32))
+    }
+}
+
+/**
+ * A point of a curve in affine coordinates, or the point at infinity (the identity)
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AffinePoint {
+    Infinity,
+    Point(FieldElement, FieldElement)
+}
+
+/**
+ * A point (X / Z^2, Y / Z^3) in Jacobian coordinates, the point at infinity having Z = 0.
+ * Additions and doublings need no inversions, only one at the end to go back to affine.
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct JacobianPoint {
+    pub x: FieldElement,
+    pub y: FieldElement,
+    pub z: FieldElement
+}
+
+fn conditional_swap_points(a: &mut JacobianPoint, b: &mut JacobianPoint, swap: u64) {
+    conditional_swap(&mut a.x, &mut b.x, swap);
+    conditional_swap(&mut a.y, &mut b.y, swap);
+    conditional_swap(&mut a.z, &mut b.z, swap);
+}
+
+fn conditional_select_point(a: &JacobianPoint, b: &JacobianPoint, choice: u64) -> JacobianPoint {
+    JacobianPoint {
+        x: conditional_select(&a.x, &b.x, choice),
+        y: conditional_select(&a.y, &b.y, choice),
+        z: conditional_select(&a.z, &b.z, choice)
+    }
+}
+
+//a scalar as 32 big-endian bytes
+fn scalar_bytes(k: &BigUint) -> [u8; 32] {
+    let digits = k.to_bytes_be();
+    let mut bytes = [0u8; 32];
+    bytes[32 - digits.len()..].copy_from_slice(&digits);
+    bytes
+}
+
+/**
+ * A curve y^2 = x^3 + ax + b over a prime field, with a generator G of prime order n.
+ * The scalars field does arithmetic modulo n.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeierstrassCurve {
+    pub field: PrimeField,
+    pub scalars: PrimeField,
+    pub a: FieldElement,
+    pub b: FieldElement,
+    pub generator: AffinePoint,
+    pub order: BigUint
+}
+
+impl WeierstrassCurve {
+    /**
+     * Panics if the generator (gx, gy) isn't on the curve
+     */
+    pub fn new(p: &BigUint, a: &BigUint, b: &BigUint, gx: &BigUint, gy: &BigUint, order: &BigUint) -> Self {
+        let field = PrimeField::new(p);
+        let mut curve = WeierstrassCurve {
+            a: field.element(a),
+            b: field.element(b),
+            generator: AffinePoint::Infinity,
+            scalars: PrimeField::new(order),
+            order: order.clone(),
+            field
+        };
+        curve.generator = curve.point(gx, gy).expect("The generator must be on the curve.");
+        curve
+    }
+
+    /**
+     * NIST P-256 (secp256r1) from FIPS 186-4, with a = -3 and cofactor 1
+     */
+    pub fn p256() -> Self {
+        let hex = |s: &str| {BigUint::from_str_radix(s, 16).unwrap()};
+        let p = hex("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff");
+        WeierstrassCurve::new(
+            &p,
+            &(&p - 3u32),
+            &hex("5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b"),
+            &hex("6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"),
+            &hex("4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"),
+            &hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551")
+        )
+    }
+
+    /**
+     * The point (x, y), or None if it isn't on the curve
+     */
+    pub fn point(&self, x: &BigUint, y: &BigUint) -> Option<AffinePoint> {
+        if x >= self.field.modulus() || y >= self.field.modulus() {
+            return None;
+        }
+        let point = AffinePoint::Point(self.field.element(x), self.field.element(y));
+        if self.contains(&point) {
+            Some(point)
+        } else {
+            None
+        }
+    }
+
+    /**
+     * The affine coordinates of a point, None for the point at infinity
+     */
+    pub fn coordinates(&self, point: &AffinePoint) -> Option<(BigUint, BigUint)> {
+        match point {
+            AffinePoint::Infinity => None,
+            AffinePoint::Point(x, y) => Some((self.field.to_biguint(x), self.field.to_biguint(y)))
+        }
+    }
+
+    //x^3 + ax + b
+    fn right_side(&self, x: &FieldElement) -> FieldElement {
+        let f = &self.field;
+        let x3 = f.mul(&f.square(x), x);
+        f.add(&f.add(&x3, &f.mul(&self.a, x)), &self.b)
+    }
+
+    pub fn contains(&self, point: &AffinePoint) -> bool {
+        match point {
+            AffinePoint::Infinity => true,
+            AffinePoint::Point(x, y) => self.field.square(y) == self.right_side(x)
+        }
+    }
+
+    pub fn negate(&self, point: &AffinePoint) -> AffinePoint {
+        match point {
+            AffinePoint::Infinity => AffinePoint::Infinity,
+            AffinePoint::Point(x, y) => AffinePoint::Point(*x, self.field.neg(y))
+        }
+    }
+
+    /**
+     * P + Q with the chord rule: the line through P and Q meets the curve in a third
+     * point, and the sum is its reflection. Uses one inversion.
+     */
+    pub fn add_affine(&self, p: &AffinePoint, q: &AffinePoint) -> AffinePoint {
+        let f = &self.field;
+        match (p, q) {
+            (AffinePoint::Infinity, _) => *q,
+            (_, AffinePoint::Infinity) => *p,
+            (AffinePoint::Point(x1, y1), AffinePoint::Point(x2, y2)) => {
+                if x1 == x2 {
+                    return if y1 == y2 {self.double_affine(p)} else {AffinePoint::Infinity};
+                }
+                let slope = f.mul(&f.sub(y2, y1), &f.inv(&f.sub(x2, x1)));
+                let x3 = f.sub(&f.sub(&f.square(&slope), x1), x2);
+                let y3 = f.sub(&f.mul(&slope, &f.sub(x1, &x3)), y1);
+                AffinePoint::Point(x3, y3)
+            }
+        }
+    }
+
+    /**
+     * 2P with the tangent rule, slope (3x^2 + a) / 2y
+     */
+    pub fn double_affine(&self, p: &AffinePoint) -> AffinePoint {
+        let f = &self.field;
+        match p {
+            AffinePoint::Infinity => AffinePoint::Infinity,
+            AffinePoint::Point(x, y) => {
+                if y.is_zero() {
+                    return AffinePoint::Infinity;
+                }
+                let x_squared = f.square(x);
+                let numerator = f.add(&f.add(&f.add(&x_squared, &x_squared), &x_squared), &self.a);
+                let slope = f.mul(&numerator, &f.inv(&f.add(y, y)));
+                let x3 = f.sub(&f.square(&slope), &f.add(x, x));
+                let y3 = f.sub(&f.mul(&slope, &f.sub(x, &x3)), y);
+                AffinePoint::Point(x3, y3)
+            }
+        }
+    }
+
+    pub fn to_jacobian(&self, p: &AffinePoint) -> JacobianPoint {
+        match p {
+            AffinePoint::Infinity => JacobianPoint{x: self.field.one(), y: self.field.one(), z: self.field.zero()},
+            AffinePoint::Point(x, y) => JacobianPoint{x: *x, y: *y, z: self.field.one()}
+        }
+    }
+
+    pub fn to_affine(&self, p: &JacobianPoint) -> AffinePoint {
+        let f = &self.field;
+        if p.z.is_zero() {
+            return AffinePoint::Infinity;
+        }
+        let z_inv = f.inv(&p.z);
+        let z_inv_squared = f.square(&z_inv);
+        AffinePoint::Point(f.mul(&p.x, &z_inv_squared), f.mul(&p.y, &f.mul(&z_inv_squared, &z_inv)))
+    }
+
+    /**
+     * 2P in Jacobian coordinates (dbl-2007-bl). The point at infinity and points with
+     * y = 0 give Z = 0 without special cases.
+     */
+    pub fn double_jacobian(&self, p: &JacobianPoint) -> JacobianPoint {
+        let f = &self.field;
+        let xx = f.square(&p.x);
+        let yy = f.square(&p.y);
+        let yyyy = f.square(&yy);
+        let zz = f.square(&p.z);
+        let s = f.sub(&f.sub(&f.square(&f.add(&p.x, &yy)), &xx), &yyyy);
+        let s = f.add(&s, &s);
+        let m = f.add(&f.add(&f.add(&xx, &xx), &xx), &f.mul(&self.a, &f.square(&zz)));
+        let x3 = f.sub(&f.square(&m), &f.add(&s, &s));
+        let eight_yyyy = f.add(&yyyy, &yyyy);
+        let eight_yyyy = f.add(&eight_yyyy, &eight_yyyy);
+        let eight_yyyy = f.add(&eight_yyyy, &eight_yyyy);
+        let y3 = f.sub(&f.mul(&m, &f.sub(&s, &x3)), &eight_yyyy);
+        let z3 = f.sub(&f.sub(&f.square(&f.add(&p.y, &p.z)), &yy), &zz);
+        JacobianPoint{x: x3, y: y3, z: z3}
+    }
+
+    /**
+     * P + Q in Jacobian coordinates (add-2007-bl). The formula fails when either point is
+     * at infinity or P = Q, so those results are computed too and picked with masks:
+     * every call does the same work, which the ladder in multiply relies on.
+     */
+    pub fn add_jacobian(&self, p: &JacobianPoint, q: &JacobianPoint) -> JacobianPoint {
+        let f = &self.field;
+        let z1z1 = f.square(&p.z);
+        let z2z2 = f.square(&q.z);
+        let u1 = f.mul(&p.x, &z2z2);
+        let u2 = f.mul(&q.x, &z1z1);
+        let s1 = f.mul(&f.mul(&p.y, &q.z), &z2z2);
+        let s2 = f.mul(&f.mul(&q.y, &p.z), &z1z1);
+        let h = f.sub(&u2, &u1);
+        let i = f.square(&f.add(&h, &h));
+        let j = f.mul(&h, &i);
+        let r = f.sub(&s2, &s1);
+        let r = f.add(&r, &r);
+        let v = f.mul(&u1, &i);
+        let x3 = f.sub(&f.sub(&f.square(&r), &j), &f.add(&v, &v));
+        let s1j = f.mul(&s1, &j);
+        let y3 = f.sub(&f.mul(&r, &f.sub(&v, &x3)), &f.add(&s1j, &s1j));
+        let z3 = f.mul(&f.sub(&f.sub(&f.square(&f.add(&p.z, &q.z)), &z1z1), &z2z2), &h);
+        let sum = JacobianPoint{x: x3, y: y3, z: z3};
+
+        let doubled = self.double_jacobian(p);
+        let p_infinite = p.z.is_zero() as u64;
+        let q_infinite = q.z.is_zero() as u64;
+        let equal = (h.is_zero() as u64) & (r.is_zero() as u64) & (p_infinite ^ 1) & (q_infinite ^ 1);
+        let result = conditional_select_point(&doubled, &sum, equal);
+        let result = conditional_select_point(p, &result, q_infinite);
+        conditional_select_point(q, &result, p_infinite)
+    }
+
+    /**
+     * kP with the Montgomery ladder: R0 = jP and R1 = (j+1)P for the scalar j read so far,
+     * and each bit b of k turns them into R_b + R_(1-b) and 2R_b. Every bit costs one
+     * addition and one doubling, and which register gets which result is a masked swap,
+     * so the sequence of operations doesn't depend on k.
+     *
+     * k is reduced modulo the order n and all n.bits() bits are processed, so P must be
+     * in the group generated by G, which is every point of the curve when the cofactor is 1.
+     */
+    pub fn multiply(&self, k: &BigUint, p: &AffinePoint) -> AffinePoint {
+        let bytes = scalar_bytes(&(k % &self.order));
+        let mut r0 = self.to_jacobian(&AffinePoint::Infinity);
+        let mut r1 = self.to_jacobian(p);
+        for i in (0..self.order.bits() as usize).rev() {
+            let bit = ((bytes[31 - i / 8] >> (i % 8)) & 1) as u64;
+            conditional_swap_points(&mut r0, &mut r1, bit);
+            r1 = self.add_jacobian(&r0, &r1);
+            r0 = self.double_jacobian(&r0);
+            conditional_swap_points(&mut r0, &mut r1, bit);
+        }
+        self.to_affine(&r0)
+    }
+
+    /**
+     * The SEC 1 encoding of a point: 0x04 || x || y, or 0x02 / 0x03 (for even / odd y) || x
+     * when compressed, and 0x00 for the point at infinity
+     */
+    pub fn encode_point(&self, point: &AffinePoint, compressed: bool) -> Vec<u8> {
+        let size = self.field.size();
+        match point {
+            AffinePoint::Infinity => vec![0x00],
+            AffinePoint::Point(x, y) => {
+                let y_bytes = self.field.to_be_bytes(y);
+                let mut out = if compressed {vec![0x02 | (y_bytes[31] & 1)]} else {vec![0x04]};
+                out.extend_from_slice(&self.field.to_be_bytes(x)[32 - size..]);
+                if !compressed {
+                    out.extend_from_slice(&y_bytes[32 - size..]);
+                }
+                out
+            }
+        }
+    }
+
+    /**
+     * Parses a SEC 1 encoded point, see encode_point. Returns None if it isn't on the curve.
+     * Compressed points need a square root, which is only implemented for p = 3 (mod 4),
+     * as for P-256: y = (x^3 + ax + b)^((p+1)/4).
+     */
+    pub fn decode_point(&self, bytes: &[u8]) -> Option<AffinePoint> {
+        let size = self.field.size();
+        let (&tag, rest) = bytes.split_first()?;
+        match tag {
+            0x00 if rest.is_empty() => Some(AffinePoint::Infinity),
+            0x04 if rest.len() == 2 * size => {
+                self.point(&BigUint::from_bytes_be(&rest[..size]), &BigUint::from_bytes_be(&rest[size..]))
+            },
+            0x02 | 0x03 if rest.len() == size => {
+                let p = self.field.modulus();
+                if p % 4u32 != BigUint::from(3u32) {
+                    return None;
+                }
+                let x = BigUint::from_bytes_be(rest);
+                if &x >= p {
+                    return None;
+                }
+                let x = self.field.element(&x);
+                let right_side = self.right_side(&x);
+                let mut y = self.field.pow(&right_side, &((p + 1u32) >> 2));
+                if self.field.square(&y) != right_side {
+                    return None;
+                }
+                if (self.field.to_be_bytes(&y)[31] & 1) != (tag & 1) {
+                    y = self.field.neg(&y);
+                }
+                Some(AffinePoint::Point(x, y))
+            },
+            _ => None
+        }
+    }
+}
+
+//the leftmost bits of a hash as an integer (bits2int in RFC 6979)
+fn bits_to_int(bytes: &[u8], bits: u64) -> BigUint {
+    let x = BigUint::from_bytes_be(bytes);
+    let length = bytes.len() as u64 * 8;
+    if length > bits {
+        x >> (length - bits)
+    } else {
+        x
+    }
+}
+
+//a number as exactly len big-endian bytes (int2octets in RFC 6979)
+fn int_to_bytes(x: &BigUint, len: usize) -> Vec<u8> {
+    let digits = x.to_bytes_be();
+    let mut out = vec![0u8; len - digits.len()];
+    out.extend(digits);
+    out
+}
+
+/**
+ * The deterministic nonces of RFC 6979 for DSA and ECDSA: an HMAC-DRBG over hash H,
+ * seeded with the private key and the hash of the message, so the same key and message
+ * always give the same nonce while different messages give unrelated ones. A signature
+ * then needs no random generator, and a bad one can't leak the key.
+ *
+ * The iterator yields candidates in 1..order; callers take the next one if a candidate
+ * gives r = 0 or s = 0.
+ */
+pub struct Rfc6979<H: Digest> {
+    key: Vec<u8>,
+    v: Vec<u8>,
+    order: BigUint,
+    started: bool,
+    hash: PhantomData<H>
+}
+
+impl<H: Digest> Rfc6979<H> {
+    pub fn new(order: &BigUint, secret: &BigUint, message_hash: &[u8]) -> Self {
+        let len = order.bits().div_ceil(8) as usize;
+        let x = int_to_bytes(secret, len);
+        let h = int_to_bytes(&(bits_to_int(message_hash, order.bits()) % order), len);
+        let mut key = vec![0x00u8; H::OUTPUT_SIZE];
+        let mut v = vec![0x01u8; H::OUTPUT_SIZE];
+        for marker in [0x00u8, 0x01] {
+            let mut mac = Hmac::<H>::new(&key);
+            mac.update(&v);
+            mac.update(&[marker]);
+            mac.update(&x);
+            mac.update(&h);
+            key = mac.finalize();
+            v = hmac::<H>(&key, &v);
+        }
+        Rfc6979{key, v, order: order.clone(), started: false, hash: PhantomData}
+    }
+}
+
+impl<H: Digest> Iterator for Rfc6979<H> {
+    type Item = BigUint;
+
+    fn next(&mut self) -> Option<BigUint> {
+        let len = self.order.bits().div_ceil(8) as usize;
+        loop {
+            if self.started {
+                let mut mac = Hmac::<H>::new(&self.key);
+                mac.update(&self.v);
+                mac.update(&[0x00]);
+                self.key = mac.finalize();
+                self.v = hmac::<H>(&self.key, &self.v);
+            }
+            self.started = true;
+
+            let mut t: Vec<u8> = Vec::with_capacity(len + H::OUTPUT_SIZE);
+            while t.len() < len {
+                self.v = hmac::<H>(&self.key, &self.v);
+                t.extend_from_slice(&self.v);
+            }
+            let k = bits_to_int(&t, self.order.bits());
+            if !k.is_zero() && k < self.order {
+                return Some(k);
+            }
+        }
+    }
+}
+
+/**
+ * An ECDSA signature (r, s), both in 1..n
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EcdsaSignature {
+    pub r: BigUint,
+    pub s: BigUint
+}
+
+impl EcdsaSignature {
+    /**
+     * The DER encoding used by X.509 and TLS, SEQUENCE { r INTEGER, s INTEGER }
+     */
+    pub fn to_der(&self) -> Vec<u8> {
+        Der::Sequence(vec![Der::Integer(self.r.clone()), Der::Integer(self.s.clone())]).encode().unwrap()
+    }
+
+    pub fn from_der(der: &[u8]) -> Option<Self> {
+        match Der::decode(der)? {
+            Der::Sequence(items) => match items.as_slice() {
+                [Der::Integer(r), Der::Integer(s)] => Some(EcdsaSignature{r: r.clone(), s: s.clone()}),
+                _ => None
+            },
+            _ => None
+        }
+    }
+}
+
+/**
+ * A public key, the point Q = dG
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EcPublicKey {
+    pub curve: WeierstrassCurve,
+    pub point: AffinePoint
+}
+
+/**
+ * A private key, the scalar d in 1..n
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EcPrivateKey {
+    pub curve: WeierstrassCurve,
+    pub d: BigUint
+}
+
+impl EcPrivateKey {
+    /**
+     * A new key with d drawn uniformly from 1..n
+     */
+    pub fn generate<R: RngCore + CryptoRng + ?Sized>(curve: &WeierstrassCurve, rng: &mut R) -> Self {
+        let d = random_big_below(&(&curve.order - 1u32), rng) + 1u32;
+        EcPrivateKey{curve: curve.clone(), d}
+    }
+
+    /**
+     * The key for a given scalar, None unless it is in 1..n
+     */
+    pub fn from_scalar(curve: &WeierstrassCurve, d: BigUint) -> Option<Self> {
+        if d.is_zero() || d >= curve.order {
+            return None;
+        }
+        Some(EcPrivateKey{curve: curve.clone(), d})
+    }
+
+    pub fn public_key(&self) -> EcPublicKey {
+        EcPublicKey{curve: self.curve.clone(), point: self.curve.multiply(&self.d, &self.curve.generator)}
+    }
+
+    /**
+     * ECDH: the x coordinate of dQ for the other party's point Q, as big-endian bytes of
+     * the field size. Both sides get x(d1 * d2 * G). It should go through a key derivation
+     * function like hkdf before being used as a key.
+     *
+     * Returns None if the keys are on different curves or the other point is invalid.
+     */
+    pub fn diffie_hellman(&self, other: &EcPublicKey) -> Option<Vec<u8>> {
+        if other.curve != self.curve || !other.is_valid() {
+            return None;
+        }
+        match self.curve.multiply(&self.d, &other.point) {
+            AffinePoint::Infinity => None,
+            AffinePoint::Point(x, _) => {
+                let size = self.curve.field.size();
+                Some(self.curve.field.to_be_bytes(&x)[32 - size..].to_vec())
+            }
+        }
+    }
+
+    /**
+     * ECDSA signing with hash H and the deterministic nonce k of RFC 6979:
+     * r = x(kG) mod n and s = k^-1 * (z + r * d) mod n, where z is the leftmost bits of
+     * H(message). The scalar arithmetic is done in constant time in the field modulo n.
+     */
+    pub fn sign_ecdsa<H: Digest>(&self, message: &[u8]) -> EcdsaSignature {
+        let curve = &self.curve;
+        let scalars = &curve.scalars;
+        let hash = H::digest(message);
+        let z = scalars.element(&bits_to_int(&hash, curve.order.bits()));
+        let d = scalars.from_be_bytes(&scalar_bytes(&self.d));
+
+        let mut nonces = Rfc6979::<H>::new(&curve.order, &self.d, &hash);
+        loop {
+            let k = nonces.next().unwrap();
+            let x = match curve.multiply(&k, &curve.generator) {
+                AffinePoint::Infinity => continue,
+                AffinePoint::Point(x, _) => curve.field.to_biguint(&x)
+            };
+            let r = x % &curve.order;
+            if r.is_zero() {
+                continue;
+            }
+            let k_inv = scalars.inv(&scalars.from_be_bytes(&scalar_bytes(&k)));
+            let s = scalars.mul(&k_inv, &scalars.add(&z, &scalars.mul(&scalars.element(&r), &d)));
+            if !s.is_zero() {
+                return EcdsaSignature{r, s: scalars.to_biguint(&s)};
+            }
+        }
+    }
+}
+
+impl EcPublicKey {
+    /**
+     * Whether the point is on the curve and not at infinity. On curves with cofactor 1,
+     * like P-256, that also puts it in the group generated by G.
+     */
+    pub fn is_valid(&self) -> bool {
+        self.point != AffinePoint::Infinity && self.curve.contains(&self.point)
+    }
+
+    /**
+     * The key from a SEC 1 encoded point, None if it is invalid
+     */
+    pub fn from_bytes(curve: &WeierstrassCurve, bytes: &[u8]) -> Option<Self> {
+        let key = EcPublicKey{curve: curve.clone(), point: curve.decode_point(bytes)?};
+        if key.is_valid() {
+            Some(key)
+        } else {
+            None
+        }
+    }
+
+    /**
+     * The uncompressed SEC 1 encoding of the point
+     */
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.curve.encode_point(&self.point, false)
+    }
+
+    /**
+     * ECDSA verification: with w = s^-1 mod n, the signature is valid if the point
+     * (z * w)G + (r * w)Q isn't at infinity and its x coordinate is r modulo n.
+     */
+    pub fn verify_ecdsa<H: Digest>(&self, message: &[u8], signature: &EcdsaSignature) -> bool {
+        let curve = &self.curve;
+        let n = &curve.order;
+        let EcdsaSignature{r, s} = signature;
+        if r.is_zero() || r >= n || s.is_zero() || s >= n || !self.is_valid() {
+            return false;
+        }
+        let scalars = &curve.scalars;
+        let z = scalars.element(&bits_to_int(&H::digest(message), n.bits()));
+        let w = scalars.inv(&scalars.element(s));
+        let u1 = scalars.to_biguint(&scalars.mul(&z, &w));
+        let u2 = scalars.to_biguint(&scalars.mul(&scalars.element(r), &w));
+        let sum = curve.add_affine(&curve.multiply(&u1, &curve.generator), &curve.multiply(&u2, &self.point));
+        match curve.coordinates(&sum) {
+            None => false,
+            Some((x, _)) => &(x % n) == r
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::drbg::chacha_drbg::ChaChaDrbg;
+
+    fn number(hex: &str) -> BigUint {
+        BigUint::from_str_radix(hex, 16).unwrap()
+    }
+
+    //the P-256 key of RFC 6979, A.2.5
+    fn rfc6979_key() -> EcPrivateKey {
+        let curve = WeierstrassCurve::p256();
+        EcPrivateKey::from_scalar(&curve, number("C9AFA9D845BA75166B5C215767B1D6934E50C3DB36E89B127B8A622B120F6721")).unwrap()
+    }
+
+    #[test]
+    fn rfc6979_public_key() {
+        let key = rfc6979_key();
+        let (x, y) = key.curve.coordinates(&key.public_key().point).unwrap();
+        assert_eq!(x, number("60FED4BA255A9D31C961EB74C6356D68C049B8923B61FA6CE669622E60F29FB6"));
+        assert_eq!(y, number("7903FE1008B8BC99A41AE9E95628BC64F2F1B20C2D7E9F5177A3C294D4462299"));
+    }
+
+    #[test]
+    fn rfc6979_sha256_sample() {
+        let key = rfc6979_key();
+        let signature = key.sign_ecdsa::<Sha256>(b"sample");
+        assert_eq!(signature.r, number("EFD48B2AACB6A8FD1140DD9CD45E81D69D2C877B56AAF991C34D0EA84EAF3716"));
+        assert_eq!(signature.s, number("F7CB1C942D657C41D436C7A1B6E29F65F3E900DBB9AFF4064DC4AB2F843ACDA8"));
+        assert!(key.public_key().verify_ecdsa::<Sha256>(b"sample", &signature));
+        assert!(!key.public_key().verify_ecdsa::<Sha256>(b"samples", &signature));
+    }
+
+    #[test]
+    fn rfc6979_sha512_test() {
+        let key = rfc6979_key();
+        let signature = key.sign_ecdsa::<Sha512>(b"test");
+        assert_eq!(signature.r, number("461D93F31B6540894788FD206C07CFA0CC35F46FA3C91816FFF1040AD1581A04"));
+        assert_eq!(signature.s, number("39AF9F15DE0DB8D97E72719C74820D304CE5226E32DEDAE67519E840D1194E55"));
+        assert!(key.public_key().verify_ecdsa::<Sha512>(b"test", &signature));
+        assert_eq!(EcdsaSignature::from_der(&signature.to_der()), Some(signature));
+    }
+
+    #[test]
+    fn compressed_point_round_trip() {
+        let curve = WeierstrassCurve::p256();
+        let mut rng = ChaChaDrbg::seed_from_u64(43);
+        for _i in 0..20 {
+            let point = EcPrivateKey::generate(&curve, &mut rng).public_key().point;
+            let compressed = curve.encode_point(&point, true);
+            let uncompressed = curve.encode_point(&point, false);
+            assert_eq!(compressed.len(), 33);
+            assert_eq!(uncompressed.len(), 65);
+            assert_eq!(curve.decode_point(&compressed), Some(point));
+            assert_eq!(curve.decode_point(&uncompressed), Some(point));
+            //the other tag gives the negated point
+            let mut flipped = compressed.clone();
+            flipped[0] ^= 1;
+            assert_eq!(curve.decode_point(&flipped), Some(curve.negate(&point)));
+        }
+        assert_eq!(curve.decode_point(&curve.encode_point(&AffinePoint::Infinity, true)), Some(AffinePoint::Infinity));
+    }
+
+    #[test]
+    fn p256_ecdh_agreement() {
+        let curve = WeierstrassCurve::p256();
+        let mut rng = ChaChaDrbg::seed_from_u64(256);
+        for _i in 0..10 {
+            let alice = EcPrivateKey::generate(&curve, &mut rng);
+            let bob = EcPrivateKey::generate(&curve, &mut rng);
+            let shared = alice.diffie_hellman(&bob.public_key()).unwrap();
+            assert_eq!(shared.len(), 32);
+            assert_eq!(bob.diffie_hellman(&alice.public_key()), Some(shared));
+        }
+
+        //checked against another implementation
+        let alice = rfc6979_key();
+        let bob = EcPrivateKey::from_scalar(&curve, number("0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF")).unwrap();
+        let expected = number("8C339726B1D968756182352FC15018109527F618C7EE1DE136728624EDD2AFE3").to_bytes_be();
+        assert_eq!(alice.diffie_hellman(&bob.public_key()), Some(expected.clone()));
+        assert_eq!(bob.diffie_hellman(&alice.public_key()), Some(expected));
+
+        //points off the curve or at infinity are refused
+        let mut invalid = bob.public_key();
+        invalid.point = AffinePoint::Infinity;
+        assert_eq!(alice.diffie_hellman(&invalid), None);
+        let (x, y) = curve.coordinates(&bob.public_key().point).unwrap();
+        invalid.point = AffinePoint::Point(curve.field.element(&x), curve.field.element(&(y + 1u32)));
+        assert_eq!(alice.diffie_hellman(&invalid), None);
+    }
+}
@@ -0,0 +1,383 @@
+pub mod der_pem {
+    extern crate num;
+    use num::BigUint;
+
+    /**
+     * The ASN.1 values needed for key formats, in their DER (Distinguished Encoding Rules)
+     * form. Only non-negative integers are supported.
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum Der {
+        Integer(BigUint),
+        BitString(Vec<u8>),             //a whole number of bytes, no unused bits
+        OctetString(Vec<u8>),
+        Null,
+        ObjectIdentifier(Vec<u64>),
+        Sequence(Vec<Der>)
+    }
+
+    const INTEGER: u8 = 0x02;
+    const BIT_STRING: u8 = 0x03;
+    const OCTET_STRING: u8 = 0x04;
+    const NULL: u8 = 0x05;
+    const OBJECT_IDENTIFIER: u8 = 0x06;
+    const SEQUENCE: u8 = 0x30;
+
+    //the length in short form below 128, otherwise 0x80 + number of length bytes, then the bytes
+    fn encode_length(len: usize, out: &mut Vec<u8>) {
+        if len < 0x80 {
+            out.push(len as u8);
+        } else {
+            let bytes: Vec<u8> = len.to_be_bytes().iter().cloned().skip_while(|&b| {b == 0}).collect();
+            out.push(0x80 | bytes.len() as u8);
+            out.extend(bytes);
+        }
+    }
+
+    impl Der {
+        /**
+         * The DER encoding: tag byte, length, contents. Returns None if an object identifier
+         * can't be encoded: it needs at least two arcs, a first arc of 0, 1 or 2, and a
+         * second arc below 40 unless the first is 2.
+         */
+        pub fn encode(&self) -> Option<Vec<u8>> {
+            let (tag, contents) = match self {
+                Der::Integer(x) => {
+                    //two's complement, so a leading 1 bit needs a 0x00 in front
+                    let mut bytes = x.to_bytes_be();
+                    if bytes[0] & 0x80 != 0 {
+                        bytes.insert(0, 0);
+                    }
+                    (INTEGER, bytes)
+                },
+                Der::BitString(bytes) => {
+                    let mut contents = vec![0];
+                    contents.extend_from_slice(bytes);
+                    (BIT_STRING, contents)
+                },
+                Der::OctetString(bytes) => (OCTET_STRING, bytes.clone()),
+                Der::Null => (NULL, Vec::new()),
+                Der::ObjectIdentifier(arcs) => {
+                    //the first two arcs share a byte, the rest are base 128 with continuation bits
+                    if arcs.len() < 2 || arcs[0] > 2 || (arcs[0] < 2 && arcs[1] >= 40) {
+                        return None;
+                    }
+                    let mut contents: Vec<u8> = Vec::new();
+                    let mut values = vec![(arcs[0] * 40).checked_add(arcs[1])?];
+                    values.extend_from_slice(&arcs[2..]);
+                    for value in values {
+                        let mut groups = vec![(value & 0x7f) as u8];
+                        let mut rest = value >> 7;
+                        while rest > 0 {
+                            groups.push((rest & 0x7f) as u8 | 0x80);
+                            rest >>= 7;
+                        }
+                        contents.extend(groups.iter().rev());
+                    }
+                    (OBJECT_IDENTIFIER, contents)
+                },
+                Der::Sequence(items) => {
+                    let encoded = items.iter().map(|item| {item.encode()}).collect::<Option<Vec<Vec<u8>>>>()?;
+                    (SEQUENCE, encoded.concat())
+                }
+            };
+            let mut out = vec![tag];
+            encode_length(contents.len(), &mut out);
+            out.extend(contents);
+            Some(out)
+        }
+
+        /**
+         * Parses exactly one DER value filling the whole input. Returns None for malformed
+         * input and for anything DER forbids, like lengths or integers that aren't written
+         * in the fewest bytes.
+         */
+        pub fn decode(input: &[u8]) -> Option<Der> {
+            let (value, rest) = Der::decode_prefix(input)?;
+            if rest.is_empty() {
+                Some(value)
+            } else {
+                None
+            }
+        }
+
+        //one value from the front of the input and what follows it
+        fn decode_prefix(input: &[u8]) -> Option<(Der, &[u8])> {
+            let (&tag, rest) = input.split_first()?;
+            let (&first, rest) = rest.split_first()?;
+            let (len, rest) = if first < 0x80 {
+                (first as usize, rest)
+            } else {
+                let num_bytes = (first & 0x7f) as usize;
+                if num_bytes == 0 || num_bytes > std::mem::size_of::<usize>() || rest.len() < num_bytes || rest[0] == 0 {
+                    return None;
+                }
+                let len = rest[..num_bytes].iter().fold(0usize, |acc, &b| {(acc << 8) | b as usize});
+                if len < 0x80 {
+                    return None;
+                }
+                (len, &rest[num_bytes..])
+            };
+            if rest.len() < len {
+                return None;
+            }
+            let (contents, rest) = rest.split_at(len);
+
+            let value = match tag {
+                INTEGER => {
+                    if contents.is_empty() || contents[0] & 0x80 != 0 {
+                        return None;
+                    }
+                    if contents.len() > 1 && contents[0] == 0 && contents[1] & 0x80 == 0 {
+                        return None;
+                    }
+                    Der::Integer(BigUint::from_bytes_be(contents))
+                },
+                BIT_STRING => {
+                    if contents.first() != Some(&0) {
+                        return None;
+                    }
+                    Der::BitString(contents[1..].to_vec())
+                },
+                OCTET_STRING => Der::OctetString(contents.to_vec()),
+                NULL => {
+                    if !contents.is_empty() {
+                        return None;
+                    }
+                    Der::Null
+                },
+                OBJECT_IDENTIFIER => {
+                    let mut values: Vec<u64> = Vec::new();
+                    let mut value: u64 = 0;
+                    let mut in_progress = false;
+                    for &b in contents {
+                        if !in_progress && b == 0x80 {
+                            return None;
+                        }
+                        value = value.checked_mul(128)? | (b & 0x7f) as u64;
+                        in_progress = b & 0x80 != 0;
+                        if !in_progress {
+                            values.push(value);
+                            value = 0;
+                        }
+                    }
+                    if in_progress || values.is_empty() {
+                        return None;
+                    }
+                    let (first, second) = if values[0] < 80 {(values[0] / 40, values[0] % 40)} else {(2, values[0] - 80)};
+                    let mut arcs = vec![first, second];
+                    arcs.extend_from_slice(&values[1..]);
+                    Der::ObjectIdentifier(arcs)
+                },
+                SEQUENCE => {
+                    let mut items: Vec<Der> = Vec::new();
+                    let mut remaining = contents;
+                    while !remaining.is_empty() {
+                        let (item, next) = Der::decode_prefix(remaining)?;
+                        items.push(item);
+                        remaining = next;
+                    }
+                    Der::Sequence(items)
+                },
+                _ => return None
+            };
+            Some((value, rest))
+        }
+    }
+
+    const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    /**
+     * Standard base64 with = padding: every 3 bytes become 4 characters of 6 bits each
+     */
+    pub fn base64_encode(data: &[u8]) -> String {
+        let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+        for chunk in data.chunks(3) {
+            let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+            let bits = ((bytes[0] as u32) << 16) | ((bytes[1] as u32) << 8) | bytes[2] as u32;
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    out.push(BASE64_ALPHABET[((bits >> (18 - 6 * i)) & 0x3f) as usize] as char);
+                } else {
+                    out.push('=');
+                }
+            }
+        }
+        out
+    }
+
+    /**
+     * Decodes standard base64, ignoring whitespace. Returns None for invalid input.
+     */
+    pub fn base64_decode(text: &str) -> Option<Vec<u8>> {
+        let chars: Vec<u8> = text.bytes().filter(|b| {!b.is_ascii_whitespace()}).collect();
+        if !chars.len().is_multiple_of(4) {
+            return None;
+        }
+        let mut out: Vec<u8> = Vec::with_capacity(chars.len() / 4 * 3);
+        for (index, group) in chars.chunks(4).enumerate() {
+            let last = index == chars.len() / 4 - 1;
+            let padding = group.iter().rev().take_while(|&&c| {c == b'='}).count();
+            if padding > 2 || (padding > 0 && !last) {
+                return None;
+            }
+            let mut bits: u32 = 0;
+            for &c in group[..4 - padding].iter() {
+                let value = BASE64_ALPHABET.iter().position(|&a| {a == c})? as u32;
+                bits = (bits << 6) | value;
+            }
+            bits <<= 6 * padding;
+            out.extend_from_slice(&bits.to_be_bytes()[1..4 - padding]);
+        }
+        Some(out)
+    }
+
+    /**
+     * PEM: the base64 of DER data in lines of 64 characters, between
+     * -----BEGIN label----- and -----END label----- lines
+     */
+    pub fn pem_encode(label: &str, der: &[u8]) -> String {
+        let encoded = base64_encode(der);
+        let mut out = format!("-----BEGIN {}-----\n", label);
+        for line in encoded.as_bytes().chunks(64) {
+            out.push_str(std::str::from_utf8(line).unwrap());
+            out.push('\n');
+        }
+        out.push_str(&format!("-----END {}-----\n", label));
+        out
+    }
+
+    /**
+     * The DER data of the first PEM block with the given label.
+     * Text around the block is ignored. Returns None if there is no valid block.
+     */
+    pub fn pem_decode(label: &str, text: &str) -> Option<Vec<u8>> {
+        let begin = format!("-----BEGIN {}-----", label);
+        let end = format!("-----END {}-----", label);
+        let start = text.find(&begin)? + begin.len();
+        let stop = start + text[start..].find(&end)?;
+        base64_decode(&text[start..stop])
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn integer(x: u64) -> Der {
+            Der::Integer(BigUint::from(x))
+        }
+
+        #[test]
+        fn known_encodings() {
+            let cases: Vec<(Der, Vec<u8>)> = vec![
+                (integer(0), vec![0x02, 0x01, 0x00]),
+                (integer(127), vec![0x02, 0x01, 0x7f]),
+                (integer(128), vec![0x02, 0x02, 0x00, 0x80]),
+                (integer(256), vec![0x02, 0x02, 0x01, 0x00]),
+                (Der::BitString(vec![0xff]), vec![0x03, 0x02, 0x00, 0xff]),
+                (Der::OctetString(Vec::new()), vec![0x04, 0x00]),
+                (Der::Null, vec![0x05, 0x00]),
+                //rsaEncryption, 1.2.840.113549.1.1.1
+                (Der::ObjectIdentifier(vec![1, 2, 840, 113549, 1, 1, 1]), vec![0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01]),
+                //the first arc 2 takes any second arc, X.690 8.19.5
+                (Der::ObjectIdentifier(vec![2, 999, 3]), vec![0x06, 0x03, 0x88, 0x37, 0x03]),
+                (Der::Sequence(vec![integer(1), Der::Null]), vec![0x30, 0x05, 0x02, 0x01, 0x01, 0x05, 0x00])
+            ];
+            for (value, bytes) in cases {
+                assert_eq!(value.encode().as_ref(), Some(&bytes));
+                assert_eq!(Der::decode(&bytes), Some(value));
+            }
+        }
+
+        #[test]
+        fn nested_round_trip() {
+            let big = BigUint::parse_bytes(b"f123456789abcdef0123456789abcdef0123456789abcdef", 16).unwrap();
+            for len in [0, 1, 127, 128, 255, 256, 70000].iter() {
+                let value = Der::Sequence(vec![
+                    Der::Integer(big.clone()),
+                    Der::Sequence(vec![Der::ObjectIdentifier(vec![0, 39, 1 << 40]), Der::Null]),
+                    Der::OctetString(vec![0xab; *len]),
+                    Der::BitString(vec![0x01; *len]),
+                    Der::Sequence(Vec::new())
+                ]);
+                let encoded = value.encode().unwrap();
+                assert_eq!(Der::decode(&encoded), Some(value));
+            }
+        }
+
+        #[test]
+        fn invalid_object_identifiers_are_refused() {
+            let invalid: [&[u64]; 6] = [&[], &[1], &[3, 1], &[0, 40], &[1, 40, 5], &[2, u64::MAX]];
+            for arcs in invalid.iter() {
+                assert_eq!(Der::ObjectIdentifier(arcs.to_vec()).encode(), None);
+                //also inside a sequence
+                assert_eq!(Der::Sequence(vec![Der::Null, Der::ObjectIdentifier(arcs.to_vec())]).encode(), None);
+            }
+            assert!(Der::ObjectIdentifier(vec![2, 40]).encode().is_some());
+            assert!(Der::ObjectIdentifier(vec![1, 39]).encode().is_some());
+        }
+
+        #[test]
+        fn malformed_der() {
+            let malformed: [&[u8]; 17] = [
+                &[],
+                &[0x02],
+                &[0x02, 0x02, 0x01],                //truncated contents
+                &[0x02, 0x01, 0x01, 0x00],          //trailing data
+                &[0x02, 0x81, 0x01, 0x00],          //long form for a short length
+                &[0x04, 0x82, 0x00, 0x80],          //length with a leading zero byte
+                &[0x04, 0x80],                      //indefinite length
+                &[0x02, 0x00],
+                &[0x02, 0x01, 0x80],                //negative
+                &[0x02, 0x02, 0x00, 0x01],          //not minimal
+                &[0x03, 0x02, 0x01, 0x00],          //unused bits
+                &[0x05, 0x01, 0x00],
+                &[0x06, 0x00],
+                &[0x06, 0x02, 0x80, 0x01],          //arc with a leading zero group
+                &[0x06, 0x01, 0x81],                //unterminated arc
+                &[0x30, 0x03, 0x02, 0x01],          //item longer than the sequence
+                &[0x31, 0x00]                       //SET isn't supported
+            ];
+            for bytes in malformed.iter() {
+                assert_eq!(Der::decode(bytes), None);
+            }
+            //an arc of more than 64 bits
+            let mut too_big = vec![0x06, 0x0b, 0x2a];
+            too_big.extend_from_slice(&[0xff; 9]);
+            too_big.push(0x7f);
+            assert_eq!(Der::decode(&too_big), None);
+        }
+
+        //RFC 4648, 10
+        #[test]
+        fn base64_vectors() {
+            let vectors = [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v"), ("foob", "Zm9vYg=="), ("fooba", "Zm9vYmE="), ("foobar", "Zm9vYmFy")];
+            for (data, encoded) in vectors.iter() {
+                assert_eq!(base64_encode(data.as_bytes()), *encoded);
+                assert_eq!(base64_decode(encoded), Some(data.as_bytes().to_vec()));
+            }
+            assert_eq!(base64_decode("Zm9v\n YmFy"), Some(b"foobar".to_vec()));
+            for invalid in ["Zg=", "Z===", "Zg==Zm9v", "Zm9*", "Zm9vY"].iter() {
+                assert_eq!(base64_decode(invalid), None);
+            }
+        }
+
+        #[test]
+        fn pem_round_trip() {
+            let der: Vec<u8> = (0..=255u8).collect();
+            let pem = pem_encode("TEST DATA", &der);
+            let lines: Vec<&str> = pem.lines().collect();
+            assert_eq!(lines[0], "-----BEGIN TEST DATA-----");
+            assert_eq!(*lines.last().unwrap(), "-----END TEST DATA-----");
+            assert!(lines[1..lines.len() - 1].iter().all(|line| {line.len() <= 64}));
+            assert_eq!(lines[1].len(), 64);
+            assert_eq!(pem_decode("TEST DATA", &pem), Some(der.clone()));
+
+            let surrounded = format!("comment\n{}trailing text\n", pem);
+            assert_eq!(pem_decode("TEST DATA", &surrounded), Some(der));
+            assert_eq!(pem_decode("OTHER DATA", &pem), None);
+            assert_eq!(pem_decode("TEST DATA", &pem.replace("-----END TEST DATA-----", "")), None);
+            assert_eq!(pem_decode("TEST DATA", &pem.replace("AAEC", "AA*C")), None);
+        }
+    }
+}
@@ -0,0 +1,493 @@
+pub mod factorization_algos {
+    extern crate num;
+    extern crate rand;
+    use num::{BigUint, Integer, One, Zero, ToPrimitive};
+    use rand::{RngCore, SeedableRng};
+    use rand::rngs::StdRng;
+    use crate::modular::modular_arith::*;
+    use crate::primes::prime_sieves::primes_up_to;
+    use crate::randomized::randomized_algos::{baillie_psw, random_big_below};
+
+    /**
+     * A prime factorization as (prime, exponent) pairs, sorted by prime
+     */
+    pub type Factorization<T> = Vec<(T, u32)>;
+
+    /**
+     * Divides out every prime up to bound.
+     * Returns the factorization found and the remaining cofactor, which has no prime
+     * factor up to bound (so it is 1 or prime if it is below bound^2).
+     */
+    pub fn trial_division(n: &BigUint, bound: u64) -> (Factorization<BigUint>, BigUint) {
+        let mut factors: Factorization<BigUint> = Vec::new();
+        let mut rest = n.clone();
+        for p in primes_up_to(bound) {
+            let p = BigUint::from(p);
+            if &p * &p > rest {
+                break;
+            }
+            let mut exponent = 0;
+            while (&rest % &p).is_zero() {
+                rest /= &p;
+                exponent += 1;
+            }
+            if exponent > 0 {
+                factors.push((p, exponent));
+            }
+        }
+        //the cofactor is prime if it has no factor up to its square root
+        if rest > BigUint::one() && BigUint::from(bound).pow(2) >= rest {
+            factors.push((rest, 1));
+            rest = BigUint::one();
+        }
+        (factors, rest)
+    }
+
+    //|a - b|
+    fn abs_diff(a: &BigUint, b: &BigUint) -> BigUint {
+        if a > b {a - b} else {b - a}
+    }
+
+    /**
+     * Pollard's rho method with Brent's cycle detection, for a composite n.
+     *
+     * The map f(x) = x^2 + c (mod n) is pseudo-random, so modulo an unknown prime factor p
+     * its sequence repeats after about sqrt(p) steps, and then gcd(x - y, n) reveals p.
+     * Brent's variant compares x_i with x_j for j between 2^k and 2^(k+1) as k grows,
+     * and multiplies up a batch of differences before each gcd.
+     *
+     * The constant c and the starting point are drawn from rng, again after each failure.
+     * Returns a nontrivial factor, or None if none turned up in max_iterations steps.
+     * Runtime: O(sqrt(p)) multiplications for the smallest prime factor p
+     */
+    pub fn pollard_rho<R: RngCore + ?Sized>(n: &BigUint, max_iterations: u64, rng: &mut R) -> Option<BigUint> {
+        let one = BigUint::one();
+        if n <= &BigUint::from(3u32) {
+            return None;
+        }
+        if n.is_even() {
+            return Some(BigUint::from(2u32));
+        }
+        let batch = 128;
+        let mut iterations = 0;
+
+        while iterations < max_iterations {
+            let c = random_big_below(&(n - 1u32), rng) + 1u32;
+            let f = |x: &BigUint| -> BigUint {(x * x + &c) % n};
+            let mut y = random_big_below(n, rng);
+            let (mut x, mut ys) = (y.clone(), y.clone());
+            let mut g = one.clone();
+            let mut q = one.clone();
+            let mut r: u64 = 1;
+
+            while g.is_one() && iterations < max_iterations {
+                x = y.clone();
+                for _i in 0..r {
+                    y = f(&y);
+                }
+                let mut k = 0;
+                while k < r && g.is_one() {
+                    ys = y.clone();
+                    for _i in 0..batch.min(r - k) {
+                        y = f(&y);
+                        q = q * abs_diff(&x, &y) % n;
+                    }
+                    g = q.gcd(n);
+                    k += batch;
+                }
+                iterations += r;
+                r *= 2;
+            }
+
+            //the batch overshot: step through it one difference at a time
+            if &g == n {
+                loop {
+                    ys = f(&ys);
+                    g = abs_diff(&x, &ys).gcd(n);
+                    if !g.is_one() {
+                        break;
+                    }
+                }
+            }
+            if !g.is_one() && &g != n {
+                return Some(g);
+            }
+        }
+        None
+    }
+
+    /**
+     * Pollard's p - 1 method. If some prime factor p of n has p - 1 made of prime powers
+     * up to bound, then p - 1 divides M = the product of those prime powers, so
+     * a^M = 1 (mod p) by Fermat's little theorem and gcd(a^M - 1, n) reveals p.
+     *
+     * Returns a nontrivial factor, or None if none was found with this bound.
+     */
+    pub fn pollard_p_minus_1(n: &BigUint, bound: u64) -> Option<BigUint> {
+        if n <= &BigUint::from(3u32) {
+            return None;
+        }
+        if n.is_even() {
+            return Some(BigUint::from(2u32));
+        }
+        let mut a = BigUint::from(2u32);
+        for p in primes_up_to(bound) {
+            //largest power of p up to bound
+            let mut power = p;
+            while power <= bound / p {
+                power *= p;
+            }
+            a = pow_mod_big(&a, &BigUint::from(power), n);
+
+            let g = (&a + n - 1u32).gcd(n);
+            if &g == n {
+                return None;
+            }
+            if !g.is_one() {
+                return Some(g);
+            }
+        }
+        None
+    }
+
+    //a point (X : Z) of a Montgomery curve in projective coordinates, with y left out
+    type Point = (BigUint, BigUint);
+
+    //arithmetic modulo n on a Montgomery curve, given a24 = (A + 2) / 4
+    struct MontgomeryCurve<'a> {
+        n: &'a BigUint,
+        a24: BigUint
+    }
+
+    impl MontgomeryCurve<'_> {
+        fn add(&self, x: &BigUint, y: &BigUint) -> BigUint {
+            (x + y) % self.n
+        }
+
+        fn sub(&self, x: &BigUint, y: &BigUint) -> BigUint {
+            (x + self.n - y) % self.n
+        }
+
+        //2P
+        fn double(&self, p: &Point) -> Point {
+            let sum = self.add(&p.0, &p.1);
+            let diff = self.sub(&p.0, &p.1);
+            let sum_squared = &sum * &sum % self.n;
+            let diff_squared = &diff * &diff % self.n;
+            let t = self.sub(&sum_squared, &diff_squared);      //4XZ
+            let x = &sum_squared * &diff_squared % self.n;
+            let z = &t * (diff_squared + &self.a24 * &t % self.n) % self.n;
+            (x, z)
+        }
+
+        //P + Q, given P - Q
+        fn add_points(&self, p: &Point, q: &Point, difference: &Point) -> Point {
+            let u = self.sub(&p.0, &p.1) * self.add(&q.0, &q.1) % self.n;
+            let v = self.add(&p.0, &p.1) * self.sub(&q.0, &q.1) % self.n;
+            let sum = self.add(&u, &v);
+            let diff = self.sub(&u, &v);
+            let x = &difference.1 * (&sum * &sum % self.n) % self.n;
+            let z = &difference.0 * (&diff * &diff % self.n) % self.n;
+            (x, z)
+        }
+
+        //kP by the Montgomery ladder, which keeps R1 - R0 = P throughout
+        fn multiply(&self, k: u64, p: &Point) -> Point {
+            let mut r0 = p.clone();
+            let mut r1 = self.double(p);
+            for bit in (0..(63 - k.leading_zeros())).rev() {
+                if (k >> bit) & 1 == 1 {
+                    r0 = self.add_points(&r1, &r0, p);
+                    r1 = self.double(&r1);
+                } else {
+                    r1 = self.add_points(&r0, &r1, p);
+                    r0 = self.double(&r0);
+                }
+            }
+            r0
+        }
+    }
+
+    /**
+     * Lenstra's elliptic curve method. It is the p - 1 method with the group of units modulo p
+     * replaced by the points of a random elliptic curve modulo p, whose order varies from
+     * curve to curve in roughly p - 2 sqrt(p)..p + 2 sqrt(p). A point is multiplied by every
+     * prime power up to b1 using arithmetic modulo n. When the order of the curve modulo p
+     * has only such prime factors, the point becomes the point at infinity modulo p, whose
+     * Z coordinate is 0, and gcd(Z, n) reveals p.
+     *
+     * The curves are Montgomery curves By^2 = x^3 + Ax^2 + x in (X : Z) coordinates, which
+     * need no modular inverses, chosen by Suyama's parametrization so that their orders
+     * are divisible by 12.
+     *
+     * Tries the given number of curves drawn from rng and returns a nontrivial factor or None.
+     * Runtime: grows with the size of the smallest prime factor, not of n
+     */
+    pub fn ecm<R: RngCore + ?Sized>(n: &BigUint, b1: u64, curves: usize, rng: &mut R) -> Option<BigUint> {
+        if n <= &BigUint::from(3u32) {
+            return None;
+        }
+        if n.is_even() {
+            return Some(BigUint::from(2u32));
+        }
+        let primes = primes_up_to(b1);
+
+        for _curve in 0..curves {
+            //Suyama: u = s^2 - 5, v = 4s, starting point (u^3 : v^3),
+            //a24 = (v - u)^3 (3u + v) / (16 u^3 v)
+            let sigma = random_big_below(&(n - 6u32), rng) + 6u32;
+            let u = (&sigma * &sigma + n - 5u32) % n;
+            let v = (sigma << 2u32) % n;
+            let u_cubed = u.modpow(&BigUint::from(3u32), n);
+            let v_cubed = v.modpow(&BigUint::from(3u32), n);
+            let denominator = BigUint::from(16u32) * &u_cubed * &v % n;
+            let inverse = match inv_mod_big(&denominator, n) {
+                Some(inverse) => inverse,
+                None => {
+                    //the parameters already share a factor with n
+                    let g = denominator.gcd(n);
+                    if &g != n {
+                        return Some(g);
+                    }
+                    continue;
+                }
+            };
+            let v_minus_u = (&v + n - &u) % n;
+            let a24 = v_minus_u.modpow(&BigUint::from(3u32), n) * ((u * 3u32 + v) % n) % n * inverse % n;
+            let curve = MontgomeryCurve{n, a24};
+
+            let mut point: Point = (u_cubed, v_cubed);
+            for &p in primes.iter() {
+                let mut power = p;
+                while power <= b1 / p {
+                    power *= p;
+                }
+                point = curve.multiply(power, &point);
+            }
+
+            let g = point.1.gcd(n);
+            if !g.is_one() && &g != n {
+                return Some(g);
+            }
+        }
+        None
+    }
+
+    //n = root^k for the largest possible k > 1, if n is a perfect power
+    fn perfect_power(n: &BigUint) -> Option<(BigUint, u32)> {
+        for k in (2..=(n.bits() as u32)).rev() {
+            let root = n.nth_root(k);
+            if root > BigUint::one() && &root.pow(k) == n {
+                return Some((root, k));
+            }
+        }
+        None
+    }
+
+    //a nontrivial factor of a composite n that isn't a perfect power
+    fn find_factor<R: RngCore + ?Sized>(n: &BigUint, rng: &mut R) -> BigUint {
+        let mut rho_iterations = 1 << 14;
+        let mut b1 = 2000;
+        loop {
+            if let Some(d) = pollard_rho(n, rho_iterations, rng) {
+                return d;
+            }
+            if let Some(d) = pollard_p_minus_1(n, 10 * b1) {
+                return d;
+            }
+            if let Some(d) = ecm(n, b1, 20, rng) {
+                return d;
+            }
+            rho_iterations *= 2;
+            b1 *= 3;
+        }
+    }
+
+    /**
+     * The prime factorization of n > 0.
+     *
+     * Primes below 1000 are divided out first. The rest is split recursively: parts that
+     * pass the Baillie-PSW test are taken as prime, perfect powers are split into their
+     * roots, and other composites are split by Pollard's rho, then p - 1, then ECM, with
+     * growing effort until one of them finds a factor. Their random choices are drawn from
+     * a fixed seed, so a given n is always factored the same way.
+     */
+    pub fn factorize(n: &BigUint) -> Factorization<BigUint> {
+        if n.is_zero() {
+            panic!("0 has no prime factorization.");
+        }
+        let (mut factors, rest) = trial_division(n, 1000);
+        let mut stack: Vec<(BigUint, u32)> = Vec::new();
+        let mut rng = StdRng::seed_from_u64(0);
+        if !rest.is_one() {
+            stack.push((rest, 1));
+        }
+
+        //(number, multiplicity) pairs still to be split
+        while let Some((m, multiplicity)) = stack.pop() {
+            if baillie_psw(&m).is_probably_prime() {
+                factors.push((m, multiplicity));
+            } else if let Some((root, k)) = perfect_power(&m) {
+                stack.push((root, multiplicity * k));
+            } else {
+                let d = find_factor(&m, &mut rng);
+                let other = &m / &d;
+                stack.push((d, multiplicity));
+                stack.push((other, multiplicity));
+            }
+        }
+
+        //merge repeated primes
+        factors.sort();
+        let mut merged: Factorization<BigUint> = Vec::new();
+        for (p, e) in factors {
+            match merged.last_mut() {
+                Some((last, exponent)) if *last == p => *exponent += e,
+                _ => merged.push((p, e))
+            }
+        }
+        merged
+    }
+
+    /**
+     * The prime factorization of a u128 n > 0, see factorize
+     */
+    pub fn factorize_u128(n: u128) -> Factorization<u128> {
+        factorize(&BigUint::from(n)).into_iter().map(|(p, e)| {(p.to_u128().unwrap(), e)}).collect()
+    }
+
+    /**
+     * Euler's totient φ(n), the number of integers in 1..=n coprime to n:
+     * the product of p^(e-1) (p - 1) over the prime powers p^e of n
+     */
+    pub fn totient(n: &BigUint) -> BigUint {
+        factorize(n).into_iter().map(|(p, e)| {p.pow(e - 1) * (p - 1u32)}).product()
+    }
+
+    /**
+     * Carmichael's function λ(n), the exponent of the group of units modulo n:
+     * the smallest m > 0 with a^m = 1 (mod n) for every a coprime to n.
+     * It is the lcm of λ(p^e) over the prime powers of n, where λ(p^e) = φ(p^e) except
+     * that λ(2^e) = 2^(e-2) for e >= 3.
+     */
+    pub fn carmichael_lambda(n: &BigUint) -> BigUint {
+        factorize(n).into_iter().map(|(p, e)| {
+            if p == BigUint::from(2u32) && e >= 3 {
+                BigUint::one() << (e - 2)
+            } else {
+                p.pow(e - 1) * (p - 1u32)
+            }
+        }).fold(BigUint::one(), |acc, x| {acc.lcm(&x)})
+    }
+
+    /**
+     * The number of divisors of n, the product of e + 1 over the prime powers p^e of n
+     */
+    pub fn divisor_count(n: &BigUint) -> BigUint {
+        factorize(n).into_iter().map(|(_, e)| {BigUint::from(e + 1)}).product()
+    }
+
+    /**
+     * The sum of the divisors of n, the product of 1 + p + ... + p^e = (p^(e+1) - 1) / (p - 1)
+     * over the prime powers p^e of n
+     */
+    pub fn divisor_sum(n: &BigUint) -> BigUint {
+        factorize(n).into_iter().map(|(p, e)| {(p.pow(e + 1) - 1u32) / (p - 1u32)}).product()
+    }
+
+    /**
+     * The Möbius function μ(n): 0 if a square divides n, otherwise 1 or -1 for an even or
+     * odd number of prime factors
+     */
+    pub fn mobius(n: &BigUint) -> i32 {
+        let factors = factorize(n);
+        if factors.iter().any(|&(_, e)| {e > 1}) {
+            0
+        } else if factors.len().is_multiple_of(2) {
+            1
+        } else {
+            -1
+        }
+    }
+
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::drbg::chacha_drbg::ChaChaDrbg;
+
+        //the factorization by dividing by every number in turn
+        fn naive_factorize(mut n: u64) -> Factorization<u64> {
+            let mut factors = Vec::new();
+            let mut d = 2;
+            while n > 1 {
+                let mut exponent = 0;
+                while n.is_multiple_of(d) {
+                    n /= d;
+                    exponent += 1;
+                }
+                if exponent > 0 {
+                    factors.push((d, exponent));
+                }
+                d += 1;
+            }
+            factors
+        }
+
+        fn gcd(a: u64, b: u64) -> u64 {
+            if b == 0 {a} else {gcd(b, a % b)}
+        }
+
+        #[test]
+        fn arithmetic_functions_match_trial_division() {
+            for n in 1..=3000u64 {
+                let expected = naive_factorize(n);
+                let big = BigUint::from(n);
+                assert_eq!(factorize_u128(n as u128), expected.iter().map(|&(p, e)| {(p as u128, e)}).collect::<Vec<_>>());
+                assert_eq!(totient(&big), BigUint::from((1..=n).filter(|&a| {gcd(a, n) == 1}).count()), "{}", n);
+                let mu = if expected.iter().any(|&(_p, e)| {e > 1}) {0} else if expected.len().is_multiple_of(2) {1} else {-1};
+                assert_eq!(mobius(&big), mu, "{}", n);
+            }
+        }
+
+        #[test]
+        fn carmichael_lambda_matches_the_orders() {
+            for n in 1..=400u64 {
+                let units: Vec<u64> = (1..=n).filter(|&a| {gcd(a, n) == 1}).collect();
+                //the smallest m with a^m = 1 for every unit a
+                let lambda = (1..=n).find(|&m| {
+                    units.iter().all(|&a| {(0..m).fold(1 % n, |x, _i| {x * a % n}) == 1 % n})
+                }).unwrap();
+                assert_eq!(carmichael_lambda(&BigUint::from(n)), BigUint::from(lambda), "{}", n);
+            }
+        }
+
+        #[test]
+        fn factorize_large_numbers() {
+            //Mersenne primes 2^31 - 1 and 2^61 - 1, and a square of a prime over 1000
+            let n = ((1u128 << 31) - 1) * ((1u128 << 61) - 1) * 1009 * 1009 * 8;
+            assert_eq!(factorize_u128(n), vec![(2, 3), (1009, 2), ((1 << 31) - 1, 1), ((1 << 61) - 1, 1)]);
+            let p = BigUint::from(10000000000000000051u64);
+            assert_eq!(factorize(&p.pow(3)), vec![(p, 3)]);
+        }
+
+        #[test]
+        fn seeded_pollard_rho_is_reproducible() {
+            let n = BigUint::from(1000003u64 * 1000033);
+            let first = pollard_rho(&n, 1 << 16, &mut ChaChaDrbg::seed_from_u64(36));
+            assert!(first == Some(BigUint::from(1000003u32)) || first == Some(BigUint::from(1000033u32)));
+            assert_eq!(pollard_rho(&n, 1 << 16, &mut ChaChaDrbg::seed_from_u64(36)), first);
+            assert_eq!(pollard_rho(&BigUint::from(1000003u32), 1 << 10, &mut ChaChaDrbg::seed_from_u64(36)), None);
+        }
+
+        #[test]
+        fn ecm_splits_a_semiprime_with_20_digit_factors() {
+            let p = BigUint::from(10000000000000000051u64);
+            let q = BigUint::from(70000000000000000013u128);
+            let n = &p * &q;
+            //the first curve drawn from this seed has an order modulo p made of primes up to b1
+            assert_eq!(ecm(&n, 11000, 1, &mut ChaChaDrbg::seed_from_u64(1144)), Some(p));
+            assert_eq!(ecm(&q, 2000, 2, &mut ChaChaDrbg::seed_from_u64(1144)), None);
+        }
+    }
+}
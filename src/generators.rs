@@ -0,0 +1,428 @@
+pub mod graph_generators {
+    extern crate rand;
+    use crate::graph::*;
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use std::collections::HashSet;
+
+    /**
+     * A flow network in the form edmonds_karp expects it:
+     * edge weights hold the current flow (all 0) and capacities[i] is the capacity of edges[i].
+     */
+    pub struct FlowNetwork {
+        pub graph: Graph,
+        pub capacities: Vec<i32>,
+        pub source: Vertex,
+        pub sink: Vertex
+    }
+
+    //vertices with ids 1..=n
+    fn vertices(n: usize) -> Vec<Vertex> {
+        (1..=n as i32).map(Vertex::new).collect()
+    }
+
+    //graph from (position, position) pairs, all with weight 1
+    fn from_pairs(n: usize, pairs: Vec<(usize, usize)>) -> Graph {
+        let vs = vertices(n);
+        let edges = pairs.iter().map(|&(u, v)| {Edge::new(vs[u], vs[v], 1)}).collect();
+        Graph::new(edges, vs)
+    }
+
+    /**
+     * Erdős–Rényi G(n, p): every possible edge is present independently with probability p.
+     *
+     * The generators in this module number vertices 1..=n. Undirected graphs list every edge
+     * once, from the lower id to the higher, which is how the undirected algorithms of the
+     * crate read them. All edges have weight 1; see with_random_weights.
+     * Equal seeds give equal graphs.
+     */
+    pub fn gnp(n: usize, p: f64, directed: bool, seed: u64) -> Graph {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut pairs: Vec<(usize, usize)> = Vec::new();
+
+        for u in 0..n {
+            let first = if directed {0} else {u + 1};
+            for v in first..n {
+                if u != v && rng.gen_bool(p) {
+                    pairs.push((u, v));
+                }
+            }
+        }
+        from_pairs(n, pairs)
+    }
+
+    /**
+     * Erdős–Rényi G(n, m): a graph chosen uniformly among those with exactly m edges.
+     * Panics if m is more than the number of possible edges.
+     */
+    pub fn gnm(n: usize, m: usize, directed: bool, seed: u64) -> Graph {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let possible = if directed {n * n.saturating_sub(1)} else {n * n.saturating_sub(1) / 2};
+        if m > possible {
+            panic!("A graph with {} vertices has at most {} edges.", n, possible);
+        }
+
+        let pairs: Vec<(usize, usize)> = if 2 * m <= possible {
+            //sparse: draw random pairs until there are enough distinct ones
+            let mut chosen: HashSet<(usize, usize)> = HashSet::new();
+            let mut pairs = Vec::new();
+            while pairs.len() < m {
+                let u = rng.gen_range(0..n);
+                let v = rng.gen_range(0..n);
+                let pair = if directed || u < v {(u, v)} else {(v, u)};
+                if u != v && chosen.insert(pair) {
+                    pairs.push(pair);
+                }
+            }
+            pairs
+        } else {
+            //dense: shuffle the list of every possible edge and keep the first m
+            let mut all: Vec<(usize, usize)> = (0..n).flat_map(|u| {
+                (0..n).filter(move |&v| {if directed {u != v} else {u < v}}).map(move |v| {(u, v)})
+            }).collect();
+            all.shuffle(&mut rng);
+            all.truncate(m);
+            all
+        };
+        from_pairs(n, pairs)
+    }
+
+    /**
+     * Barabási–Albert preferential attachment, giving a scale-free undirected graph.
+     * Starts from a star on m + 1 vertices, then every new vertex is joined to m distinct
+     * existing vertices, each picked with probability proportional to its degree.
+     * Requires 1 <= m < n.
+     */
+    pub fn barabasi_albert(n: usize, m: usize, seed: u64) -> Graph {
+        if m < 1 || m >= n {
+            panic!("Barabási–Albert needs 1 <= m < n.");
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut pairs: Vec<(usize, usize)> = (1..=m).map(|v| {(0, v)}).collect();
+
+        //every vertex appears in this list once per unit of degree
+        let mut endpoints: Vec<usize> = pairs.iter().flat_map(|&(u, v)| {vec![u, v]}).collect();
+
+        for new in (m + 1)..n {
+            let mut targets: Vec<usize> = Vec::new();
+            while targets.len() < m {
+                let t = *endpoints.choose(&mut rng).unwrap();
+                if !targets.contains(&t) {
+                    targets.push(t);
+                }
+            }
+            for t in targets {
+                pairs.push((t, new));
+                endpoints.push(t);
+                endpoints.push(new);
+            }
+        }
+        from_pairs(n, pairs)
+    }
+
+    /**
+     * Watts–Strogatz small-world graph (undirected).
+     * Starts from a ring where every vertex is joined to its k nearest neighbors (k/2 per side),
+     * then rewires the far end of each edge to a random vertex with probability beta,
+     * avoiding self loops and parallel edges. beta = 0 keeps the ring, beta = 1 is close to random.
+     * Requires an even k < n.
+     */
+    pub fn watts_strogatz(n: usize, k: usize, beta: f64, seed: u64) -> Graph {
+        if !k.is_multiple_of(2) || k >= n {
+            panic!("Watts–Strogatz needs an even k smaller than n.");
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let key = |u: usize, v: usize| {(u.min(v), u.max(v))};
+        let mut present: HashSet<(usize, usize)> = HashSet::new();
+        let mut pairs: Vec<(usize, usize)> = Vec::new();
+
+        for j in 1..=(k / 2) {
+            for u in 0..n {
+                let pair = key(u, (u + j) % n);
+                present.insert(pair);
+                pairs.push(pair);
+            }
+        }
+
+        let mut degree = vec![k; n];
+        for pair in pairs.iter_mut() {
+            let (u, v) = *pair;
+            //a vertex already joined to everything can't be rewired
+            if degree[u] < n - 1 && rng.gen_bool(beta) {
+                let mut w = rng.gen_range(0..n);
+                while w == u || present.contains(&key(u, w)) {
+                    w = rng.gen_range(0..n);
+                }
+                present.remove(pair);
+                *pair = key(u, w);
+                present.insert(*pair);
+                degree[v] -= 1;
+                degree[w] += 1;
+            }
+        }
+        from_pairs(n, pairs)
+    }
+
+    /**
+     * A rows x cols grid (undirected), each vertex joined to its horizontal and
+     * vertical neighbors. The vertex in row r and column c has id r * cols + c + 1.
+     */
+    pub fn grid(rows: usize, cols: usize) -> Graph {
+        let mut pairs: Vec<(usize, usize)> = Vec::new();
+        for r in 0..rows {
+            for c in 0..cols {
+                let u = r * cols + c;
+                if c + 1 < cols {
+                    pairs.push((u, u + 1));
+                }
+                if r + 1 < rows {
+                    pairs.push((u, u + cols));
+                }
+            }
+        }
+        from_pairs(rows * cols, pairs)
+    }
+
+    /**
+     * The complete graph on n vertices (undirected)
+     */
+    pub fn complete(n: usize) -> Graph {
+        let pairs = (0..n).flat_map(|u| {((u + 1)..n).map(move |v| {(u, v)})}).collect();
+        from_pairs(n, pairs)
+    }
+
+    /**
+     * The complete bipartite graph K(a, b) (undirected).
+     * Ids 1..=a form one side and a+1..=a+b the other.
+     */
+    pub fn complete_bipartite(a: usize, b: usize) -> Graph {
+        let pairs = (0..a).flat_map(|u| {(a..(a + b)).map(move |v| {(u, v)})}).collect();
+        from_pairs(a + b, pairs)
+    }
+
+    /**
+     * A random bipartite graph (undirected) where each of the a * b edges between the
+     * sides 1..=a and a+1..=a+b is present with probability p
+     */
+    pub fn random_bipartite(a: usize, b: usize, p: f64, seed: u64) -> Graph {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut pairs: Vec<(usize, usize)> = Vec::new();
+        for u in 0..a {
+            for v in a..(a + b) {
+                if rng.gen_bool(p) {
+                    pairs.push((u, v));
+                }
+            }
+        }
+        from_pairs(a + b, pairs)
+    }
+
+    /**
+     * A random directed acyclic graph. The vertices are put in a random order and each
+     * forward edge (earlier -> later in that order) is present with probability p.
+     */
+    pub fn random_dag(n: usize, p: f64, seed: u64) -> Graph {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut order: Vec<usize> = (0..n).collect();
+        order.shuffle(&mut rng);
+
+        let mut pairs: Vec<(usize, usize)> = Vec::new();
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if rng.gen_bool(p) {
+                    pairs.push((order[i], order[j]));
+                }
+            }
+        }
+        from_pairs(n, pairs)
+    }
+
+    /**
+     * A random flow network from vertex 1 (source) to vertex n (sink) with capacities
+     * drawn from 1..=max_capacity. The network is a DAG in id order where every forward edge
+     * is present with probability p. Each vertex is also given at least one incoming and one
+     * outgoing edge, so every vertex lies on some source-sink path. Requires n >= 2.
+     */
+    pub fn random_flow_network(n: usize, p: f64, max_capacity: i32, seed: u64) -> FlowNetwork {
+        if n < 2 {
+            panic!("A flow network needs at least 2 vertices.");
+        }
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut present: HashSet<(usize, usize)> = HashSet::new();
+        let mut pairs: Vec<(usize, usize)> = Vec::new();
+        let mut add = |pair: (usize, usize), pairs: &mut Vec<(usize, usize)>| {
+            if present.insert(pair) {
+                pairs.push(pair);
+            }
+        };
+
+        for u in 0..n {
+            for v in (u + 1)..n {
+                if rng.gen_bool(p) {
+                    add((u, v), &mut pairs);
+                }
+            }
+        }
+        let mut has_incoming = vec![false; n];
+        pairs.iter().for_each(|&(_, v)| {has_incoming[v] = true});
+        for (v, _) in has_incoming.iter().enumerate().skip(1).filter(|(_, &has)| {!has}) {
+            let u = rng.gen_range(0..v);
+            add((u, v), &mut pairs);
+        }
+        let mut has_outgoing = vec![false; n];
+        pairs.iter().for_each(|&(u, _)| {has_outgoing[u] = true});
+        for (u, _) in has_outgoing.iter().enumerate().take(n - 1).filter(|(_, &has)| {!has}) {
+            let v = rng.gen_range((u + 1)..n);
+            add((u, v), &mut pairs);
+        }
+
+        let capacities = pairs.iter().map(|_| {rng.gen_range(1..=max_capacity)}).collect();
+        let mut graph = from_pairs(n, pairs);
+        graph.edges.iter_mut().for_each(|e| {e.weight = 0});
+        let source = graph.vertices[0];
+        let sink = graph.vertices[n - 1];
+
+        FlowNetwork {
+            graph,
+            capacities,
+            source,
+            sink
+        }
+    }
+
+    /**
+     * Gives every edge a weight drawn uniformly from min..=max,
+     * e.g. to test shortest paths or minimum spanning trees
+     */
+    pub fn with_random_weights(mut graph: Graph, min: i32, max: i32, seed: u64) -> Graph {
+        let mut rng = StdRng::seed_from_u64(seed);
+        graph.edges.iter_mut().for_each(|e| {e.weight = rng.gen_range(min..=max)});
+        graph
+    }
+
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        //the edges as (from id, to id) pairs
+        fn pairs(graph: &Graph) -> Vec<(i32, i32)> {
+            graph.edges.iter().map(|e| {(e.from.id, e.to.id)}).collect()
+        }
+
+        //whether the edges are distinct, without self loops, and listed from the lower id if undirected
+        fn is_simple(graph: &Graph, directed: bool) -> bool {
+            let edges = pairs(graph);
+            let distinct: HashSet<(i32, i32)> = edges.iter().copied().collect();
+            distinct.len() == edges.len() && edges.iter().all(|&(u, v)| {if directed {u != v} else {u < v}})
+        }
+
+        //Kahn's algorithm: whether every vertex can be removed once nothing points to it
+        fn is_acyclic(graph: &Graph) -> bool {
+            let adj = graph.adjacency_list(true);
+            let mut in_degree = vec![0; adj.len()];
+            adj.iter().flatten().for_each(|&(v, _w)| {in_degree[v] += 1});
+            let mut ready: Vec<usize> = (0..adj.len()).filter(|&v| {in_degree[v] == 0}).collect();
+            let mut removed = 0;
+            while let Some(u) = ready.pop() {
+                removed += 1;
+                for &(v, _w) in adj[u].iter() {
+                    in_degree[v] -= 1;
+                    if in_degree[v] == 0 {
+                        ready.push(v);
+                    }
+                }
+            }
+            removed == adj.len()
+        }
+
+        #[test]
+        fn gnm_has_exactly_m_edges() {
+            //sparse and dense, up to every possible edge
+            for &(directed, m) in [(false, 0), (false, 20), (false, 44), (false, 45), (true, 1), (true, 30), (true, 80), (true, 90)].iter() {
+                let graph = gnm(10, m, directed, 30);
+                assert_eq!(graph.vertices.len(), 10);
+                assert_eq!(graph.edges.len(), m);
+                assert!(is_simple(&graph, directed));
+            }
+        }
+
+        #[test]
+        fn equal_seeds_give_equal_graphs() {
+            assert_eq!(pairs(&gnp(30, 0.2, false, 7)), pairs(&gnp(30, 0.2, false, 7)));
+            assert_ne!(pairs(&gnp(30, 0.2, false, 7)), pairs(&gnp(30, 0.2, false, 8)));
+            assert_eq!(pairs(&barabasi_albert(40, 3, 7)), pairs(&barabasi_albert(40, 3, 7)));
+            assert_ne!(pairs(&barabasi_albert(40, 3, 7)), pairs(&barabasi_albert(40, 3, 8)));
+            assert_eq!(pairs(&watts_strogatz(30, 4, 0.3, 7)), pairs(&watts_strogatz(30, 4, 0.3, 7)));
+            assert_ne!(pairs(&watts_strogatz(30, 4, 0.3, 7)), pairs(&watts_strogatz(30, 4, 0.3, 8)));
+            assert!(is_simple(&gnp(30, 0.5, true, 7), true));
+            assert!(is_simple(&gnp(30, 0.5, false, 7), false));
+        }
+
+        #[test]
+        fn random_models_keep_their_edge_counts() {
+            //a star of m edges, then m more for each of the other n - m - 1 vertices
+            let graph = barabasi_albert(40, 3, 30);
+            assert_eq!(graph.edges.len(), 3 + 3 * 36);
+            assert!(is_simple(&graph, false));
+            //rewiring moves edges without adding or dropping any
+            for &beta in [0.0, 0.3, 1.0].iter() {
+                let graph = watts_strogatz(30, 4, beta, 30);
+                assert_eq!(graph.edges.len(), 60);
+                assert!(is_simple(&graph, false));
+            }
+            assert_eq!(gnp(20, 1.0, false, 30).edges.len(), 190);
+            assert_eq!(gnp(20, 0.0, true, 30).edges.len(), 0);
+        }
+
+        #[test]
+        fn random_dags_are_acyclic() {
+            for seed in 0..20 {
+                let graph = random_dag(25, 0.3, seed);
+                assert!(is_acyclic(&graph));
+                assert!(is_simple(&graph, true));
+            }
+            assert_eq!(random_dag(12, 1.0, 30).edges.len(), 66);
+            //a cycle, to check the check
+            let cycle = from_pairs(3, vec![(0, 1), (1, 2), (2, 0)]);
+            assert!(!is_acyclic(&cycle));
+        }
+
+        #[test]
+        fn deterministic_graphs_have_the_right_edge_counts() {
+            assert_eq!(grid(4, 5).edges.len(), 4 * 4 + 3 * 5);
+            assert_eq!(grid(1, 1).edges.len(), 0);
+            assert!(is_simple(&grid(4, 5), false));
+            assert_eq!(complete(7).edges.len(), 21);
+            assert!(is_simple(&complete(7), false));
+            let k34 = complete_bipartite(3, 4);
+            assert_eq!(k34.edges.len(), 12);
+            assert!(pairs(&k34).iter().all(|&(u, v)| {u <= 3 && v > 3}));
+            let bipartite = random_bipartite(5, 6, 0.5, 30);
+            assert!(pairs(&bipartite).iter().all(|&(u, v)| {u <= 5 && v > 5}));
+            assert_eq!(random_bipartite(5, 6, 1.0, 30).edges.len(), 30);
+        }
+
+        #[test]
+        fn flow_network_capacities_are_in_bounds() {
+            for seed in 0..20 {
+                let network = random_flow_network(15, 0.2, 9, seed);
+                let graph = &network.graph;
+                assert_eq!(network.capacities.len(), graph.edges.len());
+                assert!(network.capacities.iter().all(|&c| {(1..=9).contains(&c)}));
+                assert!(graph.edges.iter().all(|e| {e.weight == 0 && e.from.id < e.to.id}));
+                assert_eq!((network.source.id, network.sink.id), (1, 15));
+                //every vertex but the source has an edge in, and every one but the sink an edge out
+                for v in 2..=15 {
+                    assert!(graph.edges.iter().any(|e| {e.to.id == v}));
+                }
+                for u in 1..15 {
+                    assert!(graph.edges.iter().any(|e| {e.from.id == u}));
+                }
+            }
+            let weighted = with_random_weights(grid(3, 3), -5, 5, 30);
+            assert!(weighted.edges.iter().all(|e| {(-5..=5).contains(&e.weight)}));
+        }
+    }
+}
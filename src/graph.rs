@@ -78,16 +78,95 @@
 
     pub struct Graph {
         pub vertices: Vec<Vertex>,
-        pub edges: Vec<Edge>
+        pub edges: Vec<Edge>,
+        next_id: i32                //id given to the next vertex from add_node
     }
 
     impl Graph {
         pub fn new(edges: Vec<Edge>, vertices: Vec<Vertex>) -> Self {
+            let next_id = vertices.iter().map(|v| {v.id + 1}).max().unwrap_or(1).max(1);
             Graph {
                 vertices,
-                edges
+                edges,
+                next_id
             }
         }
+
+        /**
+         * Adds a new vertex and returns it.
+         * Ids are never reused, so a new vertex gets an id larger than any vertex this graph
+         * has held, including removed ones. Ids of existing vertices never change.
+         */
+        pub fn add_node(&mut self) -> Vertex {
+            while self.index_of(self.next_id).is_some() {
+                self.next_id += 1;
+            }
+            let vertex = Vertex::new(self.next_id);
+            self.next_id += 1;
+            self.vertices.push(vertex);
+            vertex
+        }
+
+        /**
+         * Adds a directed edge between the vertices with the given ids.
+         * Returns None, leaving the graph unchanged, if either vertex isn't in the graph.
+         */
+        pub fn add_edge(&mut self, from: i32, to: i32, weight: i32) -> Option<Edge> {
+            let from_index = self.index_of(from)?;
+            let to_index = self.index_of(to)?;
+            let edge = Edge::new(self.vertices[from_index], self.vertices[to_index], weight);
+            self.edges.push(edge);
+            Some(edge)
+        }
+
+        /**
+         * Removes one edge from -> to (the first one added, if there are parallel edges)
+         * and returns it, or None if there is no such edge.
+         */
+        pub fn remove_edge(&mut self, from: i32, to: i32) -> Option<Edge> {
+            let position = self.edges.iter().position(|e| {e.from.id == from && e.to.id == to})?;
+            Some(self.edges.remove(position))
+        }
+
+        /**
+         * Removes the vertex with the given id along with every edge touching it.
+         * The other vertices keep their ids, but those stored after it in the vertex list
+         * move down one position, so per-vertex results computed before the removal
+         * should be looked up again by id.
+         */
+        pub fn remove_node(&mut self, id: i32) -> Option<Vertex> {
+            let position = self.index_of(id)?;
+            self.edges.retain(|e| {e.from.id != id && e.to.id != id});
+            Some(self.vertices.remove(position))
+        }
+
+        /**
+         * Returns the position in the vertex list of the vertex with the given id
+         */
+        pub fn index_of(&self, id: i32) -> Option<usize> {
+            self.vertices.iter().position(|v| {v.id == id})
+        }
+
+        /**
+         * Builds an adjacency list over vertex positions.
+         * Entry i holds (neighbor position, edge weight) for every edge leaving vertices[i].
+         * If directed is false, every edge is also added in the reverse direction,
+         * which is how the algorithms for undirected graphs read the edge list.
+         */
+        pub fn adjacency_list(&self, directed: bool) -> Vec<Vec<(usize, i32)>> {
+            let mut adj: Vec<Vec<(usize, i32)>> = vec![Vec::new(); self.vertices.len()];
+
+            for edge in self.edges.iter() {
+                let from = self.index_of(edge.from.id).expect("Edge endpoint is not a vertex of the graph");
+                let to = self.index_of(edge.to.id).expect("Edge endpoint is not a vertex of the graph");
+                adj[from].push((to, edge.weight));
+                if !directed && from != to {
+                    adj[to].push((from, edge.weight));
+                }
+            }
+
+            adj
+        }
     }
 
     pub struct CNF {
@@ -256,26 +335,22 @@
      * BFS is better suited to search for the shortest path between two points.
      * Dijkstra's algorithm is a variation of BFS
      */
-    pub fn bfs(graph: &mut Graph, start: usize) -> Vec<i32> {
+    pub fn bfs(graph: &Graph, start: usize) -> Vec<i32> {
         //Initialize data to be used
-        let mut distances: Vec<i32> = Vec::new();
-        for _i in 0..graph.vertices.len() - 1 {
-            distances.push(i32::MAX);
-        }
+        let adj = graph.adjacency_list(true);
+        let mut distances: Vec<i32> = vec![i32::MAX; graph.vertices.len()];
         distances[start] = 0;
         let mut q: Queue<usize> = Queue::new();
+        q.add(start).unwrap();
         
         //visit each vertex level-by-level
         while q.size() > 0 {
             let u = q.remove().unwrap();
-            let outgoing_edges = graph.edges.iter().filter(|e| {e.from == graph.vertices[u as usize]});
         
-            for edge in outgoing_edges {
-                let v_to_index = graph.vertices.iter().position(|&r| r == edge.to).unwrap();
-                
-                if distances[v_to_index] == i32::MAX {
-                    q.add(v_to_index).unwrap();
-                    distances[v_to_index] = distances[u] + 1;
+            for &(v, _weight) in adj[u].iter() {
+                if distances[v] == i32::MAX {
+                    q.add(v).unwrap();
+                    distances[v] = distances[u] + 1;
                 }
             }
         }
@@ -294,37 +369,38 @@
      * The negative values used in this implementation, therefore, are used as a work-around.
      */
     pub fn dijkstra(graph: &Graph, start: Vertex) -> Vec<Option<Vertex>> {
-        let mut dist = vec![];
-        let mut prev: Vec<Option<Vertex>> = Vec::new();
-
-        for i in 0..graph.vertices.len() - 1 {
-            prev[i] = None;
-            if graph.vertices[i] == start {
-                dist[i] = 0;  
-            } else {
-                dist[i] = i32::MIN;
-            }
-        }
-        let mut pq: PriorityQueue<Vertex, i32> = PriorityQueue::new();
-        for v in 0..graph.vertices.len() - 1 {
-            pq.push(graph.vertices[v], dist[v]);
-        }
-
-        while !pq.is_empty() {
-            let (u, _val) = pq.pop().unwrap();
-
-            for edge in graph.edges.iter().filter(|j|{j.from == u}) {
-                let j = graph.vertices.iter().position(|v| {*v == edge.from}).unwrap();
-                let k = graph.vertices.iter().position(|v| {*v == edge.to}).unwrap();
+        dijkstra_paths(graph, start).1
+    }
 
-                if dist[k] < dist[j] - edge.weight {
-                    dist[k] =  dist[j] - edge.weight;
-                    prev[k] = Some(graph.vertices[j]);
-                    pq.change_priority(&graph.vertices[k], dist[k]);
+    /**
+     * Dijkstra's algorithm returning both outputs of the search:
+     * the distance from start to every vertex (i32::MAX if unreachable, as in bfs)
+     * and the previous vertex on each shortest path.
+     * The indices of both arrays match the indices of the vertices in the graph struct.
+     */
+    pub fn dijkstra_paths(graph: &Graph, start: Vertex) -> (Vec<i32>, Vec<Option<Vertex>>) {
+        let adj = graph.adjacency_list(true);
+        let start_index = graph.index_of(start.id).expect("Start vertex is not in the graph");
+        let mut dist: Vec<i32> = vec![i32::MAX; graph.vertices.len()];
+        let mut prev: Vec<Option<Vertex>> = vec![None; graph.vertices.len()];
+        let mut done: Vec<bool> = vec![false; graph.vertices.len()];
+
+        dist[start_index] = 0;
+        let mut pq: PriorityQueue<usize, i32> = PriorityQueue::new();
+        pq.push(start_index, 0);
+
+        while let Some((u, _val)) = pq.pop() {
+            done[u] = true;
+
+            for &(k, weight) in adj[u].iter() {
+                if !done[k] && dist[u] + weight < dist[k] {
+                    dist[k] = dist[u] + weight;
+                    prev[k] = Some(graph.vertices[u]);
+                    pq.push_increase(k, -dist[k]);
                 }
             }
         }
-        prev
+        (dist, prev)
     }
 
     /**
@@ -589,6 +665,87 @@
         answers
     }
 
-    pub fn page_rank() {
+    /**
+     * PageRank scores a vertex by the chance that a random surfer is on it.
+     * At each step the surfer follows a random outgoing edge with probability damping
+     * (usually 0.85), and otherwise jumps to a vertex chosen uniformly at random.
+     * A vertex without outgoing edges sends its surfer to a random vertex.
+     *
+     * This is the power iteration method: the rank vector is updated until the total
+     * change between two iterations is below tolerance or max_iterations is reached.
+     * Edge weights are ignored. The scores sum to 1, with indices matching graph.vertices.
+     *
+     * Runtime: O(n + m) per iteration
+     */
+    pub fn page_rank(graph: &Graph, damping: f64, tolerance: f64, max_iterations: usize) -> Vec<f64> {
+        let n = graph.vertices.len();
+        if n == 0 {
+            return Vec::new();
+        }
+        let adj = graph.adjacency_list(true);
+        let mut ranks: Vec<f64> = vec![1.0 / n as f64; n];
+
+        for _i in 0..max_iterations {
+            //rank held by vertices without outgoing edges is spread over all vertices
+            let dangling: f64 = (0..n).filter(|&u| {adj[u].is_empty()}).map(|u| {ranks[u]}).sum();
+            let base = (1.0 - damping) / n as f64 + damping * dangling / n as f64;
+            let mut next: Vec<f64> = vec![base; n];
+
+            for (u, outgoing) in adj.iter().enumerate() {
+                for &(v, _weight) in outgoing.iter() {
+                    next[v] += damping * ranks[u] / outgoing.len() as f64;
+                }
+            }
+
+            let change: f64 = next.iter().zip(ranks.iter()).map(|(a, b)| {(a - b).abs()}).sum();
+            ranks = next;
+            if change < tolerance {
+                break;
+            }
+        }
+        ranks
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
 
+        fn graph_of(n: i32, pairs: &[(i32, i32)]) -> Graph {
+            let vertices: Vec<Vertex> = (1..=n).map(Vertex::new).collect();
+            let edges = pairs.iter().map(|&(u, v)| {Edge::new(Vertex::new(u), Vertex::new(v), 1)}).collect();
+            Graph::new(edges, vertices)
+        }
+
+        #[test]
+        fn ids_survive_remove_node() {
+            let mut graph = graph_of(4, &[(1, 2), (2, 3), (3, 4), (4, 1)]);
+            assert_eq!(graph.remove_node(2).map(|v| {v.id}), Some(2));
+            assert!(graph.remove_node(2).is_none());
+            //the others keep their ids and only the edges of 2 are gone
+            assert_eq!(graph.vertices.iter().map(|v| {v.id}).collect::<Vec<i32>>(), vec![1, 3, 4]);
+            assert_eq!(graph.edges.iter().map(|e| {(e.from.id, e.to.id)}).collect::<Vec<(i32, i32)>>(), vec![(3, 4), (4, 1)]);
+            assert_eq!((graph.index_of(3), graph.index_of(2)), (Some(1), None));
+
+            //new ids are never reused, even the removed ones or the highest after it goes
+            assert_eq!(graph.add_node().id, 5);
+            graph.remove_node(5);
+            assert_eq!(graph.add_node().id, 6);
+            assert_eq!(Graph::new(Vec::new(), Vec::new()).add_node().id, 1);
+        }
+
+        #[test]
+        fn edges_need_both_endpoints() {
+            let mut graph = graph_of(3, &[(1, 2)]);
+            assert!(graph.add_edge(1, 4, 5).is_none());
+            assert!(graph.add_edge(7, 1, 5).is_none());
+            assert_eq!(graph.edges.len(), 1);
+            let edge = graph.add_edge(2, 3, 5).unwrap();
+            assert_eq!((edge.from.id, edge.to.id, edge.weight), (2, 3, 5));
+
+            graph.remove_node(3);
+            assert!(graph.add_edge(2, 3, 1).is_none());
+            assert_eq!(graph.remove_edge(1, 2).map(|e| {e.weight}), Some(1));
+            assert!(graph.remove_edge(1, 2).is_none());
+            assert!(graph.edges.is_empty());
+        }
     }
\ No newline at end of file
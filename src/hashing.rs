@@ -0,0 +1,546 @@
+pub mod hash_algos {
+    use std::convert::TryInto;
+
+    /**
+     * A cryptographic hash function that takes its input in pieces:
+     * create it with default(), feed it with update() and read the hash with finalize().
+     */
+    pub trait Digest: Clone + Default {
+        //length of the hash in bytes
+        const OUTPUT_SIZE: usize;
+        //size of the blocks the input is processed in, in bytes
+        const BLOCK_SIZE: usize;
+
+        fn update(&mut self, data: &[u8]);
+
+        fn finalize(self) -> Vec<u8>;
+
+        /**
+         * The hash of data in one call
+         */
+        fn digest(data: &[u8]) -> Vec<u8> {
+            let mut hasher = Self::default();
+            hasher.update(data);
+            hasher.finalize()
+        }
+    }
+
+    //first 32 bits of the fractional parts of the cube roots of the first 64 primes
+    const SHA256_K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+        0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+        0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+        0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+        0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+        0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+        0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2
+    ];
+
+    //first 32 bits of the fractional parts of the square roots of the first 8 primes
+    const SHA256_H0: [u32; 8] = [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+    /**
+     * SHA-256 (FIPS 180-4). The message is padded with a 1 bit, zeros and its length in bits
+     * to a multiple of 64 bytes, and every 64-byte block goes through 64 rounds of a
+     * compression function that mixes it into 8 words of state.
+     */
+    #[derive(Clone, Debug)]
+    pub struct Sha256 {
+        state: [u32; 8],
+        buffer: Vec<u8>,            //input not yet making a whole block
+        length: u64                 //bytes seen so far
+    }
+
+    impl Default for Sha256 {
+        fn default() -> Self {
+            Sha256{state: SHA256_H0, buffer: Vec::with_capacity(64), length: 0}
+        }
+    }
+
+    impl Sha256 {
+        fn compress(&mut self, block: &[u8]) {
+            let mut w = [0u32; 64];
+            for (i, word) in block.chunks(4).enumerate() {
+                w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+            }
+            for i in 16..64 {
+                let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+                let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+                w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+            }
+
+            let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+            for i in 0..64 {
+                let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+                let choice = (e & f) ^ (!e & g);
+                let t1 = h.wrapping_add(s1).wrapping_add(choice).wrapping_add(SHA256_K[i]).wrapping_add(w[i]);
+                let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+                let majority = (a & b) ^ (a & c) ^ (b & c);
+                let t2 = s0.wrapping_add(majority);
+                h = g;
+                g = f;
+                f = e;
+                e = d.wrapping_add(t1);
+                d = c;
+                c = b;
+                b = a;
+                a = t1.wrapping_add(t2);
+            }
+
+            for (word, added) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+                *word = word.wrapping_add(added);
+            }
+        }
+    }
+
+    impl Digest for Sha256 {
+        const OUTPUT_SIZE: usize = 32;
+        const BLOCK_SIZE: usize = 64;
+
+        fn update(&mut self, data: &[u8]) {
+            self.length += data.len() as u64;
+            self.buffer.extend_from_slice(data);
+            let whole = self.buffer.len() / 64 * 64;
+            let blocks: Vec<u8> = self.buffer.drain(..whole).collect();
+            for block in blocks.chunks(64) {
+                self.compress(block);
+            }
+        }
+
+        fn finalize(mut self) -> Vec<u8> {
+            let bit_length = self.length.wrapping_mul(8);
+            let mut padding = vec![0x80u8];
+            padding.resize((119 - self.buffer.len()) % 64 + 1, 0);
+            padding.extend_from_slice(&bit_length.to_be_bytes());
+            self.update(&padding);
+            self.state.iter().flat_map(|word| {word.to_be_bytes()}).collect()
+        }
+    }
+
+    //first 64 bits of the fractional parts of the cube roots of the first 80 primes
+    const SHA512_K: [u64; 80] = [
+        0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
+        0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
+        0xd807aa98a3030242, 0x12835b0145706fbe, 0x243185be4ee4b28c, 0x550c7dc3d5ffb4e2,
+        0x72be5d74f27b896f, 0x80deb1fe3b1696b1, 0x9bdc06a725c71235, 0xc19bf174cf692694,
+        0xe49b69c19ef14ad2, 0xefbe4786384f25e3, 0x0fc19dc68b8cd5b5, 0x240ca1cc77ac9c65,
+        0x2de92c6f592b0275, 0x4a7484aa6ea6e483, 0x5cb0a9dcbd41fbd4, 0x76f988da831153b5,
+        0x983e5152ee66dfab, 0xa831c66d2db43210, 0xb00327c898fb213f, 0xbf597fc7beef0ee4,
+        0xc6e00bf33da88fc2, 0xd5a79147930aa725, 0x06ca6351e003826f, 0x142929670a0e6e70,
+        0x27b70a8546d22ffc, 0x2e1b21385c26c926, 0x4d2c6dfc5ac42aed, 0x53380d139d95b3df,
+        0x650a73548baf63de, 0x766a0abb3c77b2a8, 0x81c2c92e47edaee6, 0x92722c851482353b,
+        0xa2bfe8a14cf10364, 0xa81a664bbc423001, 0xc24b8b70d0f89791, 0xc76c51a30654be30,
+        0xd192e819d6ef5218, 0xd69906245565a910, 0xf40e35855771202a, 0x106aa07032bbd1b8,
+        0x19a4c116b8d2d0c8, 0x1e376c085141ab53, 0x2748774cdf8eeb99, 0x34b0bcb5e19b48a8,
+        0x391c0cb3c5c95a63, 0x4ed8aa4ae3418acb, 0x5b9cca4f7763e373, 0x682e6ff3d6b2b8a3,
+        0x748f82ee5defb2fc, 0x78a5636f43172f60, 0x84c87814a1f0ab72, 0x8cc702081a6439ec,
+        0x90befffa23631e28, 0xa4506cebde82bde9, 0xbef9a3f7b2c67915, 0xc67178f2e372532b,
+        0xca273eceea26619c, 0xd186b8c721c0c207, 0xeada7dd6cde0eb1e, 0xf57d4f7fee6ed178,
+        0x06f067aa72176fba, 0x0a637dc5a2c898a6, 0x113f9804bef90dae, 0x1b710b35131c471b,
+        0x28db77f523047d84, 0x32caab7b40c72493, 0x3c9ebe0a15c9bebc, 0x431d67c49c100d4c,
+        0x4cc5d4becb3e42b6, 0x597f299cfc657e2a, 0x5fcb6fab3ad6faec, 0x6c44198c4a475817
+    ];
+
+    //first 64 bits of the fractional parts of the square roots of the first 8 primes
+    const SHA512_H0: [u64; 8] = [
+        0x6a09e667f3bcc908, 0xbb67ae8584caa73b, 0x3c6ef372fe94f82b, 0xa54ff53a5f1d36f1,
+        0x510e527fade682d1, 0x9b05688c2b3e6c1f, 0x1f83d9abfb41bd6b, 0x5be0cd19137e2179
+    ];
+
+    /**
+     * SHA-512 (FIPS 180-4). The same design as SHA-256 with 64-bit words, 128-byte blocks,
+     * 80 rounds and a 128-bit message length. It is faster than SHA-256 on 64-bit machines.
+     */
+    #[derive(Clone, Debug)]
+    pub struct Sha512 {
+        state: [u64; 8],
+        buffer: Vec<u8>,
+        length: u128
+    }
+
+    impl Default for Sha512 {
+        fn default() -> Self {
+            Sha512{state: SHA512_H0, buffer: Vec::with_capacity(128), length: 0}
+        }
+    }
+
+    impl Sha512 {
+        fn compress(&mut self, block: &[u8]) {
+            let mut w = [0u64; 80];
+            for (i, word) in block.chunks(8).enumerate() {
+                w[i] = u64::from_be_bytes(word.try_into().unwrap());
+            }
+            for i in 16..80 {
+                let s0 = w[i - 15].rotate_right(1) ^ w[i - 15].rotate_right(8) ^ (w[i - 15] >> 7);
+                let s1 = w[i - 2].rotate_right(19) ^ w[i - 2].rotate_right(61) ^ (w[i - 2] >> 6);
+                w[i] = w[i - 16].wrapping_add(s0).wrapping_add(w[i - 7]).wrapping_add(s1);
+            }
+
+            let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+            for i in 0..80 {
+                let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+                let choice = (e & f) ^ (!e & g);
+                let t1 = h.wrapping_add(s1).wrapping_add(choice).wrapping_add(SHA512_K[i]).wrapping_add(w[i]);
+                let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+                let majority = (a & b) ^ (a & c) ^ (b & c);
+                let t2 = s0.wrapping_add(majority);
+                h = g;
+                g = f;
+                f = e;
+                e = d.wrapping_add(t1);
+                d = c;
+                c = b;
+                b = a;
+                a = t1.wrapping_add(t2);
+            }
+
+            for (word, added) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+                *word = word.wrapping_add(added);
+            }
+        }
+    }
+
+    impl Digest for Sha512 {
+        const OUTPUT_SIZE: usize = 64;
+        const BLOCK_SIZE: usize = 128;
+
+        fn update(&mut self, data: &[u8]) {
+            self.length += data.len() as u128;
+            self.buffer.extend_from_slice(data);
+            let whole = self.buffer.len() / 128 * 128;
+            let blocks: Vec<u8> = self.buffer.drain(..whole).collect();
+            for block in blocks.chunks(128) {
+                self.compress(block);
+            }
+        }
+
+        fn finalize(mut self) -> Vec<u8> {
+            let bit_length = self.length.wrapping_mul(8);
+            let mut padding = vec![0x80u8];
+            padding.resize((239 - self.buffer.len()) % 128 + 1, 0);
+            padding.extend_from_slice(&bit_length.to_be_bytes());
+            self.update(&padding);
+            self.state.iter().flat_map(|word| {word.to_be_bytes()}).collect()
+        }
+    }
+
+    const KECCAK_ROUND_CONSTANTS: [u64; 24] = [
+        0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+        0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+        0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+        0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+        0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+        0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008
+    ];
+
+    //rotation of each lane in the order the combined rho and pi steps visit them
+    const KECCAK_ROTATIONS: [u32; 24] = [1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44];
+    const KECCAK_LANES: [usize; 24] = [10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1];
+
+    /**
+     * The Keccak-f[1600] permutation on 25 lanes of 64 bits (lane x + 5y is at state[x + 5y]).
+     * Each of the 24 rounds applies θ (mix in column parities), ρ and π (rotate the lanes and
+     * move them around), χ (the only nonlinear step) and ι (add a round constant).
+     */
+    pub fn keccak_f(state: &mut [u64; 25]) {
+        for &round_constant in KECCAK_ROUND_CONSTANTS.iter() {
+            //θ
+            let mut parity = [0u64; 5];
+            for (x, p) in parity.iter_mut().enumerate() {
+                *p = state[x] ^ state[x + 5] ^ state[x + 10] ^ state[x + 15] ^ state[x + 20];
+            }
+            for x in 0..5 {
+                let d = parity[(x + 4) % 5] ^ parity[(x + 1) % 5].rotate_left(1);
+                for y in 0..5 {
+                    state[x + 5 * y] ^= d;
+                }
+            }
+
+            //ρ and π
+            let mut carried = state[1];
+            for (&lane, &rotation) in KECCAK_LANES.iter().zip(KECCAK_ROTATIONS.iter()) {
+                let next = state[lane];
+                state[lane] = carried.rotate_left(rotation);
+                carried = next;
+            }
+
+            //χ
+            for y in 0..5 {
+                let row = [state[5 * y], state[5 * y + 1], state[5 * y + 2], state[5 * y + 3], state[5 * y + 4]];
+                for x in 0..5 {
+                    state[x + 5 * y] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+                }
+            }
+
+            //ι
+            state[0] ^= round_constant;
+        }
+    }
+
+    /**
+     * SHA-3 (FIPS 202) with an output of OUTPUT bytes. Unlike SHA-2 it is a sponge:
+     * the input is XORed into the first 200 - 2 * OUTPUT bytes of a 200-byte state (the rate),
+     * with Keccak-f applied after each such block, and the hash is read from the rate at the
+     * end. The rest of the state (the capacity) is never touched directly, which is what
+     * makes it secure and immune to length extension.
+     */
+    #[derive(Clone, Debug)]
+    pub struct Sha3<const OUTPUT: usize> {
+        state: [u64; 25],
+        buffer: Vec<u8>
+    }
+
+    pub type Sha3_224 = Sha3<28>;
+    pub type Sha3_256 = Sha3<32>;
+    pub type Sha3_384 = Sha3<48>;
+    pub type Sha3_512 = Sha3<64>;
+
+    impl<const OUTPUT: usize> Default for Sha3<OUTPUT> {
+        fn default() -> Self {
+            Sha3{state: [0; 25], buffer: Vec::with_capacity(200 - 2 * OUTPUT)}
+        }
+    }
+
+    impl<const OUTPUT: usize> Sha3<OUTPUT> {
+        const RATE: usize = 200 - 2 * OUTPUT;
+
+        //XOR a block into the state, lanes being little-endian, and permute
+        fn absorb(&mut self, block: &[u8]) {
+            for (lane, bytes) in self.state.iter_mut().zip(block.chunks(8)) {
+                *lane ^= u64::from_le_bytes(bytes.try_into().unwrap());
+            }
+            keccak_f(&mut self.state);
+        }
+    }
+
+    impl<const OUTPUT: usize> Digest for Sha3<OUTPUT> {
+        const OUTPUT_SIZE: usize = OUTPUT;
+        const BLOCK_SIZE: usize = 200 - 2 * OUTPUT;
+
+        fn update(&mut self, data: &[u8]) {
+            self.buffer.extend_from_slice(data);
+            let whole = self.buffer.len() / Self::RATE * Self::RATE;
+            let blocks: Vec<u8> = self.buffer.drain(..whole).collect();
+            for block in blocks.chunks(Self::RATE) {
+                self.absorb(block);
+            }
+        }
+
+        fn finalize(mut self) -> Vec<u8> {
+            //domain separation bits 01, then the padding 10*1
+            let mut block = std::mem::take(&mut self.buffer);
+            block.push(0x06);
+            block.resize(Self::RATE, 0);
+            block[Self::RATE - 1] |= 0x80;
+            self.absorb(&block);
+            self.state.iter().flat_map(|lane| {lane.to_le_bytes()}).take(OUTPUT).collect()
+        }
+    }
+
+    /**
+     * HMAC (RFC 2104): H((key ^ opad) || H((key ^ ipad) || message)), with the key padded
+     * to the block size of H (or hashed first if longer). Unlike a plain hash of key and
+     * message, it resists length extension and forgery.
+     */
+    #[derive(Clone, Debug)]
+    pub struct Hmac<H: Digest> {
+        inner: H,
+        outer_key: Vec<u8>
+    }
+
+    impl<H: Digest> Hmac<H> {
+        pub fn new(key: &[u8]) -> Self {
+            let mut padded_key = if key.len() > H::BLOCK_SIZE {H::digest(key)} else {key.to_vec()};
+            padded_key.resize(H::BLOCK_SIZE, 0);
+
+            let mut inner = H::default();
+            inner.update(&padded_key.iter().map(|b| {b ^ 0x36}).collect::<Vec<u8>>());
+            let outer_key = padded_key.iter().map(|b| {b ^ 0x5c}).collect();
+            Hmac{inner, outer_key}
+        }
+
+        pub fn update(&mut self, data: &[u8]) {
+            self.inner.update(data);
+        }
+
+        pub fn finalize(self) -> Vec<u8> {
+            let mut outer = H::default();
+            outer.update(&self.outer_key);
+            outer.update(&self.inner.finalize());
+            outer.finalize()
+        }
+    }
+
+    /**
+     * The HMAC of a message in one call
+     */
+    pub fn hmac<H: Digest>(key: &[u8], message: &[u8]) -> Vec<u8> {
+        let mut mac = Hmac::<H>::new(key);
+        mac.update(message);
+        mac.finalize()
+    }
+
+    /**
+     * HKDF-Extract (RFC 5869): concentrates the entropy of the input keying material into
+     * a pseudorandom key, HMAC(salt, ikm). An empty salt stands for a string of zeros.
+     */
+    pub fn hkdf_extract<H: Digest>(salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+        if salt.is_empty() {
+            hmac::<H>(&vec![0; H::OUTPUT_SIZE], ikm)
+        } else {
+            hmac::<H>(salt, ikm)
+        }
+    }
+
+    /**
+     * HKDF-Expand (RFC 5869): len bytes of output keying material from a pseudorandom key,
+     * T(1) || T(2) || ... with T(i) = HMAC(prk, T(i-1) || info || i).
+     * Returns None if more than 255 hashes' worth of output is asked for.
+     */
+    pub fn hkdf_expand<H: Digest>(prk: &[u8], info: &[u8], len: usize) -> Option<Vec<u8>> {
+        if len > 255 * H::OUTPUT_SIZE {
+            return None;
+        }
+        let mut okm: Vec<u8> = Vec::with_capacity(len + H::OUTPUT_SIZE);
+        let mut previous: Vec<u8> = Vec::new();
+        let mut counter: u8 = 1;
+        while okm.len() < len {
+            let mut mac = Hmac::<H>::new(prk);
+            mac.update(&previous);
+            mac.update(info);
+            mac.update(&[counter]);
+            previous = mac.finalize();
+            okm.extend_from_slice(&previous);
+            counter = counter.wrapping_add(1);
+        }
+        okm.truncate(len);
+        Some(okm)
+    }
+
+    /**
+     * HKDF extract-then-expand: len bytes of key material derived from ikm
+     */
+    pub fn hkdf<H: Digest>(salt: &[u8], ikm: &[u8], info: &[u8], len: usize) -> Option<Vec<u8>> {
+        hkdf_expand::<H>(&hkdf_extract::<H>(salt, ikm), info, len)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const ABC: &[u8] = b"abc";
+        const MESSAGE_448: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+
+        fn hex(bytes: &[u8]) -> String {
+            bytes.iter().map(|byte| {format!("{:02x}", byte)}).collect()
+        }
+
+        //one million 'a's, fed in chunks of uneven sizes that straddle the block boundaries
+        fn million_a<H: Digest>() -> String {
+            let a = [b'a'; 1000];
+            let sizes = [1, 7, 55, 63, 64, 65, 127, 128, 129, 136, 1000];
+            let mut hasher = H::default();
+            let mut fed = 0;
+            for &size in sizes.iter().cycle() {
+                let size = size.min(1_000_000 - fed);
+                hasher.update(&a[..size]);
+                fed += size;
+                if fed == 1_000_000 {
+                    break;
+                }
+            }
+            hex(&hasher.finalize())
+        }
+
+        //the FIPS 180-4 and FIPS 202 example hashes of "abc", the 448-bit message and a million 'a's
+        fn check<H: Digest>(abc: &str, message_448: &str, million: &str) {
+            assert_eq!(hex(&H::digest(ABC)), abc);
+            assert_eq!(hex(&H::digest(MESSAGE_448)), message_448);
+            assert_eq!(million_a::<H>(), million);
+        }
+
+        #[test]
+        fn sha256_examples() {
+            check::<Sha256>(
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+                "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+            );
+        }
+
+        #[test]
+        fn sha512_examples() {
+            check::<Sha512>(
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+                "204a8fc6dda82f0a0ced7beb8e08a41657c16ef468b228a8279be331a703c33596fd15c13b1b07f9aa1d3bea57789ca031ad85c7a71dd70354ec631238ca3445",
+                "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973ebde0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b"
+            );
+        }
+
+        #[test]
+        fn sha3_examples() {
+            check::<Sha3_224>(
+                "e642824c3f8cf24ad09234ee7d3c766fc9a3a5168d0c94ad73b46fdf",
+                "8a24108b154ada21c9fd5574494479ba5c7e7ab76ef264ead0fcce33",
+                "d69335b93325192e516a912e6d19a15cb51c6ed5c15243e7a7fd653c"
+            );
+            check::<Sha3_256>(
+                "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+                "41c0dba2a9d6240849100376a8235e2c82e1b9998a999e21db32dd97496d3376",
+                "5c8875ae474a3634ba4fd55ec85bffd661f32aca75c6d699d0cdcb6c115891c1"
+            );
+            check::<Sha3_384>(
+                "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25",
+                "991c665755eb3a4b6bbdfb75c78a492e8c56a22c5c4d7e429bfdbc32b9d4ad5aa04a1f076e62fea19eef51acd0657c22",
+                "eee9e24d78c1855337983451df97c8ad9eedf256c6334f8e948d252d5e0e76847aa0774ddb90a842190d2c558b4b8340"
+            );
+            check::<Sha3_512>(
+                "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+                "04a371e84ecfb5b8b77cb48610fca8182dd457ce6f326a0fd3d7ec2f1e91636dee691fbe0c985302ba1b0d8dc78c086346b533b49c030d99a27daf1139d6e75e",
+                "3c3a876da14034ab60627c077bb98f7e120a2a5370212dffb3385a18d4f38859ed311d0a9d5141ce9cc5c66ee689b266a8aa18ace8282a0e0db596c90b0a7b87"
+            );
+        }
+
+        //RFC 4231, test cases 1 to 4, 6 and 7 (5 is about truncated output)
+        #[test]
+        fn hmac_sha256_rfc4231() {
+            let key_4: Vec<u8> = (1..=25).collect();
+            let cases: [(&[u8], &[u8], &str); 6] = [
+                (&[0x0b; 20], b"Hi There", "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"),
+                (b"Jefe", b"what do ya want for nothing?", "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"),
+                (&[0xaa; 20], &[0xdd; 50], "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe"),
+                (&key_4, &[0xcd; 50], "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b"),
+                (&[0xaa; 131], b"Test Using Larger Than Block-Size Key - Hash Key First", "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"),
+                (
+                    &[0xaa; 131],
+                    b"This is a test using a larger than block-size key and a larger than block-size data. The key needs to be hashed before being used by the HMAC algorithm.",
+                    "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2"
+                )
+            ];
+            for (key, message, expected) in cases.iter() {
+                assert_eq!(hex(&hmac::<Sha256>(key, message)), *expected);
+                //the same through update in two pieces
+                let mut mac = Hmac::<Sha256>::new(key);
+                let (first, second) = message.split_at(message.len() / 3);
+                mac.update(first);
+                mac.update(second);
+                assert_eq!(hex(&mac.finalize()), *expected);
+            }
+        }
+
+        //RFC 5869, test case 1
+        #[test]
+        fn hkdf_sha256_rfc5869() {
+            let ikm = [0x0b; 22];
+            let salt: Vec<u8> = (0x00..=0x0c).collect();
+            let info: Vec<u8> = (0xf0..=0xf9).collect();
+            let prk = hkdf_extract::<Sha256>(&salt, &ikm);
+            assert_eq!(hex(&prk), "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5");
+            let okm = "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865";
+            assert_eq!(hex(&hkdf_expand::<Sha256>(&prk, &info, 42).unwrap()), okm);
+            assert_eq!(hex(&hkdf::<Sha256>(&salt, &ikm, &info, 42).unwrap()), okm);
+            assert_eq!(hkdf_expand::<Sha256>(&prk, &info, 255 * 32 + 1), None);
+        }
+    }
+}
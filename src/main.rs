@@ -1,17 +1,38 @@
 #![recursion_limit="512"]
 pub mod dynamic;
-mod graph;
-mod rsa;
+pub mod diff;
+pub mod alignment;
+pub mod graph;
+pub mod rsa;
+pub mod rsa_audit;
+pub mod discrete_log;
+pub mod elliptic_curve;
+pub mod curve25519;
+pub mod hashing;
+pub mod encoding;
+pub mod drbg;
 pub mod divide_conquer;
 pub mod randomized;
+pub mod modular;
+pub mod primes;
+pub mod factorization;
 pub mod max_flow_lp;
+pub mod coloring;
+pub mod community;
+pub mod centrality;
+pub mod data_structures;
+pub mod generators;
 use dynamic::dynamic_algos;
+use diff::diff_algos;
+use alignment::alignment_algos::{self, GapPenalty, SubstitutionMatrix};
 use graph::*;
 use randomized::randomized_algos;
-use dynamic::dynamic_algos::Item;
+use dynamic::dynamic_algos::{EditCosts, Item, Monotonicity};
 use divide_conquer::dc_algos;
 use max_flow_lp::max_flow_and_lp_algos;
 use rsa::*;
+use modular::modular_arith::MontgomeryFixed;
+use num::BigUint;
 
 fn main() {
     //lcs
@@ -20,7 +41,33 @@ fn main() {
     let str1: String = String::from(s1);
     let str2: String = String::from(s2);
     let lcs_answer = dynamic_algos::lcs(str1, str2);
-    println!("{}", lcs_answer);
+    println!("{}, {}", lcs_answer.length, lcs_answer.subsequence.iter().collect::<String>());
+    let words_1: Vec<&str> = "the quick brown fox jumps".split(' ').collect();
+    let words_2: Vec<&str> = "the quick red fox leaps".split(' ').collect();
+    println!("{:?}", dynamic_algos::lcs_hirschberg(&words_1, &words_2).subsequence);
+
+    //edit distances
+    let kitten: Vec<char> = "kitten".chars().collect();
+    let sitting: Vec<char> = "sitting".chars().collect();
+    let edits = dynamic_algos::edit_script(&kitten, &sitting, &EditCosts::default());
+    println!("{}, {:?}", edits.distance, edits.operations);
+    let ca: Vec<char> = "ca".chars().collect();
+    let abc: Vec<char> = "abc".chars().collect();
+    println!("{}, {}, {:?}", dynamic_algos::osa_distance(&ca, &abc), dynamic_algos::damerau_levenshtein(&ca, &abc), dynamic_algos::levenshtein_bounded(&kitten, &sitting, 2));
+
+    //sequence alignment
+    let blosum62 = SubstitutionMatrix::blosum62();
+    let gaps = GapPenalty::affine(12, 1);
+    let (protein_1, protein_2) = (b"HEAGAWGHEE", b"PAWHEAE");
+    let global = alignment_algos::needleman_wunsch(protein_1, protein_2, &blosum62, &gaps);
+    print!("{}", global.pretty(protein_1, protein_2, &blosum62, 60));
+    let local = alignment_algos::smith_waterman(protein_1, protein_2, &SubstitutionMatrix::pam250(), &gaps);
+    print!("{}", local.pretty(protein_1, protein_2, &SubstitutionMatrix::pam250(), 60));
+
+    //diff
+    let old_config = "host = localhost\nport = 8080\ndebug = true\nworkers = 4\n";
+    let new_config = "host = localhost\nport = 9090\ndebug = true\nworkers = 4\nlog = info\n";
+    print!("{}", diff_algos::unified_diff(old_config, new_config, "old.conf", "new.conf", 1));
 
     //lis
     let mut arr = Vec::new();
@@ -29,7 +76,11 @@ fn main() {
         arr.push(i);
     }
     let lis_answer = dynamic_algos::lis(arr);
-    println!("{}", lis_answer);
+    println!("{}, {:?}", lis_answer.length, lis_answer.indices);
+    let patience_answer = dynamic_algos::lis_patience(&vals, Monotonicity::NonIncreasing);
+    let count_answer = dynamic_algos::count_lis(&vals, Monotonicity::StrictlyIncreasing);
+    let bitonic_answer = dynamic_algos::longest_bitonic(&vals);
+    println!("{:?}, {}, {:?}", patience_answer.indices, count_answer, bitonic_answer.indices);
 
     //knapsack no rep
     //knapsack with rep
@@ -40,11 +91,31 @@ fn main() {
     let item5 = Item::new(4, 1);
     let item_arr = [item1, item2, item3, item4, item5];
     let no_rep_answer = dynamic_algos::knapsack_no_rep(&item_arr, 8);
-    let with_rep_answer = dynamic_algos::knapsack_no_rep(&item_arr, 8);
-    println!("{}, {}", no_rep_answer, with_rep_answer);
+    let with_rep_answer = dynamic_algos::knapsack_with_rep(&item_arr, 8);
+    println!("{}, {:?}, {}, {:?}", no_rep_answer.value, no_rep_answer.items, with_rep_answer.value, with_rep_answer.items);
 
     //chain matrix multiply
     let m_arr = [2, 4, 6, 7, 4, 3, 3, 2, 5, 6, 8, 2, 2];
     let cost_answer = dynamic_algos::chain_matrix_multiply(&m_arr);
-    println!("{}", cost_answer);
+    println!("{}, {}", cost_answer.cost, cost_answer.order);
+
+    //rsa key serialization, with a key written by OpenSSL
+    let pem = include_str!("../fixtures/rsa/private_pkcs8.pem");
+    let key = RsaPrivateKey::from_pkcs8_pem(pem).unwrap();
+    println!("{}, {}", key.bits(), key.to_pkcs8_pem() == pem);
+
+    //constant-time exponentiation: the same operations for exponents of any bit pattern
+    let modulus = BigUint::from(0xffff_ffff_ffff_ffc5u64);
+    let fixed = MontgomeryFixed::new(&modulus);
+    let mut counts = Vec::new();
+    for exp in [1u64, 0x8000_0000_0000_0000, 0xffff_ffff_ffff_ffff, 0x5555_5555_5555_5555] {
+        fixed.reset_counts();
+        let power = fixed.pow_fixed_window(&BigUint::from(3u32), &BigUint::from(exp), 64);
+        counts.push(fixed.operation_counts());
+        println!("{}, {:?}", power == BigUint::from(3u32).modpow(&BigUint::from(exp), &modulus), fixed.operation_counts());
+    }
+    println!("{}", counts.iter().all(|c| *c == counts[0]));
+
+    //fermat factoring of a modulus with close primes, 5959 = 101 * 59
+    println!("{:?}", rsa_audit::fermat_factor(&BigUint::from(5959u32), 10));
 }
\ No newline at end of file
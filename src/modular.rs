@@ -0,0 +1,769 @@
+pub mod modular_arith {
+    extern crate num;
+    use num::{BigInt, BigUint, Integer, One, Zero};
+    use std::cell::Cell;
+    use std::fmt;
+    use std::ops::{Add, Sub, Mul, Div, Neg};
+
+    /**
+     * (a * b) % m for u64 values. The product is computed in u128, so it can't overflow.
+     */
+    pub fn mul_mod_u64(a: u64, b: u64, m: u64) -> u64 {
+        ((a as u128 * b as u128) % m as u128) as u64
+    }
+
+    /**
+     * (a + b) % m for u128 values already reduced below m, without overflowing
+     */
+    pub fn add_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+        if a >= m - b {
+            a - (m - b)
+        } else {
+            a + b
+        }
+    }
+
+    /**
+     * (a * b) % m for u128 values.
+     * A u128 product can need 256 bits, so when it could overflow this falls back to
+     * double-and-add: the bits of b are read from the top, and at each bit the result is
+     * doubled and a is added if the bit is set, reducing modulo m at every step.
+     */
+    pub fn mul_mod_u128(a: u128, b: u128, m: u128) -> u128 {
+        let (a, b) = (a % m, b % m);
+        if let Some(product) = a.checked_mul(b) {
+            return product % m;
+        }
+
+        let mut result: u128 = 0;
+        for i in (0..(128 - b.leading_zeros())).rev() {
+            result = add_mod_u128(result, result, m);
+            if (b >> i) & 1 == 1 {
+                result = add_mod_u128(result, a, m);
+            }
+        }
+        result
+    }
+
+    /**
+     * Modular exponentiation base^exp % m by repeated squaring.
+     * The exponent is read from the lowest bit: the running base is squared every step
+     * and multiplied into the result whenever the bit is set.
+     *
+     * Runtime: O(log exp) multiplications
+     */
+    pub fn pow_mod_u64(base: u64, exp: u64, m: u64) -> u64 {
+        if m == 1 {
+            return 0;
+        }
+        let mut result: u64 = 1;
+        let mut b = base % m;
+        let mut e = exp;
+        while e > 0 {
+            if e & 1 == 1 {
+                result = mul_mod_u64(result, b, m);
+            }
+            b = mul_mod_u64(b, b, m);
+            e >>= 1;
+        }
+        result
+    }
+
+    /**
+     * Modular exponentiation base^exp % m for u128 values, see pow_mod_u64
+     */
+    pub fn pow_mod_u128(base: u128, exp: u128, m: u128) -> u128 {
+        if m == 1 {
+            return 0;
+        }
+        let mut result: u128 = 1;
+        let mut b = base % m;
+        let mut e = exp;
+        while e > 0 {
+            if e & 1 == 1 {
+                result = mul_mod_u128(result, b, m);
+            }
+            b = mul_mod_u128(b, b, m);
+            e >>= 1;
+        }
+        result
+    }
+
+    /**
+     * Modular exponentiation for arbitrary size integers
+     */
+    pub fn pow_mod_big(base: &BigUint, exp: &BigUint, m: &BigUint) -> BigUint {
+        base.modpow(exp, m)
+    }
+
+    /**
+     * The multiplicative inverse of a modulo m: the x with (a * x) % m == 1.
+     * It exists only if gcd(a, m) = 1; otherwise None is returned.
+     *
+     * This is the iterative extended Euclidean algorithm. Only the coefficient of a is
+     * tracked, and it is kept reduced modulo m so it never goes negative or overflows.
+     */
+    pub fn inv_mod_u128(a: u128, m: u128) -> Option<u128> {
+        if m == 0 {
+            return None;
+        }
+        let (mut old_r, mut r) = (a % m, m);
+        let (mut old_s, mut s) = (1 % m, 0);        //old_s * a == old_r (mod m)
+
+        while r != 0 {
+            let q = old_r / r;
+            let next_r = old_r - q * r;
+            old_r = r;
+            r = next_r;
+
+            //next_s = old_s - q * s (mod m)
+            let next_s = add_mod_u128(old_s, m - mul_mod_u128(q, s, m), m) % m;
+            old_s = s;
+            s = next_s;
+        }
+
+        //the loop ends one step after the gcd, so the coefficients are swapped
+        if old_r == 1 {
+            Some(old_s)
+        } else {
+            None
+        }
+    }
+
+    /**
+     * The multiplicative inverse of a modulo m for u64 values, see inv_mod_u128
+     */
+    pub fn inv_mod_u64(a: u64, m: u64) -> Option<u64> {
+        inv_mod_u128(a as u128, m as u128).map(|x| {x as u64})
+    }
+
+    /**
+     * The multiplicative inverse of a modulo m for arbitrary size integers, see inv_mod_u128
+     */
+    pub fn inv_mod_big(a: &BigUint, m: &BigUint) -> Option<BigUint> {
+        if m.is_zero() {
+            return None;
+        }
+        let m_signed = BigInt::from(m.clone());
+        let (mut old_r, mut r) = (BigInt::from(a % m), m_signed.clone());
+        let (mut old_s, mut s) = (BigInt::one(), BigInt::zero());
+
+        while !r.is_zero() {
+            let q = &old_r / &r;
+            let next_r = &old_r - &q * &r;
+            old_r = std::mem::replace(&mut r, next_r);
+            let next_s = &old_s - &q * &s;
+            old_s = std::mem::replace(&mut s, next_s);
+        }
+
+        if old_r.is_one() {
+            old_s.mod_floor(&m_signed).to_biguint()
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Montgomery multiplication for a fixed odd modulus n < 2^64.
+     *
+     * Each value x is stored as x * R mod n with R = 2^64 (its Montgomery form).
+     * The product of two such values is reduced with REDC, which replaces the division
+     * by n with a multiplication and a shift by 64 bits:
+     *
+     *  REDC(T) = (T + ((T * n') mod R) * n) / R, where n * n' = -1 (mod R)
+     *
+     * Converting costs two multiplications, so this pays off for repeated operations on
+     * the same modulus, such as exponentiation or many rounds of a primality test.
+     */
+    #[derive(Clone, Copy, Debug)]
+    pub struct Montgomery64 {
+        n: u64,
+        n_prime: u64,           //-n^-1 mod 2^64
+        r2: u64                 //R^2 mod n, used to convert into Montgomery form
+    }
+
+    impl Montgomery64 {
+        pub fn new(n: u64) -> Self {
+            if n.is_multiple_of(2) {
+                panic!("Montgomery multiplication needs an odd modulus.");
+            }
+            //Newton's iteration doubles the number of correct low bits of n^-1 each step
+            let mut inv: u64 = 1;
+            for _i in 0..6 {
+                inv = inv.wrapping_mul(2u64.wrapping_sub(n.wrapping_mul(inv)));
+            }
+            let r = (1u128 << 64) % n as u128;
+            let r2 = (r * r % n as u128) as u64;
+
+            Montgomery64 {
+                n,
+                n_prime: inv.wrapping_neg(),
+                r2
+            }
+        }
+
+        pub fn modulus(&self) -> u64 {
+            self.n
+        }
+
+        //REDC: t / R mod n for t < n * R
+        fn reduce(&self, t: u128) -> u64 {
+            let m = (t as u64).wrapping_mul(self.n_prime);
+            let (sum, overflow) = t.overflowing_add(m as u128 * self.n as u128);
+            let mut result = (sum >> 64) as u64;
+            if overflow || result >= self.n {
+                result = result.wrapping_sub(self.n);
+            }
+            result
+        }
+
+        pub fn to_montgomery(&self, x: u64) -> u64 {
+            self.reduce((x % self.n) as u128 * self.r2 as u128)
+        }
+
+        pub fn from_montgomery(&self, x: u64) -> u64 {
+            self.reduce(x as u128)
+        }
+
+        /**
+         * Product of two values in Montgomery form, in Montgomery form
+         */
+        pub fn mul(&self, a: u64, b: u64) -> u64 {
+            self.reduce(a as u128 * b as u128)
+        }
+
+        /**
+         * base^exp % n, taking and returning ordinary (not Montgomery form) values
+         */
+        pub fn pow(&self, base: u64, exp: u64) -> u64 {
+            let mut result = self.to_montgomery(1);
+            let mut b = self.to_montgomery(base);
+            let mut e = exp;
+            while e > 0 {
+                if e & 1 == 1 {
+                    result = self.mul(result, b);
+                }
+                b = self.mul(b, b);
+                e >>= 1;
+            }
+            self.from_montgomery(result)
+        }
+    }
+
+    /**
+     * Montgomery multiplication for a fixed odd modulus of arbitrary size.
+     * R is 2^(64k) for a k-word modulus, so the division in REDC is a shift by whole words.
+     * See Montgomery64 for the method.
+     */
+    #[derive(Clone, Debug)]
+    pub struct MontgomeryBig {
+        n: BigUint,
+        n_prime: BigUint,       //-n^-1 mod R
+        r_bits: usize,
+        r_mask: BigUint,        //R - 1
+        r2: BigUint
+    }
+
+    impl MontgomeryBig {
+        pub fn new(n: &BigUint) -> Self {
+            if n.is_even() {
+                panic!("Montgomery multiplication needs an odd modulus.");
+            }
+            let r_bits = (n.bits() as usize).div_ceil(64) * 64;
+            let r = BigUint::one() << r_bits;
+            let n_inv = inv_mod_big(n, &r).unwrap();
+            let n_prime = &r - n_inv;
+            let r2 = (&r * &r) % n;
+
+            MontgomeryBig {
+                n: n.clone(),
+                n_prime,
+                r_bits,
+                r_mask: &r - 1u32,
+                r2
+            }
+        }
+
+        pub fn modulus(&self) -> &BigUint {
+            &self.n
+        }
+
+        fn reduce(&self, t: &BigUint) -> BigUint {
+            let m = ((t & &self.r_mask) * &self.n_prime) & &self.r_mask;
+            let result: BigUint = (t + m * &self.n) >> self.r_bits;
+            if result >= self.n {
+                result - &self.n
+            } else {
+                result
+            }
+        }
+
+        pub fn to_montgomery(&self, x: &BigUint) -> BigUint {
+            self.reduce(&((x % &self.n) * &self.r2))
+        }
+
+        pub fn from_montgomery(&self, x: &BigUint) -> BigUint {
+            self.reduce(x)
+        }
+
+        /**
+         * Product of two values in Montgomery form, in Montgomery form
+         */
+        pub fn mul(&self, a: &BigUint, b: &BigUint) -> BigUint {
+            self.reduce(&(a * b))
+        }
+
+        /**
+         * base^exp % n, taking and returning ordinary (not Montgomery form) values
+         */
+        pub fn pow(&self, base: &BigUint, exp: &BigUint) -> BigUint {
+            let mut result = self.to_montgomery(&BigUint::one());
+            let b = self.to_montgomery(base);
+            for i in (0..exp.bits()).rev() {
+                result = self.mul(&result, &result);
+                if exp.bit(i) {
+                    result = self.mul(&result, &b);
+                }
+            }
+            self.from_montgomery(&result)
+        }
+    }
+
+    /**
+     * The operations done by a MontgomeryFixed since it was made or its counts reset
+     */
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct OperationCounts {
+        pub multiplications: u64,
+        pub squarings: u64,
+        pub table_lookups: u64
+    }
+
+    //a + b + carry, as the low 64 bits and the carry out
+    pub fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+        let t = a as u128 + b as u128 + carry as u128;
+        (t as u64, (t >> 64) as u64)
+    }
+
+    //a - b - borrow, as the low 64 bits and the borrow out (0 or 1)
+    pub fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+        let t = (a as u128).wrapping_sub(b as u128 + borrow as u128);
+        (t as u64, (t >> 127) as u64)
+    }
+
+    //a + b * c + carry, as the low 64 bits and the carry out
+    pub fn mac(a: u64, b: u64, c: u64, carry: u64) -> (u64, u64) {
+        let t = a as u128 + b as u128 * c as u128 + carry as u128;
+        (t as u64, (t >> 64) as u64)
+    }
+
+    //all ones for a choice of 1, all zeros for 0
+    pub fn limb_mask(choice: u64) -> u64 {
+        0u64.wrapping_sub(choice)
+    }
+
+    /**
+     * Montgomery multiplication on k limbs of 64 bits, least significant first:
+     * out = a * b / 2^(64k) mod n for a * b < n * 2^(64k), with n_prime = -n^-1 mod 2^64.
+     *
+     * This is the word-by-word (CIOS) form of REDC from Montgomery64: after adding each limb
+     * of b times a, the multiple of n that clears the low limb is added and everything moves
+     * down a limb. The result is then below 2n, and n is always subtracted once, the
+     * difference being kept or dropped with a mask, so the same instructions run for every
+     * value. t is scratch space of k + 2 limbs. Shared by MontgomeryFixed and the elliptic
+     * curve fields.
+     */
+    pub fn montgomery_multiply_limbs(a: &[u64], b: &[u64], n: &[u64], n_prime: u64, t: &mut [u64], out: &mut [u64]) {
+        let k = n.len();
+        t.iter_mut().for_each(|limb| {*limb = 0});
+        for &b_limb in b.iter() {
+            let mut carry = 0;
+            for (t_limb, &a_limb) in t.iter_mut().zip(a.iter()) {
+                let (limb, c) = mac(*t_limb, a_limb, b_limb, carry);
+                *t_limb = limb;
+                carry = c;
+            }
+            let (limb, c) = adc(t[k], carry, 0);
+            t[k] = limb;
+            t[k + 1] = c;
+
+            let m = t[0].wrapping_mul(n_prime);
+            let (_, mut carry) = mac(t[0], m, n[0], 0);
+            for j in 1..k {
+                let (limb, c) = mac(t[j], m, n[j], carry);
+                t[j - 1] = limb;
+                carry = c;
+            }
+            let (limb, c) = adc(t[k], carry, 0);
+            t[k - 1] = limb;
+            t[k] = t[k + 1] + c;
+        }
+
+        let mut borrow = 0;
+        for (limb, (&t_limb, &n_limb)) in out.iter_mut().zip(t.iter().zip(n.iter())) {
+            let (difference, b) = sbb(t_limb, n_limb, borrow);
+            *limb = difference;
+            borrow = b;
+        }
+        let keep = limb_mask(t[k] | (borrow ^ 1));
+        for (limb, &original) in out.iter_mut().zip(t.iter()) {
+            *limb = (*limb & keep) | (original & !keep);
+        }
+    }
+
+    /**
+     * Constant-time Montgomery multiplication for a fixed odd modulus, on numbers of a fixed
+     * number of 64-bit limbs (least significant first) rather than BigUints, whose
+     * arithmetic takes time depending on the values.
+     *
+     * Multiplication is montgomery_multiply_limbs, whose final subtraction of n is always
+     * done and kept or dropped with a mask. The exponentiations below then do the same
+     * operations, on the same memory, for every exponent of a given length, so their
+     * timing can't reveal a secret exponent. The operations are counted, so that this
+     * can be checked.
+     */
+    #[derive(Clone, Debug)]
+    pub struct MontgomeryFixed {
+        n: Vec<u64>,
+        modulus: BigUint,
+        n_prime: u64,           //-n^-1 mod 2^64
+        r2: Vec<u64>,           //R^2 mod n
+        counts: Cell<OperationCounts>
+    }
+
+    impl MontgomeryFixed {
+        pub fn new(n: &BigUint) -> Self {
+            if n.is_even() {
+                panic!("Montgomery multiplication needs an odd modulus.");
+            }
+            let limbs = n.to_u64_digits();
+            let mut inv: u64 = 1;
+            for _i in 0..6 {
+                inv = inv.wrapping_mul(2u64.wrapping_sub(limbs[0].wrapping_mul(inv)));
+            }
+            let r2 = (BigUint::one() << (128 * limbs.len())) % n;
+            let mut fixed = MontgomeryFixed {
+                modulus: n.clone(),
+                n_prime: inv.wrapping_neg(),
+                r2: Vec::new(),
+                counts: Cell::new(OperationCounts::default()),
+                n: limbs
+            };
+            fixed.r2 = fixed.to_limbs(&r2);
+            fixed
+        }
+
+        pub fn modulus(&self) -> &BigUint {
+            &self.modulus
+        }
+
+        pub fn operation_counts(&self) -> OperationCounts {
+            self.counts.get()
+        }
+
+        pub fn reset_counts(&self) {
+            self.counts.set(OperationCounts::default());
+        }
+
+        fn to_limbs(&self, x: &BigUint) -> Vec<u64> {
+            let mut limbs = x.to_u64_digits();
+            limbs.resize(self.n.len(), 0);
+            limbs
+        }
+
+        //a * b / R mod n
+        fn reduce_product(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+            let k = self.n.len();
+            let mut t = vec![0u64; k + 2];
+            let mut reduced = vec![0u64; k];
+            montgomery_multiply_limbs(a, b, &self.n, self.n_prime, &mut t, &mut reduced);
+            reduced
+        }
+
+        /**
+         * x mod n in Montgomery form. The reduction goes through BigUint, so x should not be
+         * secret; exponents are.
+         */
+        pub fn to_montgomery(&self, x: &BigUint) -> Vec<u64> {
+            self.reduce_product(&self.to_limbs(&(x % &self.modulus)), &self.r2)
+        }
+
+        pub fn from_montgomery(&self, x: &[u64]) -> BigUint {
+            let mut one = vec![0u64; self.n.len()];
+            one[0] = 1;
+            BigUint::new(self.reduce_product(x, &one).iter().flat_map(|&limb| {[limb as u32, (limb >> 32) as u32]}).collect())
+        }
+
+        /**
+         * Product of two values in Montgomery form, in Montgomery form
+         */
+        pub fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+            let mut counts = self.counts.get();
+            counts.multiplications += 1;
+            self.counts.set(counts);
+            self.reduce_product(a, b)
+        }
+
+        pub fn square(&self, a: &[u64]) -> Vec<u64> {
+            let mut counts = self.counts.get();
+            counts.squarings += 1;
+            self.counts.set(counts);
+            self.reduce_product(a, a)
+        }
+
+        /**
+         * table[index], found by reading every entry and keeping the right one with a mask,
+         * so the memory accessed doesn't depend on the index
+         */
+        pub fn lookup(&self, table: &[Vec<u64>], index: usize) -> Vec<u64> {
+            let mut counts = self.counts.get();
+            counts.table_lookups += 1;
+            self.counts.set(counts);
+            let mut result = vec![0u64; self.n.len()];
+            for (i, entry) in table.iter().enumerate() {
+                let difference = (i ^ index) as u64;
+                let keep = limb_mask(((difference | difference.wrapping_neg()) >> 63) ^ 1);
+                for (r, &limb) in result.iter_mut().zip(entry.iter()) {
+                    *r |= limb & keep;
+                }
+            }
+            result
+        }
+
+        /**
+         * base^exp % n with a fixed window of 4 bits: the powers base^0..base^15 are
+         * precomputed, and each window of the exponent costs 4 squarings, one lookup and one
+         * multiplication, even when the window is 0.
+         *
+         * exp_bits is the public length of the exponent (such as the size of the modulus);
+         * all of its windows are processed, so leading zero bits don't show either.
+         * Panics if exp has more bits than that.
+         */
+        pub fn pow_fixed_window(&self, base: &BigUint, exp: &BigUint, exp_bits: u64) -> BigUint {
+            if exp.bits() > exp_bits {
+                panic!("The exponent is longer than exp_bits.");
+            }
+            let b = self.to_montgomery(base);
+            let mut table = vec![self.to_montgomery(&BigUint::one()), b.clone()];
+            for i in 2..16 {
+                table.push(self.mul(&table[i - 1], &b));
+            }
+
+            let mut result = table[0].clone();
+            for window in (0..exp_bits.div_ceil(4)).rev() {
+                for _i in 0..4 {
+                    result = self.square(&result);
+                }
+                let index = (0..4).fold(0usize, |acc, bit| {acc | ((exp.bit(4 * window + bit) as usize) << bit)});
+                result = self.mul(&result, &self.lookup(&table, index));
+            }
+            self.from_montgomery(&result)
+        }
+
+        /**
+         * base^exp % n with the Montgomery ladder: R0 = base^j and R1 = base^(j+1) for the
+         * exponent j read so far, and each bit b turns them into R_b * R_(1-b) and R_b^2.
+         * The swaps that decide which is which are masked, so every bit costs one
+         * multiplication and one squaring. Slower than the fixed window but needs no table.
+         * See pow_fixed_window for exp_bits.
+         */
+        pub fn pow_ladder(&self, base: &BigUint, exp: &BigUint, exp_bits: u64) -> BigUint {
+            if exp.bits() > exp_bits {
+                panic!("The exponent is longer than exp_bits.");
+            }
+            let mut r0 = self.to_montgomery(&BigUint::one());
+            let mut r1 = self.to_montgomery(base);
+            for i in (0..exp_bits).rev() {
+                let swap = limb_mask(exp.bit(i) as u64);
+                for (a, b) in r0.iter_mut().zip(r1.iter_mut()) {
+                    let t = swap & (*a ^ *b);
+                    *a ^= t;
+                    *b ^= t;
+                }
+                r1 = self.mul(&r0, &r1);
+                r0 = self.square(&r0);
+                for (a, b) in r0.iter_mut().zip(r1.iter_mut()) {
+                    let t = swap & (*a ^ *b);
+                    *a ^= t;
+                    *b ^= t;
+                }
+            }
+            self.from_montgomery(&r0)
+        }
+    }
+
+    /**
+     * Constant-time modular exponentiation for a secret exponent and an odd modulus,
+     * see MontgomeryFixed::pow_fixed_window. The exponent is processed to the length of
+     * the modulus (or its own length, if longer).
+     */
+    pub fn pow_mod_ct(base: &BigUint, exp: &BigUint, m: &BigUint) -> BigUint {
+        MontgomeryFixed::new(m).pow_fixed_window(base, exp, m.bits().max(exp.bits()))
+    }
+
+    /**
+     * An integer modulo the constant M, with the arithmetic operators working modulo M.
+     * Division multiplies by the inverse and panics if it doesn't exist.
+     *
+     *  type F = ModInt<1_000_000_007>;
+     *  let x = F::new(3) / F::new(2);      //x * 2 == 3
+     */
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct ModInt<const M: u64> {
+        value: u64
+    }
+
+    impl<const M: u64> ModInt<M> {
+        pub fn new(value: u64) -> Self {
+            ModInt {
+                value: value % M
+            }
+        }
+
+        pub fn value(&self) -> u64 {
+            self.value
+        }
+
+        pub fn pow(&self, exp: u64) -> Self {
+            ModInt {
+                value: pow_mod_u64(self.value, exp, M)
+            }
+        }
+
+        /**
+         * The multiplicative inverse, if the value is coprime to M
+         */
+        pub fn inv(&self) -> Option<Self> {
+            inv_mod_u64(self.value, M).map(|value| {ModInt {value}})
+        }
+    }
+
+    impl<const M: u64> From<u64> for ModInt<M> {
+        fn from(value: u64) -> Self {
+            ModInt::new(value)
+        }
+    }
+
+    impl<const M: u64> Add for ModInt<M> {
+        type Output = Self;
+        fn add(self, other: Self) -> Self {
+            ModInt {
+                value: add_mod_u128(self.value as u128, other.value as u128, M as u128) as u64
+            }
+        }
+    }
+
+    impl<const M: u64> Sub for ModInt<M> {
+        type Output = Self;
+        fn sub(self, other: Self) -> Self {
+            self + (-other)
+        }
+    }
+
+    impl<const M: u64> Neg for ModInt<M> {
+        type Output = Self;
+        fn neg(self) -> Self {
+            ModInt {
+                value: (M - self.value) % M
+            }
+        }
+    }
+
+    impl<const M: u64> Mul for ModInt<M> {
+        type Output = Self;
+        fn mul(self, other: Self) -> Self {
+            ModInt {
+                value: mul_mod_u64(self.value, other.value, M)
+            }
+        }
+    }
+
+    impl<const M: u64> Div for ModInt<M> {
+        type Output = Self;
+        #[allow(clippy::suspicious_arithmetic_impl)]
+        fn div(self, other: Self) -> Self {
+            self * other.inv().expect("Divisor is not invertible modulo M")
+        }
+    }
+
+    impl<const M: u64> fmt::Display for ModInt<M> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.value)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use rand::SeedableRng;
+        use crate::drbg::chacha_drbg::ChaChaDrbg;
+        use crate::randomized::randomized_algos::random_big_below;
+
+        fn to_biguint(limbs: &[u64]) -> BigUint {
+            BigUint::new(limbs.iter().flat_map(|&limb| {[limb as u32, (limb >> 32) as u32]}).collect())
+        }
+
+        #[test]
+        fn operation_counts_do_not_depend_on_the_exponent() {
+            let mut rng = ChaChaDrbg::seed_from_u64(440);
+            let n = random_big_below(&(BigUint::one() << 1024), &mut rng) | BigUint::one() | (BigUint::one() << 1023);
+            let base = random_big_below(&n, &mut rng);
+            let fixed = MontgomeryFixed::new(&n);
+            let exponents = [
+                BigUint::one(),
+                BigUint::one() << 135,
+                (BigUint::one() << 136) - 1u32,
+                random_big_below(&(BigUint::one() << 136), &mut rng)
+            ];
+
+            let mut window_counts = Vec::new();
+            let mut ladder_counts = Vec::new();
+            for exp in exponents.iter() {
+                fixed.reset_counts();
+                assert_eq!(fixed.pow_fixed_window(&base, exp, 136), base.modpow(exp, &n));
+                window_counts.push(fixed.operation_counts());
+
+                fixed.reset_counts();
+                assert_eq!(fixed.pow_ladder(&base, exp, 136), base.modpow(exp, &n));
+                ladder_counts.push(fixed.operation_counts());
+            }
+            assert!(window_counts.iter().all(|&counts| {counts == window_counts[0]}), "{:?}", window_counts);
+            assert!(ladder_counts.iter().all(|&counts| {counts == ladder_counts[0]}), "{:?}", ladder_counts);
+            assert_eq!(ladder_counts[0], OperationCounts{multiplications: 136, squarings: 136, table_lookups: 0});
+        }
+
+        #[test]
+        fn pow_mod_ct_matches_modpow() {
+            let mut rng = ChaChaDrbg::seed_from_u64(4400);
+            for bits in [3u64, 64, 65, 200, 512].iter() {
+                let m = random_big_below(&(BigUint::one() << bits), &mut rng) | BigUint::one() | (BigUint::one() << (bits - 1));
+                for _i in 0..10 {
+                    let base = random_big_below(&(&m * 3u32), &mut rng);
+                    let exp = random_big_below(&(BigUint::one() << (bits + 10)), &mut rng);
+                    assert_eq!(pow_mod_ct(&base, &exp, &m), base.modpow(&exp, &m));
+                }
+            }
+        }
+
+        #[test]
+        fn montgomery_multiply_limbs_matches_biguint() {
+            let mut rng = ChaChaDrbg::seed_from_u64(44);
+            for k in 1..=6 {
+                for _i in 0..50 {
+                    let n = random_big_below(&(BigUint::one() << (64 * k)), &mut rng) | BigUint::one();
+                    let a = random_big_below(&n, &mut rng);
+                    let b = random_big_below(&n, &mut rng);
+                    let fixed = MontgomeryFixed::new(&n);
+                    let k = fixed.n.len();
+                    let mut t = vec![0u64; k + 2];
+                    let mut out = vec![0u64; k];
+                    montgomery_multiply_limbs(&fixed.to_limbs(&a), &fixed.to_limbs(&b), &fixed.n, fixed.n_prime, &mut t, &mut out);
+                    //out * R = a * b (mod n)
+                    let r = BigUint::one() << (64 * k);
+                    assert!(to_biguint(&out) < n);
+                    assert_eq!(to_biguint(&out) * r % &n, &a * &b % &n);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,434 @@
+pub mod prime_sieves {
+    extern crate bit_vec;
+    use bit_vec::BitVec;
+    use std::collections::HashMap;
+
+    //bits sieved at a time by the segmented sieve, small enough to stay in cache
+    const SEGMENT_SIZE: u64 = 1 << 18;
+
+    /**
+     * The sieve of Eratosthenes. Every prime p <= sqrt(limit) crosses off its multiples
+     * from p^2 on; the numbers left are prime.
+     * Bit i of the result is set when i is prime, for 0 <= i <= limit.
+     *
+     * Runtime: O(limit log log limit), memory: limit bits
+     */
+    pub fn eratosthenes(limit: usize) -> BitVec {
+        let mut is_prime = BitVec::from_elem(limit + 1, true);
+        is_prime.set(0, false);
+        if limit >= 1 {
+            is_prime.set(1, false);
+        }
+
+        let mut p = 2;
+        while p * p <= limit {
+            if is_prime[p] {
+                for multiple in (p * p..=limit).step_by(p) {
+                    is_prime.set(multiple, false);
+                }
+            }
+            p += 1;
+        }
+        is_prime
+    }
+
+    //primes found in a bit vector from eratosthenes or atkin
+    fn collect_primes(is_prime: &BitVec) -> Vec<u64> {
+        is_prime.iter().enumerate().filter(|(_, prime)| {*prime}).map(|(i, _)| {i as u64}).collect()
+    }
+
+    /**
+     * All primes <= limit, in increasing order
+     */
+    pub fn primes_up_to(limit: u64) -> Vec<u64> {
+        segmented_eratosthenes(0, limit + 1)
+    }
+
+    //bit i is set when low + i is prime, given every prime up to sqrt(high) in base_primes
+    fn sieve_segment(low: u64, high: u64, base_primes: &[u64]) -> BitVec {
+        let mut is_prime = BitVec::from_elem((high - low) as usize, true);
+        for &p in base_primes.iter() {
+            if p * p >= high {
+                break;
+            }
+            //first multiple of p in the segment, skipping p itself
+            let first = (p * p).max(low.div_ceil(p) * p);
+            for multiple in (first..high).step_by(p as usize) {
+                is_prime.set((multiple - low) as usize, false);
+            }
+        }
+        for n in low..high.min(2) {
+            is_prime.set((n - low) as usize, false);
+        }
+        is_prime
+    }
+
+    //smallest integer whose square is at least n
+    fn ceil_sqrt(n: u64) -> u64 {
+        let mut root = (n as f64).sqrt() as u64;
+        while root * root > n {
+            root -= 1;
+        }
+        while root * root < n {
+            root += 1;
+        }
+        root
+    }
+
+    /**
+     * The segmented sieve of Eratosthenes, giving the primes in low..high.
+     * The primes up to sqrt(high) are found first, then the range is sieved one
+     * cache-sized window at a time, so the memory used is O(sqrt(high) + window)
+     * whatever the size of the range.
+     */
+    pub fn segmented_eratosthenes(low: u64, high: u64) -> Vec<u64> {
+        let mut primes: Vec<u64> = Vec::new();
+        if low >= high {
+            return primes;
+        }
+        let base_primes = collect_primes(&eratosthenes(ceil_sqrt(high) as usize));
+
+        let mut start = low;
+        while start < high {
+            let end = high.min(start + SEGMENT_SIZE);
+            let segment = sieve_segment(start, end, &base_primes);
+            primes.extend(segment.iter().enumerate().filter(|(_, prime)| {*prime}).map(|(i, _)| {start + i as u64}));
+            start = end;
+        }
+        primes
+    }
+
+    /**
+     * The result of the linear sieve: the primes up to the limit and the smallest prime
+     * factor of every number up to it (0 for 0 and 1)
+     */
+    pub struct LinearSieve {
+        pub primes: Vec<u32>,
+        pub smallest_factor: Vec<u32>
+    }
+
+    /**
+     * The linear sieve (Euler's sieve) crosses off every composite exactly once, as
+     * i * p where p is its smallest prime factor: for each i it visits the primes p up to
+     * the smallest prime factor of i. This also records the smallest prime factors.
+     *
+     * Runtime: O(limit)
+     */
+    pub fn linear_sieve(limit: u32) -> LinearSieve {
+        let limit = limit as usize;
+        let mut smallest_factor: Vec<u32> = vec![0; limit + 1];
+        let mut primes: Vec<u32> = Vec::new();
+
+        for i in 2..=limit {
+            if smallest_factor[i] == 0 {
+                smallest_factor[i] = i as u32;
+                primes.push(i as u32);
+            }
+            for &p in primes.iter() {
+                let composite = i * p as usize;
+                if p > smallest_factor[i] || composite > limit {
+                    break;
+                }
+                smallest_factor[composite] = p;
+            }
+        }
+        LinearSieve{primes, smallest_factor}
+    }
+
+    impl LinearSieve {
+        /**
+         * The prime factorization of n as (prime, exponent) pairs in increasing order.
+         * n must be between 1 and the limit of the sieve.
+         */
+        pub fn factorize(&self, n: u32) -> Vec<(u32, u32)> {
+            if n == 0 || n as usize >= self.smallest_factor.len() {
+                panic!("{} is outside the range of the sieve.", n);
+            }
+            let mut factors: Vec<(u32, u32)> = Vec::new();
+            let mut rest = n;
+            while rest > 1 {
+                let p = self.smallest_factor[rest as usize];
+                let mut exponent = 0;
+                while rest.is_multiple_of(p) {
+                    rest /= p;
+                    exponent += 1;
+                }
+                factors.push((p, exponent));
+            }
+            factors
+        }
+
+        pub fn is_prime(&self, n: u32) -> bool {
+            n >= 2 && self.smallest_factor[n as usize] == n
+        }
+    }
+
+    /**
+     * The sieve of Atkin. Instead of crossing off multiples, it flips n for every solution of
+     * 4x^2 + y^2 = n with n = 1 (mod 4), 3x^2 + y^2 = n with n = 7 (mod 12), and
+     * 3x^2 - y^2 = n with x > y and n = 11 (mod 12). A squarefree n > 3 is prime exactly
+     * when it ends up flipped an odd number of times, so the multiples of prime squares
+     * are crossed off afterwards.
+     * Bit i of the result is set when i is prime, for 0 <= i <= limit.
+     *
+     * Runtime: O(limit)
+     */
+    pub fn atkin(limit: usize) -> BitVec {
+        let mut is_prime = BitVec::from_elem(limit + 1, false);
+        let flip = |is_prime: &mut BitVec, n: usize| {
+            let flipped = !is_prime[n];
+            is_prime.set(n, flipped);
+        };
+
+        let mut x = 1;
+        while x * x <= limit {
+            let mut y = 1;
+            while y * y <= limit {
+                let n = 4 * x * x + y * y;
+                if n <= limit && (n % 12 == 1 || n % 12 == 5) {
+                    flip(&mut is_prime, n);
+                }
+                let n = 3 * x * x + y * y;
+                if n <= limit && n % 12 == 7 {
+                    flip(&mut is_prime, n);
+                }
+                if x > y {
+                    let n = 3 * x * x - y * y;
+                    if n <= limit && n % 12 == 11 {
+                        flip(&mut is_prime, n);
+                    }
+                }
+                y += 1;
+            }
+            x += 1;
+        }
+
+        let mut r = 5;
+        while r * r <= limit {
+            if is_prime[r] {
+                for multiple in (r * r..=limit).step_by(r * r) {
+                    is_prime.set(multiple, false);
+                }
+            }
+            r += 1;
+        }
+        for p in [2, 3] {
+            if p <= limit {
+                is_prime.set(p, true);
+            }
+        }
+        is_prime
+    }
+
+    /**
+     * Unbounded iterator over the primes 2, 3, 5, 7, ... Built by primes.
+     */
+    pub struct Primes {
+        base_primes: Vec<u64>,          //every prime up to base_limit
+        base_limit: u64,
+        segment: Vec<u64>,              //primes of the current window, in reverse order
+        next_start: u64
+    }
+
+    /**
+     * An endless iterator over the primes in increasing order. It runs the segmented sieve
+     * one window at a time, extending the sieving primes as the windows move up.
+     */
+    pub fn primes() -> Primes {
+        Primes{base_primes: Vec::new(), base_limit: 0, segment: Vec::new(), next_start: 0}
+    }
+
+    impl Iterator for Primes {
+        type Item = u64;
+
+        fn next(&mut self) -> Option<u64> {
+            while self.segment.is_empty() {
+                let start = self.next_start;
+                let end = start + SEGMENT_SIZE;
+                if self.base_limit * self.base_limit < end {
+                    //double the sieving range so this happens rarely
+                    self.base_limit = 2 * ceil_sqrt(end);
+                    self.base_primes = collect_primes(&eratosthenes(self.base_limit as usize));
+                }
+                let window = sieve_segment(start, end, &self.base_primes);
+                self.segment = window.iter().enumerate().filter(|(_, prime)| {*prime}).map(|(i, _)| {start + i as u64}).rev().collect();
+                self.next_start = end;
+            }
+            self.segment.pop()
+        }
+    }
+
+    /**
+     * The n-th prime, counting from nth_prime(1) = 2
+     */
+    pub fn nth_prime(n: usize) -> u64 {
+        if n == 0 {
+            panic!("Primes are counted from 1.");
+        }
+        primes().nth(n - 1).unwrap()
+    }
+
+    /**
+     * State for prime_count: a table of the primes up to some limit,
+     * and the counts already found above it
+     */
+    struct PrimeCounter {
+        primes: Vec<u64>,
+        limit: u64,
+        memo: HashMap<u64, u64>,
+        small_phi: Vec<Vec<u64>>        //small_phi[a][i] = φ(i, a) for i below the product of the first a primes
+    }
+
+    //φ(x, a) is periodic in x for small a, so it is tabulated up to this many primes
+    const SMALL_PHI_PRIMES: usize = 6;
+
+    impl PrimeCounter {
+        //π(x)
+        fn pi(&mut self, x: u64) -> u64 {
+            if x <= self.limit {
+                return self.primes.partition_point(|&p| {p <= x}) as u64;
+            }
+            if let Some(&count) = self.memo.get(&x) {
+                return count;
+            }
+
+            let a = self.pi(integer_root(x, 4));
+            let b = self.pi(integer_root(x, 2));
+            let c = self.pi(integer_root(x, 3));
+            let mut count = self.phi(x, a as usize) + (b + a - 2) * (b - a + 1) / 2;
+            for i in (a + 1)..=b {
+                let w = x / self.primes[i as usize - 1];
+                count -= self.pi(w);
+                if i <= c {
+                    let b_i = self.pi(integer_root(w, 2));
+                    for j in i..=b_i {
+                        count -= self.pi(w / self.primes[j as usize - 1]) - (j - 1);
+                    }
+                }
+            }
+
+            self.memo.insert(x, count);
+            count
+        }
+
+        //φ(x, a), the number of integers in 1..=x with no prime factor among the first a primes
+        fn phi(&mut self, x: u64, a: usize) -> u64 {
+            if a == 0 || x == 0 {
+                return x;
+            }
+            //with P the product of the first a primes, φ(x, a) = (x / P) φ(P, a) + φ(x mod P, a)
+            if a <= SMALL_PHI_PRIMES {
+                let table = &self.small_phi[a];
+                let period = table.len() as u64;
+                return (x / period) * table[table.len() - 1] + table[(x % period) as usize];
+            }
+            //if the (a+1)-th prime squared is above x, only 1 and the primes after the a-th are left
+            if x <= self.limit && self.primes[a] * self.primes[a] > x {
+                let pi_x = self.pi(x);
+                return 1 + pi_x.saturating_sub(a as u64);
+            }
+            self.phi(x, a - 1) - self.phi(x / self.primes[a - 1], a - 1)
+        }
+    }
+
+    //floor of the k-th root of x
+    fn integer_root(x: u64, k: u32) -> u64 {
+        let mut root = (x as f64).powf(1.0 / k as f64) as u64;
+        while root > 0 && root.checked_pow(k).is_none_or(|power| {power > x}) {
+            root -= 1;
+        }
+        while (root + 1).checked_pow(k).is_some_and(|power| {power <= x}) {
+            root += 1;
+        }
+        root
+    }
+
+    /**
+     * The prime-counting function π(x), the number of primes <= x, by Lehmer's formula.
+     *
+     * Meissel and Lehmer count the primes without listing them. With a = π(x^(1/4)),
+     * b = π(x^(1/2)) and c = π(x^(1/3)),
+     *
+     *  π(x) = φ(x, a) + (b + a - 2)(b - a + 1)/2 - Σ_{a<i<=b} π(x/p_i)
+     *         - Σ_{a<i<=c} Σ_{i<=j<=π(sqrt(x/p_i))} (π(x/(p_i p_j)) - (j - 1))
+     *
+     * where φ(x, a) counts the numbers up to x with no prime factor among the first a primes,
+     * following φ(x, a) = φ(x, a-1) - φ(x/p_a, a-1), with the values for a <= 6 tabulated.
+     * The smaller π values come from a sieved table of the primes up to about x^(2/3)
+     * (at most 10^7) and the larger ones from the formula again.
+     *
+     * Runtime: about O(x / (log x)^3), memory: O(min(x^(2/3), 10^7))
+     */
+    pub fn prime_count(x: u64) -> u64 {
+        //the table must hold the primes up to sqrt(x), and at least one past it for phi
+        let limit = integer_root(x, 3).pow(2).min(10_000_000).max(integer_root(x, 2) + 1000);
+        let primes = primes_up_to(limit);
+        let mut small_phi: Vec<Vec<u64>> = vec![vec![0]];
+        let mut period = 1;
+        for a in 1..=SMALL_PHI_PRIMES {
+            period *= primes[a - 1];
+            let mut table: Vec<u64> = vec![0; period as usize];
+            for i in 1..period {
+                let coprime = primes[..a].iter().all(|&p| {!i.is_multiple_of(p)});
+                table[i as usize] = table[i as usize - 1] + coprime as u64;
+            }
+            small_phi.push(table);
+        }
+
+        let mut counter = PrimeCounter{primes, limit, memo: HashMap::new(), small_phi};
+        counter.pi(x)
+    }
+
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn sieves_agree_with_eratosthenes() {
+            for &limit in [0usize, 1, 2, 3, 10, 97, 1000, 65_536, 300_000].iter() {
+                let expected = eratosthenes(limit);
+                assert_eq!(atkin(limit), expected, "{}", limit);
+                let listed = collect_primes(&expected);
+                let linear = linear_sieve(limit as u32);
+                assert_eq!(linear.primes.iter().map(|&p| {p as u64}).collect::<Vec<u64>>(), listed);
+                assert_eq!(primes_up_to(limit as u64), listed);
+                assert_eq!(primes().take_while(|&p| {p <= limit as u64}).collect::<Vec<u64>>(), listed);
+            }
+
+            //windows that start and end inside a segment, across segment boundaries
+            let expected = collect_primes(&eratosthenes(1_200_000));
+            for &(low, high) in [(0, 2), (2, 3), (1000, 1000), (262_000, 263_000), (100_000, 1_200_000), (524_287, 524_288)].iter() {
+                let primes: Vec<u64> = expected.iter().copied().filter(|&p| {low <= p && p < high}).collect();
+                assert_eq!(segmented_eratosthenes(low, high), primes, "{}..{}", low, high);
+            }
+        }
+
+        #[test]
+        fn linear_sieve_factors() {
+            let sieve = linear_sieve(10_000);
+            assert_eq!(sieve.factorize(9240), vec![(2, 3), (3, 1), (5, 1), (7, 1), (11, 1)]);
+            assert_eq!(sieve.factorize(9973), vec![(9973, 1)]);
+            assert!(sieve.is_prime(9973) && !sieve.is_prime(9971) && !sieve.is_prime(1));
+            assert_eq!(&sieve.smallest_factor[..8], &[0, 0, 2, 3, 2, 5, 2, 7]);
+        }
+
+        #[test]
+        fn prime_counts() {
+            let sieved = primes_up_to(100_000);
+            for x in (0..100_000u64).step_by(997).chain([1, 2, 3, 4, 99_991].iter().copied()) {
+                assert_eq!(prime_count(x), sieved.iter().filter(|&&p| {p <= x}).count() as u64, "{}", x);
+            }
+            assert_eq!(prime_count(1_000_000), 78_498);
+            assert_eq!(prime_count(1_000_000_000), 50_847_534);
+        }
+
+        #[test]
+        fn nth_primes() {
+            assert_eq!(nth_prime(1), 2);
+            assert_eq!(nth_prime(26), 101);
+            assert_eq!(nth_prime(10_000), 104_729);
+            assert_eq!(nth_prime(78_498), 999_983);
+        }
+    }
+}
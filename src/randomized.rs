@@ -1,26 +1,33 @@
 pub mod randomized_algos {
     extern crate rand;
-    use rand::{Rng, thread_rng};
+    extern crate num;
+    use rand::{CryptoRng, Rng, RngCore};
+    use num::{BigInt, BigUint, Integer, One, Signed, ToPrimitive, Zero};
+    use crate::modular::modular_arith::*;
 
     pub struct ExtEuclidRes {
         pub alpha: u128,
-        beta: u128,
+        pub beta: u128,
         pub gcd: u128
     }
 
     /**
      * This algorithm is used to efficiently solve for modular exponentiation. 
      * This is the general equation for modular exponentiation: x^y mod N
+     * 
+     * The arguments are f64 for compatibility. They are converted to integers and the
+     * exponentiation is done exactly by modular_arith::pow_mod_u128, so the result is correct
+     * as long as the arguments are integers below 2^53 (the largest exactly representable in f64).
+     * New code should use the integer functions in modular_arith directly.
+     *
+     * A modulo of 0 doesn't panic: like the f64 remainder x % 0, the result is NaN, except
+     * for a zero exponent, which gives 1.
      */
     pub fn mod_exponent(base: f64, exp: f64, modulo: f64) -> f64 {
-        if exp == 0.0 {return 1.0;}                 //base case
-        let y = (exp / 2.0).floor();
-        let z = mod_exponent(base, y, modulo);      //recurse     
-        if exp % 2.0 == 0.0 {                       //if exponent is even
-            z.powf(2.0) % modulo
-        } else {
-            (base * z).powf(2.0) % modulo
+        if modulo as u128 == 0 {
+            return if exp as u128 == 0 {1.0} else {f64::NAN};
         }
+        pow_mod_u128(base as u128, exp as u128, modulo as u128) as f64
     }
 
     /**
@@ -31,46 +38,300 @@ pub mod randomized_algos {
      * If the GCD(x, y) = 1, it means that x and y are relatively prime to one another
      */
     pub fn euclid_gcd(x: u128, y: u128) -> u128{
-        if y == 0 {
-            x                   //base case
-        } else {
-            euclid_gcd(y, x % y)
+        let (mut a, mut b) = (x, y);
+        while b != 0 {
+            let r = a % b;      //GCD(a, b) = GCD(b, a % b)
+            a = b;
+            b = r;
         }
+        a
     }
 
     /**
      * Euclid's extended algorithm (EEA) is used to calculate GCD and multiplicative inverses.
      * If GCD(x, y) = 1 where (x % y), then EEA can give us the inverse (x^-1 % y).
      * In the result, alpha % y = x^-1 % y. 
+     * 
+     * The Bezout coefficients found by EEA can be negative, which u128 can't hold, so they
+     * are returned reduced: alpha modulo y and beta modulo x. Then alpha * x = gcd (mod y)
+     * and beta * y = gcd (mod x). Use ext_gcd for the signed coefficients themselves.
      */
     pub fn euclid_gcd_ext(x: u128, y: u128) -> ExtEuclidRes{
-        if y == 0 {
-            ExtEuclidRes{alpha: 1, beta: 0, gcd: x}                         //base case
+        let res = ext_gcd(BigInt::from(x), BigInt::from(y));
+        let reduce = |c: BigInt, m: u128| -> u128 {
+            if m == 0 {
+                c.to_u128().unwrap()
+            } else {
+                c.mod_floor(&BigInt::from(m)).to_u128().unwrap()
+            }
+        };
+        ExtEuclidRes{alpha: reduce(res.x, y), beta: reduce(res.y, x), gcd: res.gcd.to_u128().unwrap()}
+    }
+
+    /**
+     * Signed result of the extended Euclidean algorithm: a * x + b * y = gcd
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Bezout<T> {
+        pub gcd: T,
+        pub x: T,
+        pub y: T
+    }
+
+    /**
+     * The extended Euclidean algorithm for signed integers (i64, i128 or BigInt).
+     * Finds gcd(a, b) >= 0 with Bezout coefficients x and y such that a * x + b * y = gcd.
+     *
+     * Iterative version: the pairs (r, s, t) with a * s + b * t = r follow the remainders of
+     * Euclid's algorithm, starting from (a, 1, 0) and (b, 0, 1).
+     * The coefficients satisfy |x| <= |b| / gcd and |y| <= |a| / gcd.
+     *
+     * Runtime: O(log min(a, b)) steps
+     */
+    pub fn ext_gcd<T: Integer + Signed + Clone>(a: T, b: T) -> Bezout<T> {
+        let (mut old_r, mut r) = (a, b);
+        let (mut old_s, mut s) = (T::one(), T::zero());
+        let (mut old_t, mut t) = (T::zero(), T::one());
+
+        while !r.is_zero() {
+            let q = old_r.clone() / r.clone();
+            let next_r = old_r - q.clone() * r.clone();
+            old_r = std::mem::replace(&mut r, next_r);
+            let next_s = old_s - q.clone() * s.clone();
+            old_s = std::mem::replace(&mut s, next_s);
+            let next_t = old_t - q * t.clone();
+            old_t = std::mem::replace(&mut t, next_t);
+        }
+
+        //make the gcd non-negative
+        if old_r.is_negative() {
+            Bezout{gcd: -old_r, x: -old_s, y: -old_t}
         } else {
-            let res = euclid_gcd_ext(y, x % y);                             //recurse
-            let beta_prime = res.alpha - ((x/y) * res.beta);                //calc beta'
-            ExtEuclidRes{alpha: res.alpha, beta: beta_prime, gcd: res.gcd}  //result
+            Bezout{gcd: old_r, x: old_s, y: old_t}
         }
     }
 
+    /**
+     * Stein's binary GCD algorithm. It uses only shifts and subtraction, no division:
+     * gcd(2a, 2b) = 2 gcd(a, b), gcd(2a, b) = gcd(a, b) for odd b,
+     * and gcd(a, b) = gcd(a - b, b) for odd a >= b.
+     */
+    pub fn binary_gcd(x: u128, y: u128) -> u128 {
+        if x == 0 || y == 0 {
+            return x | y;
+        }
+        let shift = (x | y).trailing_zeros();      //common factors of 2
+        let mut a = x >> x.trailing_zeros();
+        let mut b = y;
+
+        while b != 0 {
+            b >>= b.trailing_zeros();
+            if a > b {
+                std::mem::swap(&mut a, &mut b);
+            }
+            b -= a;
+        }
+        a << shift
+    }
+
+    /**
+     * Least common multiple, lcm(x, y) = x / gcd(x, y) * y.
+     * Returns None if the result doesn't fit in a u128.
+     */
+    pub fn lcm(x: u128, y: u128) -> Option<u128> {
+        if x == 0 || y == 0 {
+            return Some(0);
+        }
+        (x / binary_gcd(x, y)).checked_mul(y)
+    }
+
+    /**
+     * The Chinese Remainder Theorem (CRT) solves the system x = residues[i] (mod moduli[i]).
+     *
+     * The congruences are merged two at a time. x = a1 (mod m1) and x = a2 (mod m2) have a
+     * common solution only if g = gcd(m1, m2) divides a2 - a1. In that case, with p the
+     * inverse of m1/g modulo m2/g (found by ext_gcd), the merged congruence is
+     *
+     *  x = a1 + m1 * ((a2 - a1)/g * p mod m2/g)   (mod lcm(m1, m2))
+     *
+     * so the moduli need not be pairwise coprime.
+     * Returns (x, lcm of the moduli) with 0 <= x < lcm, or None if the system has no solution.
+     * Moduli must be positive.
+     */
+    pub fn crt<T: Integer + Signed + Clone>(residues: &[T], moduli: &[T]) -> Option<(T, T)> {
+        if residues.len() != moduli.len() {
+            panic!("Each residue needs a modulus.");
+        }
+        let mut x = T::zero();
+        let mut m = T::one();
+
+        for (a, n) in residues.iter().zip(moduli.iter()) {
+            if !n.is_positive() {
+                panic!("Moduli must be positive.");
+            }
+            let bezout = ext_gcd(m.clone(), n.clone());
+            let g = bezout.gcd;
+            let diff = a.clone() - x.clone();
+            if !diff.is_multiple_of(&g) {
+                return None;
+            }
+
+            let n_over_g = n.clone() / g.clone();
+            let k = ((diff / g) * bezout.x).mod_floor(&n_over_g);
+            x = x + m.clone() * k;
+            m = m * n_over_g;
+            x = x.mod_floor(&m);
+        }
+
+        Some((x, m))
+    }
+
+    /**
+     * The Jacobi symbol (a/n) for odd n > 0, which is 0, 1 or -1.
+     * For a prime n it is the Legendre symbol. It is computed like a GCD using
+     * quadratic reciprocity, (a/n)(n/a) = (-1)^((a-1)/2 * (n-1)/2) for odd coprime a and n,
+     * and the rule for factors of two, (2/n) = -1 exactly when n = 3 or 5 (mod 8).
+     *
+     * Works for i64, i128 or BigInt.
+     */
+    pub fn jacobi<T: Integer + Signed + Clone>(a: &T, n: &T) -> i32 {
+        if !n.is_positive() || n.is_even() {
+            panic!("The Jacobi symbol needs an odd positive n.");
+        }
+        let two = T::one() + T::one();
+        let three = two.clone() + T::one();
+        let four = two.clone() * two.clone();
+        let five = four.clone() + T::one();
+        let eight = four.clone() + four.clone();
+        let mut a = a.mod_floor(n);
+        let mut n = n.clone();
+        let mut result = 1;
+
+        while !a.is_zero() {
+            while a.is_even() {
+                a = a / two.clone();
+                let r = n.mod_floor(&eight);
+                if r == three || r == five {
+                    result = -result;
+                }
+            }
+            std::mem::swap(&mut a, &mut n);
+            //both are odd now, so flip the sign if both are 3 mod 4
+            if a.mod_floor(&four) == three && n.mod_floor(&four) == three {
+                result = -result;
+            }
+            a = a.mod_floor(&n);
+        }
+
+        if n.is_one() {
+            result
+        } else {
+            0
+        }
+    }
+
+    /**
+     * The Legendre symbol (a/p) for an odd prime p:
+     * 0 if p divides a, 1 if a is a square modulo p, -1 otherwise.
+     * The primality of p is not checked.
+     */
+    pub fn legendre<T: Integer + Signed + Clone>(a: &T, p: &T) -> i32 {
+        jacobi(a, p)
+    }
+
+    /**
+     * The Tonelli-Shanks algorithm finds a square root of a modulo a prime p:
+     * an r with r * r = a (mod p), or None if a is not a square.
+     *
+     * Write p - 1 = q * 2^s with q odd and pick any non-square z. Starting from
+     * r = a^((q+1)/2), t = a^q and c = z^q, the invariant r^2 = a * t holds. While t != 1,
+     * find the least i with t^(2^i) = 1 and use b = c^(2^(s-i-1)) to set
+     * r = r * b, t = t * b^2, c = b^2, s = i, which shrinks the order of t each round.
+     *
+     * The other root is p - r. The primality of p is not checked, but both searches are
+     * bounded and the root is checked, so a composite p gives None rather than looping.
+     * Panics if p is 0 or even and not 2.
+     */
+    pub fn sqrt_mod(a: u128, p: u128) -> Option<u128> {
+        if p.is_even() && p != 2 {
+            panic!("sqrt_mod needs an odd prime p or 2.");
+        }
+        let a = a % p;
+        if a == 0 || p == 2 {
+            return Some(a);
+        }
+        if jacobi(&BigInt::from(a), &BigInt::from(p)) != 1 {
+            return None;
+        }
+        //p = 3 (mod 4) has a direct formula
+        if p % 4 == 3 {
+            let r = pow_mod_u128(a, (p + 1) / 4, p);
+            return if mul_mod_u128(r, r, p) == a {Some(r)} else {None};
+        }
+
+        let s = (p - 1).trailing_zeros();
+        let q = (p - 1) >> s;
+        let mut z = 2;
+        while jacobi(&BigInt::from(z), &BigInt::from(p)) != -1 {
+            z += 1;
+            if z >= p {
+                return None;
+            }
+        }
+
+        let mut m = s;
+        let mut c = pow_mod_u128(z, q, p);
+        let mut t = pow_mod_u128(a, q, p);
+        let mut r = pow_mod_u128(a, q / 2 + 1, p);         //a^((q+1)/2), q is odd
+
+        while t != 1 {
+            //least i with t^(2^i) = 1
+            let mut i = 0;
+            let mut t_power = t;
+            while t_power != 1 {
+                t_power = mul_mod_u128(t_power, t_power, p);
+                i += 1;
+                if i >= m {
+                    return None;       //t has no order 2^i < 2^m, so p isn't prime
+                }
+            }
+
+            let mut b = c;
+            for _j in 0..(m - i - 1) {
+                b = mul_mod_u128(b, b, p);
+            }
+            r = mul_mod_u128(r, b, p);
+            c = mul_mod_u128(b, b, p);
+            t = mul_mod_u128(t, c, p);
+            m = i;
+        }
+        if mul_mod_u128(r, r, p) == a {Some(r)} else {None}
+    }
+
     /**
      * The simple primality algorithm is used to test for whether a value x is prime.
-     * x is prime if it is relatively prime with all numbers from 1 - (x-1),
-     * so this algorithm tests for relative primality of x with a random value r.
+     * By Fermat's little theorem, r^(x-1) = 1 (mod x) for every r in 1..x when x is prime,
+     * so this algorithm checks that equation for random values r.
      * 
-     * If the two values aren't relatively prime, then r acts as a Fermat witness to
-     * the fact that x is not prime. If they are relatively prime, x might be prime,
-     * but we can't be certain.
+     * If the equation fails, then r acts as a Fermat witness to the fact that x is
+     * not prime. If it holds, x might be prime, but we can't be certain.
+     * Carmichael numbers satisfy it for every r coprime to them, so they fool this test
+     * about as often as the chance of picking r that shares a factor with them;
+     * miller_rabin has no such blind spot.
      * 
      * For this implementation, true is returned if x might be prime.
+     * The values r are drawn from rng.
      */
-    pub fn simple_primality(x: &u128, rounds: usize) -> bool{
+    pub fn simple_primality<R: RngCore + CryptoRng + ?Sized>(x: &u128, rounds: usize, rng: &mut R) -> bool{
+        let x = *x;
+        if x < 4 {
+            return x >= 2;
+        }
         let mut res: bool = true;
         
         for _i in 0..rounds {
-            let r = thread_rng().gen_range(1..*x);
-            let z = mod_exponent(*x as f64, (r-1) as f64, r as f64);
-            if z as u128 != (1 % r) as u128 {
+            let r = rng.gen_range(2..x - 1);
+            if pow_mod_u128(r, x - 1, x) != 1 {
                 res = false;
                 break;
             }
@@ -79,5 +340,553 @@ pub mod randomized_algos {
         res
     }
 
-    pub fn carmichael_nums() {}
+    /**
+     * Evidence that a number is composite
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum Witness<T> {
+        Divisor(T),             //a nontrivial factor
+        Base(T),                //a base for which the strong probable prime (Miller-Rabin) test fails
+        Lucas(i64)              //the parameter D for which the strong Lucas test fails
+    }
+
+    /**
+     * The result of a primality test. Prime is only returned when the test is proven
+     * correct for the input; probabilistic tests that pass return ProbablePrime.
+     * 0 and 1 are Neither, being neither prime nor composite.
+     */
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum Primality<T> {
+        Prime,
+        Composite(Witness<T>),
+        ProbablePrime,
+        Neither
+    }
+
+    impl<T> Primality<T> {
+        /**
+         * True for Prime and ProbablePrime
+         */
+        pub fn is_probably_prime(&self) -> bool {
+            matches!(self, Primality::Prime | Primality::ProbablePrime)
+        }
+    }
+
+    const SMALL_PRIMES: [u64; 25] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97];
+
+    /**
+     * Checks the small cases shared by all the tests: 0 and 1, the primes below 100
+     * and numbers with a prime factor below 100. None means n > 100 has no small factor.
+     */
+    fn small_primality(n: u128) -> Option<Primality<u128>> {
+        if n < 2 {
+            return Some(Primality::Neither);
+        }
+        for &p in SMALL_PRIMES.iter() {
+            let p = p as u128;
+            if n == p {
+                return Some(Primality::Prime);
+            }
+            if n.is_multiple_of(p) {
+                return Some(Primality::Composite(Witness::Divisor(p)));
+            }
+        }
+        if n < 100 * 100 {
+            return Some(Primality::Prime);
+        }
+        None
+    }
+
+    /**
+     * The strong probable prime test to base a, for odd n > 2 with n - 1 = d * 2^s, d odd.
+     * A prime n has either a^d = 1 or a^(d * 2^r) = -1 (mod n) for some 0 <= r < s,
+     * because the only square roots of 1 modulo a prime are 1 and -1.
+     * For an odd composite n, at most a quarter of the bases pass, and never one sharing
+     * a factor with n, as 1 and -1 are units.
+     */
+    pub fn strong_probable_prime(n: u128, a: u128) -> bool {
+        let s = (n - 1).trailing_zeros();
+        let d = (n - 1) >> s;
+        let mut x = pow_mod_u128(a % n, d, n);
+        if x == 1 || x == n - 1 {
+            return true;
+        }
+        for _r in 1..s {
+            x = mul_mod_u128(x, x, n);
+            if x == n - 1 {
+                return true;
+            }
+        }
+        false
+    }
+
+    /**
+     * The Miller-Rabin test with random bases: runs the strong probable prime test for
+     * the given number of rounds. A composite n survives with probability at most 4^-rounds.
+     *
+     * Returns Composite with the failing base (or the common factor, if a base happens to
+     * share one with n), or ProbablePrime if every round passed.
+     * Numbers with a prime factor below 100 are decided by trial division.
+     * The bases are drawn from rng, so a seeded generator gives reproducible witnesses.
+     */
+    pub fn miller_rabin<R: RngCore + CryptoRng + ?Sized>(n: u128, rounds: usize, rng: &mut R) -> Primality<u128> {
+        if let Some(res) = small_primality(n) {
+            return res;
+        }
+        for _i in 0..rounds {
+            let a = rng.gen_range(2..n - 1);
+            let g = a.gcd(&n);
+            if g != 1 {
+                return Primality::Composite(Witness::Divisor(g));
+            }
+            if !strong_probable_prime(n, a) {
+                return Primality::Composite(Witness::Base(a));
+            }
+        }
+        Primality::ProbablePrime
+    }
+
+    /**
+     * Deterministic Miller-Rabin for u64. The first 12 primes as bases are enough to tell
+     * every n < 3.18 * 10^23 apart (Sorenson and Webster, 2015), which covers all of u64,
+     * so the result is always Prime, Composite or Neither.
+     */
+    pub fn is_prime_u64(n: u64) -> Primality<u64> {
+        let convert = |res: Primality<u128>| -> Primality<u64> {
+            match res {
+                Primality::Composite(Witness::Divisor(p)) => Primality::Composite(Witness::Divisor(p as u64)),
+                Primality::Composite(Witness::Base(a)) => Primality::Composite(Witness::Base(a as u64)),
+                Primality::Composite(Witness::Lucas(d)) => Primality::Composite(Witness::Lucas(d)),
+                Primality::Prime => Primality::Prime,
+                Primality::ProbablePrime => Primality::Prime,
+                Primality::Neither => Primality::Neither
+            }
+        };
+        if let Some(res) = small_primality(n as u128) {
+            return convert(res);
+        }
+        for &a in SMALL_PRIMES.iter().take(12) {
+            if !strong_probable_prime(n as u128, a as u128) {
+                return Primality::Composite(Witness::Base(a));
+            }
+        }
+        Primality::Prime
+    }
+
+    /**
+     * The strong probable prime test to base a for a BigUint, see strong_probable_prime
+     */
+    pub fn strong_probable_prime_big(n: &BigUint, a: &BigUint) -> bool {
+        let one = BigUint::one();
+        let n_minus_one = n - &one;
+        let s = n_minus_one.trailing_zeros().unwrap();
+        let d = &n_minus_one >> s;
+        let mut x = pow_mod_big(&(a % n), &d, n);
+        if x == one || x == n_minus_one {
+            return true;
+        }
+        for _r in 1..s {
+            x = &x * &x % n;
+            if x == n_minus_one {
+                return true;
+            }
+        }
+        false
+    }
+
+    /**
+     * A uniformly random number in 0..bound, drawn bytewise by rejection sampling
+     */
+    pub(crate) fn random_big_below<R: Rng + ?Sized>(bound: &BigUint, rng: &mut R) -> BigUint {
+        let bits = bound.bits();
+        let mut bytes = vec![0u8; bits.div_ceil(8) as usize];
+        loop {
+            rng.fill_bytes(&mut bytes);
+            let x = BigUint::from_bytes_be(&bytes) >> (bytes.len() as u64 * 8 - bits);
+            if &x < bound {
+                return x;
+            }
+        }
+    }
+
+    //the BigUint tests reduce to the u128 ones for small n
+    fn from_u128_result(res: Primality<u128>) -> Primality<BigUint> {
+        match res {
+            Primality::Composite(Witness::Divisor(p)) => Primality::Composite(Witness::Divisor(BigUint::from(p))),
+            Primality::Composite(Witness::Base(a)) => Primality::Composite(Witness::Base(BigUint::from(a))),
+            Primality::Composite(Witness::Lucas(d)) => Primality::Composite(Witness::Lucas(d)),
+            Primality::Prime => Primality::Prime,
+            Primality::ProbablePrime => Primality::ProbablePrime,
+            Primality::Neither => Primality::Neither
+        }
+    }
+
+    /**
+     * The Miller-Rabin test with random bases for a BigUint, see miller_rabin
+     */
+    pub fn miller_rabin_big<R: RngCore + CryptoRng + ?Sized>(n: &BigUint, rounds: usize, rng: &mut R) -> Primality<BigUint> {
+        if let Some(small) = n.to_u128() {
+            return from_u128_result(miller_rabin(small, rounds, rng));
+        }
+        for &p in SMALL_PRIMES.iter() {
+            if (n % p).is_zero() {
+                return Primality::Composite(Witness::Divisor(BigUint::from(p)));
+            }
+        }
+        let range = n - 3u32;
+        for _i in 0..rounds {
+            let a = random_big_below(&range, rng) + 2u32;     //2..n-1
+            let g = a.gcd(n);
+            if !g.is_one() {
+                return Primality::Composite(Witness::Divisor(g));
+            }
+            if !strong_probable_prime_big(n, &a) {
+                return Primality::Composite(Witness::Base(a));
+            }
+        }
+        Primality::ProbablePrime
+    }
+
+    /**
+     * The strong Lucas probable prime test with Selfridge's parameters, for odd n > 2
+     * that is not a perfect square. D is the first of 5, -7, 9, -11, ... with Jacobi
+     * symbol (D/n) = -1, P = 1 and Q = (1 - D) / 4.
+     *
+     * The Lucas sequences U_k and V_k of (P, Q) satisfy U_(n+1) = 0 (mod n) for prime n.
+     * With n + 1 = d * 2^s, d odd, the strong version checks U_d = 0 or
+     * V_(d * 2^r) = 0 (mod n) for some 0 <= r < s. The terms are found by the doubling
+     * formulas U_2k = U_k V_k and V_2k = V_k^2 - 2 Q^k, and the step
+     * U_(k+1) = (P U_k + V_k) / 2, V_(k+1) = (D U_k + P V_k) / 2.
+     *
+     * Returns Err with a witness if n is composite.
+     */
+    fn strong_lucas(n: &BigUint) -> Result<(), Witness<BigUint>> {
+        let n_int = BigInt::from(n.clone());
+        let mut d: i64 = 5;
+        loop {
+            match jacobi(&BigInt::from(d), &n_int) {
+                -1 => break,
+                0 if BigInt::from(d.abs()) != n_int => {
+                    return Err(Witness::Divisor(BigUint::from(d.unsigned_abs())));
+                },
+                _ => {}
+            }
+            d = if d > 0 {-(d + 2)} else {-d + 2};
+        }
+
+        let reduce = |x: BigInt| -> BigUint {x.mod_floor(&n_int).to_biguint().unwrap()};
+        let half = |x: BigUint| -> BigUint {if x.is_even() {x >> 1} else {(x + n) >> 1}};
+        let d_mod = reduce(BigInt::from(d));
+        let q = reduce(BigInt::from((1 - d) / 4));
+        let two_mod = |x: &BigUint| -> BigUint {(x << 1) % n};
+        let sub_mod = |a: BigUint, b: BigUint| -> BigUint {(a + n - b) % n};
+
+        let n_plus_one = n + 1u32;
+        let s = n_plus_one.trailing_zeros().unwrap();
+        let k = &n_plus_one >> s;
+
+        //U_1 = 1, V_1 = P = 1, Q^1 = Q
+        let mut u = BigUint::one();
+        let mut v = BigUint::one();
+        let mut qk = q.clone();
+        for bit in (0..(k.bits() - 1)).rev() {
+            u = &u * &v % n;
+            v = sub_mod(&v * &v % n, two_mod(&qk));
+            qk = &qk * &qk % n;
+            if k.bit(bit) {
+                let next_u = half((&u + &v) % n);
+                v = half((&d_mod * &u + &v) % n);
+                u = next_u;
+                qk = &qk * &q % n;
+            }
+        }
+
+        if u.is_zero() || v.is_zero() {
+            return Ok(());
+        }
+        for _r in 1..s {
+            v = sub_mod(&v * &v % n, two_mod(&qk));
+            if v.is_zero() {
+                return Ok(());
+            }
+            qk = &qk * &qk % n;
+        }
+        Err(Witness::Lucas(d))
+    }
+
+    /**
+     * The Baillie-PSW test: trial division by the primes below 100, a strong probable prime
+     * test to base 2 and a strong Lucas test. No composite is known to pass both, and none
+     * exists below 2^64, so for n < 2^64 the result is Prime. Larger numbers that pass
+     * are ProbablePrime.
+     */
+    pub fn baillie_psw(n: &BigUint) -> Primality<BigUint> {
+        if let Some(small) = n.to_u128() {
+            if let Some(res) = small_primality(small) {
+                return from_u128_result(res);
+            }
+        }
+        for &p in SMALL_PRIMES.iter() {
+            if (n % p).is_zero() {
+                return Primality::Composite(Witness::Divisor(BigUint::from(p)));
+            }
+        }
+
+        let two = BigUint::from(2u32);
+        if !strong_probable_prime_big(n, &two) {
+            return Primality::Composite(Witness::Base(two));
+        }
+        //a square has no D with (D/n) = -1
+        let root = n.sqrt();
+        if &root * &root == *n {
+            return Primality::Composite(Witness::Divisor(root));
+        }
+        if let Err(witness) = strong_lucas(n) {
+            return Primality::Composite(witness);
+        }
+
+        if n.bits() <= 64 {
+            Primality::Prime
+        } else {
+            Primality::ProbablePrime
+        }
+    }
+
+    /**
+     * Iterator over the Carmichael numbers up to a limit, in increasing order.
+     * Built by carmichael_nums.
+     */
+    pub struct CarmichaelNums {
+        smallest_factor: Vec<u32>,          //smallest prime factor of each number up to the limit
+        next: usize
+    }
+
+    /**
+     * Carmichael numbers are the composites n with a^(n-1) = 1 (mod n) for every a coprime
+     * to n, so they pass the Fermat test of simple_primality for nearly every base:
+     * 561, 1105, 1729, 2465, ...
+     *
+     * Korselt's criterion characterizes them: n is a Carmichael number exactly when it is
+     * composite, squarefree, and p - 1 divides n - 1 for every prime p dividing n.
+     * The factorizations come from a sieve of smallest prime factors up to the limit,
+     * built in O(limit log log limit) time and kept in memory while iterating.
+     */
+    pub fn carmichael_nums(limit: u32) -> CarmichaelNums {
+        let limit = limit as usize;
+        let mut smallest_factor: Vec<u32> = vec![0; limit + 1];
+        for i in 2..=limit {
+            if smallest_factor[i] == 0 {
+                //i is prime
+                for multiple in (i..=limit).step_by(i) {
+                    if smallest_factor[multiple] == 0 {
+                        smallest_factor[multiple] = i as u32;
+                    }
+                }
+            }
+        }
+        CarmichaelNums{smallest_factor, next: 3}
+    }
+
+    impl CarmichaelNums {
+        //Korselt's criterion
+        fn is_carmichael(&self, n: usize) -> bool {
+            let mut rest = n;
+            let mut num_factors = 0;
+            while rest > 1 {
+                let p = self.smallest_factor[rest] as usize;
+                rest /= p;
+                if rest.is_multiple_of(p) || !(n - 1).is_multiple_of(p - 1) {
+                    return false;
+                }
+                num_factors += 1;
+            }
+            num_factors > 1
+        }
+    }
+
+    impl Iterator for CarmichaelNums {
+        type Item = u32;
+
+        fn next(&mut self) -> Option<u32> {
+            //Carmichael numbers are odd
+            while self.next < self.smallest_factor.len() {
+                let n = self.next;
+                self.next += 2;
+                if self.is_carmichael(n) {
+                    return Some(n as u32);
+                }
+            }
+            None
+        }
+    }
+
+    /**
+     * Counts the Fermat liars of n: the bases a in 1..n with a^(n-1) = 1 (mod n),
+     * counting the trivial liars 1 and n - 1. For a prime n every base is one, giving n - 1.
+     *
+     * The liars form a subgroup of the units modulo n, and by the Chinese Remainder Theorem
+     * their number is the product of gcd(p - 1, n - 1) over the prime powers p^k dividing n.
+     * For a Carmichael number it is φ(n), so the Fermat test only catches it by drawing
+     * a base that shares a factor with n. For 561 = 3 * 11 * 17 that is 320 of the 560 bases.
+     *
+     * n is factored by trial division in O(sqrt(n)) time.
+     */
+    pub fn fermat_liars(n: u64) -> u64 {
+        if n < 2 {
+            return 0;
+        }
+        let mut count = 1;
+        let mut rest = n;
+        let mut p = 2;
+        while p <= rest / p {
+            if rest.is_multiple_of(p) {
+                count *= euclid_gcd((p - 1) as u128, (n - 1) as u128) as u64;
+                while rest.is_multiple_of(p) {
+                    rest /= p;
+                }
+            }
+            p += 1;
+        }
+        if rest > 1 {
+            count *= euclid_gcd((rest - 1) as u128, (n - 1) as u128) as u64;
+        }
+        count
+    }
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use rand::SeedableRng;
+        use crate::drbg::chacha_drbg::ChaChaDrbg;
+
+        //strong pseudoprimes to base 2, to bases 2 to 7, and to bases 2 to 23
+        const PSEUDOPRIMES: [u64; 3] = [2047, 3215031751, 3825123056546413051];
+
+        #[test]
+        fn bases_sharing_a_factor_are_not_liars() {
+            assert!(!strong_probable_prime(25, 5));
+            assert!(!strong_probable_prime(10201, 101));
+            assert!(!strong_probable_prime_big(&BigUint::from(10201u32), &BigUint::from(101u32)));
+            assert!(strong_probable_prime(2047, 2));
+            assert!(strong_probable_prime_big(&BigUint::from(2047u32), &BigUint::from(2u32)));
+
+            //101^2 has exactly 100 strong liars, the units of order dividing 100 and 10200 in common
+            let liars = (1..10201u128).filter(|&a| {strong_probable_prime(10201, a)}).count();
+            assert_eq!(liars, 100);
+        }
+
+        #[test]
+        fn pseudoprimes_are_composite() {
+            let mut rng = ChaChaDrbg::seed_from_u64(33);
+            let carmichael: Vec<u64> = carmichael_nums(100_000).map(u64::from).collect();
+            for &n in [10201u64].iter().chain(PSEUDOPRIMES.iter()).chain(carmichael.iter()) {
+                assert!(!is_prime_u64(n).is_probably_prime(), "{}", n);
+                assert!(!baillie_psw(&BigUint::from(n)).is_probably_prime(), "{}", n);
+                assert!(!miller_rabin(n as u128, 20, &mut rng).is_probably_prime(), "{}", n);
+                assert!(!miller_rabin_big(&BigUint::from(n), 20, &mut rng).is_probably_prime(), "{}", n);
+            }
+            assert!(strong_probable_prime(3215031751, 7));
+            assert!(strong_probable_prime(3825123056546413051, 23));
+        }
+
+        #[test]
+        fn primes_are_prime() {
+            let mut rng = ChaChaDrbg::seed_from_u64(330);
+            for &p in [10007u64, 104729, 2147483647, 18446744073709551557].iter() {
+                assert_eq!(is_prime_u64(p), Primality::Prime);
+                assert_eq!(baillie_psw(&BigUint::from(p)), Primality::Prime);
+                assert!(miller_rabin(p as u128, 20, &mut rng).is_probably_prime());
+            }
+            //2^127 - 1
+            let mersenne = (BigUint::one() << 127) - 1u32;
+            assert_eq!(baillie_psw(&mersenne), Primality::ProbablePrime);
+            assert_eq!(miller_rabin_big(&mersenne, 20, &mut rng), Primality::ProbablePrime);
+            //the Fermat number 2^128 + 1 = 59649589127497217 * 5704689200685129054721
+            assert!(!baillie_psw(&((BigUint::one() << 128) + 1u32)).is_probably_prime());
+            for n in 0..3000u64 {
+                let trial = n >= 2 && (2..n).take_while(|d| {d * d <= n}).all(|d| {n % d != 0});
+                assert_eq!(is_prime_u64(n).is_probably_prime(), trial, "{}", n);
+                assert_eq!(baillie_psw(&BigUint::from(n)).is_probably_prime(), trial, "{}", n);
+            }
+        }
+
+        #[test]
+        fn sqrt_mod_finds_every_square() {
+            for &p in [2u128, 3, 5, 7, 13, 17, 41, 97, 257, 1009].iter() {
+                for a in 0..p {
+                    match sqrt_mod(a, p) {
+                        Some(r) => assert_eq!(r * r % p, a),
+                        None => assert!((0..p).all(|r| {r * r % p != a}), "{} is a square modulo {}", a, p)
+                    }
+                }
+            }
+            //p - 1 = 2^16 and 7 * 2^20 have long Tonelli-Shanks loops
+            for &p in [65537u128, 7_340_033].iter() {
+                for a in 1..500u128 {
+                    let root = sqrt_mod(a, p);
+                    assert_eq!(root.is_some(), legendre(&BigInt::from(a), &BigInt::from(p)) == 1);
+                    if let Some(r) = root {
+                        assert_eq!(r * r % p, a);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn sqrt_mod_of_composite_returns() {
+            for &n in [9u128, 15, 21, 25, 33, 49, 561, 1105].iter() {
+                for a in 0..n {
+                    if let Some(r) = sqrt_mod(a, n) {
+                        assert_eq!(r * r % n, a);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn first_carmichael_numbers() {
+            let expected = [561, 1105, 1729, 2465, 2821, 6601, 8911, 10585, 15841, 29341, 41041, 46657, 52633, 62745, 63973, 75361];
+            assert_eq!(carmichael_nums(75361).collect::<Vec<u32>>(), expected);
+            assert_eq!(carmichael_nums(560).next(), None);
+        }
+
+        #[test]
+        fn fermat_liars_match_brute_force() {
+            assert_eq!(fermat_liars(561), 320);
+            for n in 2..600u64 {
+                let liars = (1..n).filter(|&a| {pow_mod_u128(a as u128, (n - 1) as u128, n as u128) == 1}).count() as u64;
+                assert_eq!(fermat_liars(n), liars, "{}", n);
+            }
+            //a Carmichael number lies for every unit, φ(1105) = 4 * 12 * 16
+            assert_eq!(fermat_liars(1105), 768);
+        }
+
+        #[test]
+        fn mod_exponent_is_exact() {
+            assert_eq!(mod_exponent(4.0, 13.0, 497.0), 445.0);
+            assert_eq!(mod_exponent(7.0, 0.0, 13.0), 1.0);
+            assert_eq!(mod_exponent(7.0, 5.0, 1.0), 0.0);
+            //2^52 + 1 squared is far past f64 precision
+            let big = 4503599627370497.0;
+            assert_eq!(mod_exponent(big, 2.0, 9007199254740881.0), pow_mod_u128(4503599627370497, 2, 9007199254740881) as f64);
+        }
+
+        #[test]
+        fn mod_exponent_of_zero_modulus() {
+            assert!(mod_exponent(3.0, 5.0, 0.0).is_nan());
+            assert!(mod_exponent(0.0, 1.0, 0.0).is_nan());
+            assert_eq!(mod_exponent(3.0, 0.0, 0.0), 1.0);
+        }
+
+        #[test]
+        #[should_panic]
+        fn sqrt_mod_of_even_modulus_panics() {
+            sqrt_mod(2, 10);
+        }
+
+        #[test]
+        #[should_panic]
+        fn sqrt_mod_of_zero_modulus_panics() {
+            sqrt_mod(2, 0);
+        }
+    }
 }
\ No newline at end of file
@@ -1,66 +1,1003 @@
 extern crate rand;
-extern crate bit_vec;
-use bit_vec::*;
-use rand::*;
-use std::convert::TryInto;
+extern crate num;
+use rand::{CryptoRng, RngCore};
+use num::{BigUint, Integer, One, Zero};
+use crate::modular::modular_arith::*;
+use crate::primes::prime_sieves::primes_up_to;
 use crate::randomized::randomized_algos::*;
+use crate::hashing::hash_algos::*;
+use crate::encoding::der_pem::*;
 
 /**
- * For creation of key roots p and q
+ * The public exponent used by every key generated here, 2^16 + 1
  */
-pub fn generate_rand_prime() -> u128 {
-    let mut num_bits: BitVec = BitVec::new();
-    
-    //populate bitvecs with random bools
-    for _i in 1..128 {
-        let r: bool = random();
-        num_bits.push(r);
+pub const PUBLIC_EXPONENT: u32 = 65537;
+
+/**
+ * An RSA public key (N, e)
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RsaPublicKey {
+    pub n: BigUint,
+    pub e: BigUint
+}
+
+/**
+ * An RSA private key. Besides N and d it keeps the primes and the CRT values
+ * dp = d mod (p-1), dq = d mod (q-1) and q_inv = q^-1 mod p, as PKCS #1 does.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RsaPrivateKey {
+    pub n: BigUint,
+    pub e: BigUint,
+    pub d: BigUint,
+    pub p: BigUint,
+    pub q: BigUint,
+    pub dp: BigUint,
+    pub dq: BigUint,
+    pub q_inv: BigUint
+}
+
+/**
+ * A random prime of exactly the given number of bits, with its two top bits set so that
+ * the product of two of them has exactly twice as many bits.
+ * p - 1 is also made coprime to e, so that e is invertible; e = 1 leaves p - 1 free,
+ * for primes that aren't RSA factors, like the DSA subgroup order.
+ *
+ * Candidates are odd numbers with no factor below 1000; survivors must pass the
+ * Baillie-PSW test and 4 Miller-Rabin rounds with random bases.
+ * All randomness comes from rng, so the same seeded generator gives the same prime.
+ */
+pub fn generate_rand_prime<R: RngCore + CryptoRng + ?Sized>(bits: u64, e: &BigUint, rng: &mut R) -> BigUint {
+    if bits < 16 {
+        panic!("Primes need at least 16 bits here.");
+    }
+    let small_primes = primes_up_to(1000);
+    let top_bits = BigUint::from(3u32) << (bits - 2);
+
+    loop {
+        let candidate = random_big_below(&(BigUint::one() << bits), rng) | &top_bits | BigUint::one();
+        if small_primes.iter().any(|&p| {(&candidate % p).is_zero()}) {
+            continue;
+        }
+        if !(&candidate - 1u32).gcd(e).is_one() {
+            continue;
+        }
+        if baillie_psw(&candidate).is_probably_prime() && miller_rabin_big(&candidate, 4, rng).is_probably_prime() {
+            return candidate;
+        }
+    }
+}
+
+impl RsaPrivateKey {
+    /**
+     * Generates a key whose modulus N = p * q has exactly the given number of bits, with
+     * e = 65537. The standard sizes are 2048, 3072 and 4096 bits.
+     *
+     * p and q have half the bits each. As FIPS 186-4 requires, they are drawn again if
+     * |p - q| <= 2^(bits/2 - 100), since close primes let Fermat's method factor N.
+     *
+     * The primes are drawn from rng: pass a ChaChaDrbg::new() for a fresh key, or a
+     * seeded generator to replay the generation of a key.
+     */
+    pub fn generate<R: RngCore + CryptoRng + ?Sized>(bits: u64, rng: &mut R) -> Self {
+        if bits < 64 || !bits.is_multiple_of(2) {
+            panic!("The modulus needs an even number of bits, at least 64.");
+        }
+        let e = BigUint::from(PUBLIC_EXPONENT);
+        let half = bits / 2;
+        let min_distance = BigUint::one() << half.saturating_sub(100);
+
+        loop {
+            let p = generate_rand_prime(half, &e, rng);
+            let q = generate_rand_prime(half, &e, rng);
+            let distance = if p > q {&p - &q} else {&q - &p};
+            if distance <= min_distance {
+                continue;
+            }
+            if let Some(key) = RsaPrivateKey::from_primes(p, q, e.clone()) {
+                return key;
+            }
+        }
+    }
+
+    /**
+     * Builds the key for two distinct primes and a public exponent.
+     * d is the inverse of e modulo λ(N) = lcm(p-1, q-1), the smallest exponent that works.
+     * Returns None if e isn't invertible modulo λ(N). The primality of p and q is not checked.
+     */
+    pub fn from_primes(p: BigUint, q: BigUint, e: BigUint) -> Option<Self> {
+        if p == q {
+            return None;
+        }
+        //p > q is the usual order for q_inv
+        let (p, q) = if p > q {(p, q)} else {(q, p)};
+        let p_minus_one = &p - 1u32;
+        let q_minus_one = &q - 1u32;
+        let lambda = p_minus_one.lcm(&q_minus_one);
+        let d = inv_mod_big(&e, &lambda)?;
+        let q_inv = inv_mod_big(&q, &p)?;
+
+        Some(RsaPrivateKey {
+            n: &p * &q,
+            dp: &d % &p_minus_one,
+            dq: &d % &q_minus_one,
+            e,
+            d,
+            p,
+            q,
+            q_inv
+        })
+    }
+
+    pub fn public_key(&self) -> RsaPublicKey {
+        RsaPublicKey{n: self.n.clone(), e: self.e.clone()}
+    }
+
+    /**
+     * The size of the modulus in bits
+     */
+    pub fn bits(&self) -> u64 {
+        self.n.bits()
+    }
+
+    /**
+     * The size of the modulus in bytes
+     */
+    pub fn size(&self) -> usize {
+        self.n.bits().div_ceil(8) as usize
+    }
+
+    /**
+     * Textbook RSA decryption, c^d mod N, computed with the Chinese Remainder Theorem:
+     * m1 = c^dp mod p and m2 = c^dq mod q are combined as
+     * m = m2 + q * (q_inv * (m1 - m2) mod p). Two exponentiations with half-size numbers
+     * are about 4 times faster than one with the full modulus.
+     *
+     * The exponentiations are constant-time (MontgomeryFixed::pow_fixed_window), so their
+     * timing doesn't depend on the bits of dp and dq. The ciphertext is also blinded: it is
+     * multiplied by r^e for a random r from rng, and the result by r^-1, so the numbers the
+     * secret exponents are applied to are unrelated to the c an attacker chose.
+     * Panics if c >= N.
+     */
+    pub fn decrypt<R: RngCore + CryptoRng + ?Sized>(&self, c: &BigUint, rng: &mut R) -> BigUint {
+        if c >= &self.n {
+            panic!("The ciphertext must be smaller than the modulus.");
+        }
+        let (r, r_inv) = loop {
+            let r = random_big_below(&self.n, rng);
+            if let Some(r_inv) = inv_mod_big(&r, &self.n) {
+                break (r, r_inv);
+            }
+        };
+        let blinded = (c * pow_mod_big(&r, &self.e, &self.n)) % &self.n;
+
+        let m1 = MontgomeryFixed::new(&self.p).pow_fixed_window(&blinded, &self.dp, self.p.bits());
+        let m2 = MontgomeryFixed::new(&self.q).pow_fixed_window(&blinded, &self.dq, self.q.bits());
+        let h = (&self.q_inv * (m1 + &self.p - (&m2 % &self.p))) % &self.p;
+        ((m2 + h * &self.q) * r_inv) % &self.n
+    }
+}
+
+impl RsaPublicKey {
+    /**
+     * The size of the modulus in bits
+     */
+    pub fn bits(&self) -> u64 {
+        self.n.bits()
+    }
+
+    /**
+     * The size of the modulus in bytes
+     */
+    pub fn size(&self) -> usize {
+        self.n.bits().div_ceil(8) as usize
+    }
+
+    /**
+     * Textbook RSA encryption, m^e mod N. Panics if m >= N.
+     * Without padding this is deterministic and malleable, so it should only carry
+     * values that are already random, or be used under a padding scheme.
+     */
+    pub fn encrypt(&self, m: &BigUint) -> BigUint {
+        if m >= &self.n {
+            panic!("The message must be smaller than the modulus.");
+        }
+        pow_mod_big(m, &self.e, &self.n)
+    }
+}
+
+/**
+ * Hash functions that PKCS #1 v1.5 signatures can name. The DER prefix of the DigestInfo
+ * structure, which holds the algorithm identifier, goes before the hash in the signature.
+ */
+pub trait DigestInfo: Digest {
+    const DIGEST_INFO_PREFIX: &'static [u8];
+}
+
+impl DigestInfo for Sha256 {
+    const DIGEST_INFO_PREFIX: &'static [u8] = &[
+        0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05, 0x00, 0x04, 0x20
+    ];
+}
+
+impl DigestInfo for Sha512 {
+    const DIGEST_INFO_PREFIX: &'static [u8] = &[
+        0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03, 0x05, 0x00, 0x04, 0x40
+    ];
+}
+
+impl DigestInfo for Sha3_256 {
+    const DIGEST_INFO_PREFIX: &'static [u8] = &[
+        0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x08, 0x05, 0x00, 0x04, 0x20
+    ];
+}
+
+impl DigestInfo for Sha3_512 {
+    const DIGEST_INFO_PREFIX: &'static [u8] = &[
+        0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x0a, 0x05, 0x00, 0x04, 0x40
+    ];
+}
+
+//I2OSP: x as a big-endian byte string of exactly len bytes, or None if it doesn't fit
+fn to_bytes(x: &BigUint, len: usize) -> Option<Vec<u8>> {
+    let bytes = x.to_bytes_be();
+    if x.is_zero() {
+        return Some(vec![0; len]);
     }
+    if bytes.len() > len {
+        return None;
+    }
+    let mut padded = vec![0; len - bytes.len()];
+    padded.extend_from_slice(&bytes);
+    Some(padded)
+}
 
-    //convert bitvecs to nums
-    let num_as_num = u128::from_be_bytes(num_bits.to_bytes().try_into().unwrap());
-    
-    //determine if pub and priv key are prime
-    let num_is_prime = simple_primality(&num_as_num, 5);
-    
-    if num_is_prime {
-        num_as_num
-    } else {
-        generate_rand_prime()
+/**
+ * MGF1, the mask generation function of PKCS #1: the hashes of seed || counter for
+ * counter = 0, 1, 2, ... (as 4 bytes) concatenated and cut to len bytes
+ */
+pub fn mgf1<H: Digest>(seed: &[u8], len: usize) -> Vec<u8> {
+    let mut mask: Vec<u8> = Vec::with_capacity(len + H::OUTPUT_SIZE);
+    let mut counter: u32 = 0;
+    while mask.len() < len {
+        let mut hasher = H::default();
+        hasher.update(seed);
+        hasher.update(&counter.to_be_bytes());
+        mask.extend(hasher.finalize());
+        counter += 1;
     }
+    mask.truncate(len);
+    mask
 }
 
-//pub key (N, e) 
-//priv key d (congruent to e^-1 % (p-1)(q-1))
-pub fn gen_key_pair(p: u128, q: u128) -> ((u128, u128), u128) {
-    let mut found_rel_prime = false;
-    let mut e = 0;                                      //public key
-    let mut d = 0;                                      //private key
-    let pub_key: (u128, u128);
-    let rel_prime = (p - 1)*(q - 1);                    //mod value
+fn xor_in_place(data: &mut [u8], mask: &[u8]) {
+    for (byte, m) in data.iter_mut().zip(mask.iter()) {
+        *byte ^= m;
+    }
+}
 
-    while !found_rel_prime {
-        e = rand::thread_rng().gen_range(0..u128::MAX);
-        let res = euclid_gcd_ext(e, rel_prime);
-        //if rel_prime and e are relatively prime
-        if res.gcd == 1 {
-            found_rel_prime = true;
-            d = res.alpha;
+impl RsaPublicKey {
+    /**
+     * RSAES-OAEP encryption (PKCS #1 v2.2, RFC 8017) with hash H and MGF1 over H.
+     *
+     * The encoded message is 0x00 || masked seed || masked DB with DB = H(label) || zeros
+     * || 0x01 || message. The random seed masks DB and DB masks the seed, so the encryption
+     * is randomized and any change to the ciphertext scrambles the whole decoded block.
+     * The label is optional associated data that must match on decryption.
+     * The seed is drawn from rng.
+     *
+     * Panics if the message is longer than the size of the modulus minus 2 * hash size + 2.
+     */
+    pub fn encrypt_oaep<H: Digest, R: RngCore + CryptoRng + ?Sized>(&self, message: &[u8], label: &[u8], rng: &mut R) -> Vec<u8> {
+        let k = self.size();
+        let h_len = H::OUTPUT_SIZE;
+        if k < 2 * h_len + 2 || message.len() > k - 2 * h_len - 2 {
+            panic!("The message is too long for OAEP with this key.");
         }
+
+        let mut db = H::digest(label);
+        db.resize(k - h_len - 1 - message.len() - 1, 0);
+        db.push(0x01);
+        db.extend_from_slice(message);
+
+        let mut seed = vec![0u8; h_len];
+        rng.fill_bytes(&mut seed);
+        xor_in_place(&mut db, &mgf1::<H>(&seed, k - h_len - 1));
+        xor_in_place(&mut seed, &mgf1::<H>(&db, h_len));
+
+        let mut encoded = vec![0x00];
+        encoded.extend(seed);
+        encoded.extend(db);
+        let c = self.encrypt(&BigUint::from_bytes_be(&encoded));
+        to_bytes(&c, k).unwrap()
     }
 
-    pub_key = (rel_prime, e);
+    /**
+     * Verifies an RSASSA-PSS signature, see RsaPrivateKey::sign_pss
+     */
+    pub fn verify_pss<H: Digest>(&self, message: &[u8], signature: &[u8], salt_len: usize) -> bool {
+        let h_len = H::OUTPUT_SIZE;
+        let em_bits = self.n.bits() as usize - 1;
+        let em_len = em_bits.div_ceil(8);
+        let s = BigUint::from_bytes_be(signature);
+        if signature.len() != self.size() || s >= self.n || em_len < h_len + salt_len + 2 {
+            return false;
+        }
+        let encoded = match to_bytes(&self.encrypt(&s), em_len) {
+            Some(encoded) => encoded,
+            None => return false
+        };
+        if encoded[em_len - 1] != 0xbc {
+            return false;
+        }
+
+        let (masked_db, rest) = encoded.split_at(em_len - h_len - 1);
+        let hash = &rest[..h_len];
+        let unused_bits = 8 * em_len - em_bits;
+        if masked_db[0] & !(0xffu8 >> unused_bits) != 0 {
+            return false;
+        }
+        let mut db = masked_db.to_vec();
+        let mask = mgf1::<H>(hash, db.len());
+        xor_in_place(&mut db, &mask);
+        db[0] &= 0xff >> unused_bits;
 
-    (pub_key, d)
+        //DB = zeros || 0x01 || salt
+        let separator = em_len - h_len - salt_len - 2;
+        if db[..separator].iter().any(|&b| {b != 0}) || db[separator] != 0x01 {
+            return false;
+        }
+        let salt = &db[separator + 1..];
+
+        let mut hasher = H::default();
+        hasher.update(&[0u8; 8]);
+        hasher.update(&H::digest(message));
+        hasher.update(salt);
+        hasher.finalize() == hash
+    }
+
+    /**
+     * Verifies an RSASSA-PKCS1-v1_5 signature, see RsaPrivateKey::sign_pkcs1v15
+     */
+    pub fn verify_pkcs1v15<H: DigestInfo>(&self, message: &[u8], signature: &[u8]) -> bool {
+        let s = BigUint::from_bytes_be(signature);
+        if signature.len() != self.size() || s >= self.n {
+            return false;
+        }
+        match (to_bytes(&self.encrypt(&s), self.size()), pkcs1v15_signature_block::<H>(message, self.size())) {
+            (Some(encoded), Some(expected)) => encoded == expected,
+            _ => false
+        }
+    }
 }
 
-//m^e % N = encrypted message
-pub fn encrypt(message: &str, pub_key_reciever: (u128, u128)) -> u128{
-    let message_as_int = message.to_string().parse::<u128>().unwrap();
-    mod_exponent(message_as_int as f64, pub_key_reciever.1 as f64, pub_key_reciever.0 as f64) as u128
+//EMSA-PKCS1-v1_5: 0x00 || 0x01 || 0xff... || 0x00 || DigestInfo || H(message), len bytes long
+fn pkcs1v15_signature_block<H: DigestInfo>(message: &[u8], len: usize) -> Option<Vec<u8>> {
+    let t_len = H::DIGEST_INFO_PREFIX.len() + H::OUTPUT_SIZE;
+    if len < t_len + 11 {
+        return None;
+    }
+    let mut block = vec![0x00, 0x01];
+    block.resize(len - t_len - 1, 0xff);
+    block.push(0x00);
+    block.extend_from_slice(H::DIGEST_INFO_PREFIX);
+    block.extend(H::digest(message));
+    Some(block)
 }
 
-//y^d % N = decrypted message
-pub fn decrypt(encrypted: u128, pub_key: (u128, u128), priv_key: u128) -> u128{
-    mod_exponent(encrypted as f64, priv_key as f64, pub_key.0 as f64) as u128
+impl RsaPrivateKey {
+    //RSADP on a byte string, None if it isn't a number below N
+    fn decrypt_bytes<R: RngCore + CryptoRng + ?Sized>(&self, ciphertext: &[u8], rng: &mut R) -> Option<Vec<u8>> {
+        let c = BigUint::from_bytes_be(ciphertext);
+        if ciphertext.len() != self.size() || c >= self.n {
+            return None;
+        }
+        to_bytes(&self.decrypt(&c, rng), self.size())
+    }
+
+    /**
+     * RSAES-OAEP decryption, see RsaPublicKey::encrypt_oaep.
+     * Returns None if the ciphertext is invalid or the label doesn't match. All the checks
+     * of the decoded block are done before answering, so a failure doesn't reveal which
+     * one failed; telling them apart would let an attacker decrypt (Manger's attack).
+     * rng is for the blinding in decrypt.
+     */
+    pub fn decrypt_oaep<H: Digest, R: RngCore + CryptoRng + ?Sized>(&self, ciphertext: &[u8], label: &[u8], rng: &mut R) -> Option<Vec<u8>> {
+        let k = self.size();
+        let h_len = H::OUTPUT_SIZE;
+        if k < 2 * h_len + 2 {
+            return None;
+        }
+        let encoded = self.decrypt_bytes(ciphertext, rng)?;
+
+        let (masked_seed, masked_db) = encoded[1..].split_at(h_len);
+        let mut seed = masked_seed.to_vec();
+        xor_in_place(&mut seed, &mgf1::<H>(masked_db, h_len));
+        let mut db = masked_db.to_vec();
+        let mask = mgf1::<H>(&seed, db.len());
+        xor_in_place(&mut db, &mask);
+
+        //DB = H(label) || zeros || 0x01 || message, checked without early exits
+        let label_hash = H::digest(label);
+        let mut bad = encoded[0];
+        for (a, b) in db[..h_len].iter().zip(label_hash.iter()) {
+            bad |= a ^ b;
+        }
+        let mut separator = 0;
+        let mut found = 0u8;
+        for (i, &byte) in db.iter().enumerate().skip(h_len) {
+            let is_separator = (found == 0 && byte == 0x01) as u8;
+            separator |= i * is_separator as usize;
+            bad |= (found == 0 && byte != 0x00 && byte != 0x01) as u8;
+            found |= is_separator;
+        }
+        bad |= 1 - found;
+
+        if bad != 0 {
+            None
+        } else {
+            Some(db[separator + 1..].to_vec())
+        }
+    }
+
+    /**
+     * Legacy RSAES-PKCS1-v1_5 decryption, for reading messages from older systems.
+     * The block is 0x00 || 0x02 || at least 8 nonzero random bytes || 0x00 || message.
+     * New code should use OAEP: the structure of this block is checked by less than a byte
+     * of redundancy, and servers that reveal whether it was valid can be used to decrypt
+     * (Bleichenbacher's attack).
+     */
+    pub fn decrypt_pkcs1v15<R: RngCore + CryptoRng + ?Sized>(&self, ciphertext: &[u8], rng: &mut R) -> Option<Vec<u8>> {
+        let encoded = self.decrypt_bytes(ciphertext, rng)?;
+        let mut bad = encoded[0] | (encoded[1] ^ 0x02);
+        let mut separator = 0;
+        let mut found = 0u8;
+        for (i, &byte) in encoded.iter().enumerate().skip(2) {
+            let is_separator = (found == 0 && byte == 0x00) as u8;
+            separator |= i * is_separator as usize;
+            found |= is_separator;
+        }
+        bad |= 1 - found;
+        bad |= (separator < 10) as u8;
+
+        if bad != 0 {
+            None
+        } else {
+            Some(encoded[separator + 1..].to_vec())
+        }
+    }
+
+    /**
+     * RSASSA-PSS signing (PKCS #1 v2.2) with hash H, MGF1 over H and a salt of
+     * salt_len bytes (usually the hash size) drawn from rng, which also blinds the signing.
+     *
+     * The encoded message is masked DB || H' || 0xbc where H' = H(8 zero bytes || H(message)
+     * || salt) and DB = zeros || 0x01 || salt is masked by MGF1(H'). The random salt makes
+     * the scheme provably secure given the hardness of RSA.
+     *
+     * Panics if the key is too small for the hash and salt.
+     */
+    pub fn sign_pss<H: Digest, R: RngCore + CryptoRng + ?Sized>(&self, message: &[u8], salt_len: usize, rng: &mut R) -> Vec<u8> {
+        let h_len = H::OUTPUT_SIZE;
+        let em_bits = self.n.bits() as usize - 1;
+        let em_len = em_bits.div_ceil(8);
+        if em_len < h_len + salt_len + 2 {
+            panic!("The key is too small for this hash and salt length.");
+        }
+
+        let mut salt = vec![0u8; salt_len];
+        rng.fill_bytes(&mut salt);
+        let mut hasher = H::default();
+        hasher.update(&[0u8; 8]);
+        hasher.update(&H::digest(message));
+        hasher.update(&salt);
+        let hash = hasher.finalize();
+
+        let mut db = vec![0u8; em_len - salt_len - h_len - 2];
+        db.push(0x01);
+        db.extend(salt);
+        let mask = mgf1::<H>(&hash, db.len());
+        xor_in_place(&mut db, &mask);
+        db[0] &= 0xff >> (8 * em_len - em_bits);
+
+        let mut encoded = db;
+        encoded.extend(hash);
+        encoded.push(0xbc);
+        let s = self.decrypt(&BigUint::from_bytes_be(&encoded), rng);
+        to_bytes(&s, self.size()).unwrap()
+    }
+
+    /**
+     * RSASSA-PKCS1-v1_5 signing with hash H. The signed block is deterministic:
+     * 0x00 || 0x01 || 0xff padding || 0x00 || DigestInfo naming H || H(message).
+     * rng is only used for the blinding in decrypt, the signature doesn't depend on it.
+     * Panics if the key is too small for the hash.
+     */
+    pub fn sign_pkcs1v15<H: DigestInfo, R: RngCore + CryptoRng + ?Sized>(&self, message: &[u8], rng: &mut R) -> Vec<u8> {
+        let encoded = match pkcs1v15_signature_block::<H>(message, self.size()) {
+            Some(encoded) => encoded,
+            None => panic!("The key is too small for this hash.")
+        };
+        let s = self.decrypt(&BigUint::from_bytes_be(&encoded), rng);
+        to_bytes(&s, self.size()).unwrap()
+    }
+}
+
+//1.2.840.113549.1.1.1, rsaEncryption
+const RSA_ENCRYPTION_OID: [u64; 7] = [1, 2, 840, 113549, 1, 1, 1];
+
+//AlgorithmIdentifier ::= SEQUENCE { rsaEncryption, NULL }
+fn rsa_algorithm_identifier() -> Der {
+    Der::Sequence(vec![Der::ObjectIdentifier(RSA_ENCRYPTION_OID.to_vec()), Der::Null])
+}
+
+fn is_rsa_algorithm_identifier(der: &Der) -> bool {
+    match der {
+        Der::Sequence(items) => match items.as_slice() {
+            [Der::ObjectIdentifier(oid), Der::Null] | [Der::ObjectIdentifier(oid)] => oid.as_slice() == RSA_ENCRYPTION_OID,
+            _ => false
+        },
+        _ => false
+    }
+}
+
+impl RsaPublicKey {
+    /**
+     * PKCS #1 RSAPublicKey: SEQUENCE { modulus INTEGER, publicExponent INTEGER }
+     */
+    pub fn to_pkcs1_der(&self) -> Vec<u8> {
+        Der::Sequence(vec![Der::Integer(self.n.clone()), Der::Integer(self.e.clone())]).encode().unwrap()
+    }
+
+    pub fn from_pkcs1_der(der: &[u8]) -> Option<Self> {
+        match Der::decode(der)? {
+            Der::Sequence(items) => match items.as_slice() {
+                [Der::Integer(n), Der::Integer(e)] => Some(RsaPublicKey{n: n.clone(), e: e.clone()}),
+                _ => None
+            },
+            _ => None
+        }
+    }
+
+    /**
+     * X.509 SubjectPublicKeyInfo, the usual format for public keys:
+     * SEQUENCE { AlgorithmIdentifier, BIT STRING holding the PKCS #1 key }
+     */
+    pub fn to_public_key_der(&self) -> Vec<u8> {
+        Der::Sequence(vec![rsa_algorithm_identifier(), Der::BitString(self.to_pkcs1_der())]).encode().unwrap()
+    }
+
+    pub fn from_public_key_der(der: &[u8]) -> Option<Self> {
+        match Der::decode(der)? {
+            Der::Sequence(items) => match items.as_slice() {
+                [algorithm, Der::BitString(key)] if is_rsa_algorithm_identifier(algorithm) => RsaPublicKey::from_pkcs1_der(key),
+                _ => None
+            },
+            _ => None
+        }
+    }
+
+    /**
+     * PKCS #1 public key in PEM, labeled RSA PUBLIC KEY
+     */
+    pub fn to_pkcs1_pem(&self) -> String {
+        pem_encode("RSA PUBLIC KEY", &self.to_pkcs1_der())
+    }
+
+    pub fn from_pkcs1_pem(pem: &str) -> Option<Self> {
+        RsaPublicKey::from_pkcs1_der(&pem_decode("RSA PUBLIC KEY", pem)?)
+    }
+
+    /**
+     * SubjectPublicKeyInfo in PEM, labeled PUBLIC KEY, as written by openssl -pubout
+     */
+    pub fn to_public_key_pem(&self) -> String {
+        pem_encode("PUBLIC KEY", &self.to_public_key_der())
+    }
+
+    pub fn from_public_key_pem(pem: &str) -> Option<Self> {
+        RsaPublicKey::from_public_key_der(&pem_decode("PUBLIC KEY", pem)?)
+    }
+}
+
+impl RsaPrivateKey {
+    /**
+     * PKCS #1 RSAPrivateKey: SEQUENCE { version 0, n, e, d, p, q, dp, dq, q_inv }
+     */
+    pub fn to_pkcs1_der(&self) -> Vec<u8> {
+        let fields = [&self.n, &self.e, &self.d, &self.p, &self.q, &self.dp, &self.dq, &self.q_inv];
+        let mut items = vec![Der::Integer(BigUint::zero())];
+        items.extend(fields.iter().map(|&x| {Der::Integer(x.clone())}));
+        Der::Sequence(items).encode().unwrap()
+    }
+
+    /**
+     * Reads a two-prime PKCS #1 private key. Returns None if the encoding is invalid or
+     * the numbers aren't a consistent key: N = p * q and the CRT values must follow from
+     * d, p and q, and d must invert e.
+     */
+    pub fn from_pkcs1_der(der: &[u8]) -> Option<Self> {
+        let items = match Der::decode(der)? {
+            Der::Sequence(items) => items,
+            _ => return None
+        };
+        let mut numbers: Vec<BigUint> = Vec::new();
+        for item in items {
+            match item {
+                Der::Integer(x) => numbers.push(x),
+                _ => return None
+            }
+        }
+        if numbers.len() != 9 || !numbers[0].is_zero() {
+            return None;
+        }
+        let key = RsaPrivateKey {
+            n: numbers[1].clone(),
+            e: numbers[2].clone(),
+            d: numbers[3].clone(),
+            p: numbers[4].clone(),
+            q: numbers[5].clone(),
+            dp: numbers[6].clone(),
+            dq: numbers[7].clone(),
+            q_inv: numbers[8].clone()
+        };
+        if key.is_consistent() {
+            Some(key)
+        } else {
+            None
+        }
+    }
+
+    //the relations between the fields of a key
+    fn is_consistent(&self) -> bool {
+        let one = BigUint::one();
+        if self.p <= one || self.q <= one || self.n != &self.p * &self.q {
+            return false;
+        }
+        let p_minus_one = &self.p - 1u32;
+        let q_minus_one = &self.q - 1u32;
+        let lambda = p_minus_one.lcm(&q_minus_one);
+        (&self.e * &self.d) % &lambda == one
+            && self.dp == &self.d % &p_minus_one
+            && self.dq == &self.d % &q_minus_one
+            && (&self.q_inv * &self.q) % &self.p == one
+    }
+
+    /**
+     * PKCS #8 PrivateKeyInfo:
+     * SEQUENCE { version 0, AlgorithmIdentifier, OCTET STRING holding the PKCS #1 key }
+     */
+    pub fn to_pkcs8_der(&self) -> Vec<u8> {
+        Der::Sequence(vec![
+            Der::Integer(BigUint::zero()),
+            rsa_algorithm_identifier(),
+            Der::OctetString(self.to_pkcs1_der())
+        ]).encode().unwrap()
+    }
+
+    pub fn from_pkcs8_der(der: &[u8]) -> Option<Self> {
+        match Der::decode(der)? {
+            Der::Sequence(items) => match items.as_slice() {
+                [Der::Integer(version), algorithm, Der::OctetString(key)] if version.is_zero() && is_rsa_algorithm_identifier(algorithm) => {
+                    RsaPrivateKey::from_pkcs1_der(key)
+                },
+                _ => None
+            },
+            _ => None
+        }
+    }
+
+    /**
+     * PKCS #1 private key in PEM, labeled RSA PRIVATE KEY (openssl -traditional)
+     */
+    pub fn to_pkcs1_pem(&self) -> String {
+        pem_encode("RSA PRIVATE KEY", &self.to_pkcs1_der())
+    }
+
+    pub fn from_pkcs1_pem(pem: &str) -> Option<Self> {
+        RsaPrivateKey::from_pkcs1_der(&pem_decode("RSA PRIVATE KEY", pem)?)
+    }
+
+    /**
+     * Unencrypted PKCS #8 private key in PEM, labeled PRIVATE KEY, as written by openssl genpkey
+     */
+    pub fn to_pkcs8_pem(&self) -> String {
+        pem_encode("PRIVATE KEY", &self.to_pkcs8_der())
+    }
+
+    pub fn from_pkcs8_pem(pem: &str) -> Option<Self> {
+        RsaPrivateKey::from_pkcs8_der(&pem_decode("PRIVATE KEY", pem)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use std::convert::TryInto;
+    use crate::drbg::chacha_drbg::ChaChaDrbg;
+
+    //the PKCS #1 v2.1 example vectors use SHA-1, which is only needed here
+    #[derive(Clone, Default)]
+    struct Sha1 {
+        message: Vec<u8>
+    }
+
+    impl Digest for Sha1 {
+        const OUTPUT_SIZE: usize = 20;
+        const BLOCK_SIZE: usize = 64;
+
+        fn update(&mut self, data: &[u8]) {
+            self.message.extend_from_slice(data);
+        }
+
+        fn finalize(self) -> Vec<u8> {
+            let mut message = self.message;
+            let bit_len = (message.len() as u64) * 8;
+            message.push(0x80);
+            while message.len() % 64 != 56 {
+                message.push(0);
+            }
+            message.extend_from_slice(&bit_len.to_be_bytes());
+
+            let mut h: [u32; 5] = [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+            for block in message.chunks(64) {
+                let mut w = [0u32; 80];
+                for t in 0..80 {
+                    w[t] = if t < 16 {
+                        u32::from_be_bytes(block[4 * t..4 * t + 4].try_into().unwrap())
+                    } else {
+                        (w[t - 3] ^ w[t - 8] ^ w[t - 14] ^ w[t - 16]).rotate_left(1)
+                    };
+                }
+                let [mut a, mut b, mut c, mut d, mut e] = h;
+                for (t, &word) in w.iter().enumerate() {
+                    let (f, k) = match t {
+                        0..=19 => ((b & c) | (!b & d), 0x5a827999),
+                        20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+                        40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+                        _ => (b ^ c ^ d, 0xca62c1d6)
+                    };
+                    let temp = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(word);
+                    e = d;
+                    d = c;
+                    c = b.rotate_left(30);
+                    b = a;
+                    a = temp;
+                }
+                for (state, value) in h.iter_mut().zip([a, b, c, d, e]) {
+                    *state = state.wrapping_add(value);
+                }
+            }
+            h.iter().flat_map(|word| {word.to_be_bytes()}).collect()
+        }
+    }
+
+    //hands out the given bytes first, as the seed or salt of a test vector, then random ones
+    struct Replay {
+        bytes: Vec<u8>,
+        rest: ChaChaDrbg
+    }
+
+    impl RngCore for Replay {
+        fn next_u32(&mut self) -> u32 {
+            let mut bytes = [0u8; 4];
+            self.fill_bytes(&mut bytes);
+            u32::from_le_bytes(bytes)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut bytes = [0u8; 8];
+            self.fill_bytes(&mut bytes);
+            u64::from_le_bytes(bytes)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            let given = dest.len().min(self.bytes.len());
+            dest[..given].copy_from_slice(&self.bytes[..given]);
+            self.bytes.drain(..given);
+            self.rest.fill_bytes(&mut dest[given..]);
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for Replay {}
+
+    fn replay(bytes: &[u8]) -> Replay {
+        Replay{bytes: bytes.to_vec(), rest: ChaChaDrbg::seed_from_u64(1)}
+    }
+
+    //the vectors are written as hex bytes separated by spaces
+    fn hex(text: &str) -> Vec<u8> {
+        let digits: String = text.split_whitespace().collect();
+        (0..digits.len()).step_by(2).map(|i| {u8::from_str_radix(&digits[i..i + 2], 16).unwrap()}).collect()
+    }
+
+    fn key(p: &str, q: &str) -> RsaPrivateKey {
+        let p = BigUint::from_bytes_be(&hex(p));
+        let q = BigUint::from_bytes_be(&hex(q));
+        RsaPrivateKey::from_primes(p, q, BigUint::from(PUBLIC_EXPONENT)).unwrap()
+    }
+
+    //example 1 of the OAEP vectors, 1024 bits
+    fn example_1_key() -> RsaPrivateKey {
+        let key = key(
+            "d3 27 37 e7 26 7f fe 13 41 b2 d5 c0 d1 50 a8 1b 58 6f b3 13 2b ed 2f 8d 52 62 86 4a 9c b9 f3 0a
+             f3 8b e4 48 59 8d 41 3a 17 2e fb 80 2c 21 ac f1 c1 1c 52 0c 2f 26 a4 71 dc ad 21 2e ac 7c a3 9d",
+            "cc 88 53 d1 d5 4d a6 30 fa c0 04 f4 71 f2 81 c7 b8 98 2d 82 24 a4 90 ed be b3 3d 3e 3d 5c c9 3c
+             47 65 70 3d 1d d7 91 64 2f 1f 11 6a 0d d8 52 be 24 19 b2 af 72 bf e9 a0 30 e8 60 b0 28 8b 5d 77"
+        );
+        assert_eq!(key.n.to_bytes_be(), hex(
+            "a8 b3 b2 84 af 8e b5 0b 38 70 34 a8 60 f1 46 c4 91 9f 31 87 63 cd 6c 55 98 c8 ae 48 11 a1 e0 ab
+             c4 c7 e0 b0 82 d6 93 a5 e7 fc ed 67 5c f4 66 85 12 77 2c 0c bc 64 a7 42 c6 c6 30 f5 33 c8 cc 72
+             f6 2a e8 33 c4 0b f2 58 42 e9 84 bb 78 bd bf 97 c0 10 7d 55 bd b6 62 f5 c4 e0 fa b9 84 5c b5 14
+             8e f7 39 2d d3 aa ff 93 ae 1e 6b 66 7b b3 d4 24 76 16 d4 f5 ba 10 d4 cf d2 26 de 88 d3 9f 16 fb"
+        ));
+        key
+    }
+
+    //example 8 of the PSS vectors, 1031 bits
+    fn example_8_key() -> RsaPrivateKey {
+        key(
+            "08 da d7 f1 13 63 fa a6 23 d5 d6 d5 e8 a3 19 32 8d 82 19 0d 71 27 d2 84 6c 43 9b 0a b7 26 19 b0
+             a4 3a 95 32 0e 4e c3 4f c3 a9 ce a8 76 42 23 05 bd 76 c5 ba 7b e9 e2 f4 10 c8 06 06 45 a1 d2 9e
+             db",
+            "08 47 e7 32 37 6f c7 90 0f 89 8e a8 2e b2 b0 fc 41 85 65 fd ae 62 f7 d9 ec 4c e2 21 7b 97 99 0d
+             d2 72 db 15 7f 99 f6 3c 0d cb b9 fb ac db d4 c4 da db 6d f6 77 56 35 8c a4 17 48 25 b4 8f 49 70
+             6d"
+        )
+    }
+
+    #[test]
+    fn sha1_matches_fips_180() {
+        assert_eq!(Sha1::digest(b"abc"), hex("a9 99 3e 36 47 06 81 6a ba 3e 25 71 78 50 c2 6c 9c d0 d8 9d"));
+    }
+
+    #[test]
+    fn oaep_example_1() {
+        let key = example_1_key();
+        let examples = [
+            (
+                "66 28 19 4e 12 07 3d b0 3b a9 4c da 9e f9 53 23 97 d5 0d ba 79 b9 87 00 4a fe fe 34",
+                "18 b7 76 ea 21 06 9d 69 77 6a 33 e9 6b ad 48 e1 dd a0 a5 ef",
+                "35 4f e6 7b 4a 12 6d 5d 35 fe 36 c7 77 79 1a 3f 7b a1 3d ef 48 4e 2d 39 08 af f7 22 fa d4 68 fb
+                 21 69 6d e9 5d 0b e9 11 c2 d3 17 4f 8a fc c2 01 03 5f 7b 6d 8e 69 40 2d e5 45 16 18 c2 1a 53 5f
+                 a9 d7 bf c5 b8 dd 9f c2 43 f8 cf 92 7d b3 13 22 d6 e8 81 ea a9 1a 99 61 70 e6 57 a0 5a 26 64 26
+                 d9 8c 88 00 3f 84 77 c1 22 70 94 a0 d9 fa 1e 8c 40 24 30 9c e1 ec cc b5 21 00 35 d4 7a c7 2e 8a"
+            ),
+            (
+                "75 0c 40 47 f5 47 e8 e4 14 11 85 65 23 29 8a c9 ba e2 45 ef af 13 97 fb e5 6f 9d d5",
+                "0c c7 42 ce 4a 9b 7f 32 f9 51 bc b2 51 ef d9 25 fe 4f e3 5f",
+                "64 0d b1 ac c5 8e 05 68 fe 54 07 e5 f9 b7 01 df f8 c3 c9 1e 71 6c 53 6f c7 fc ec 6c b5 b7 1c 11
+                 65 98 8d 4a 27 9e 15 77 d7 30 fc 7a 29 93 2e 3f 00 c8 15 15 23 6d 8d 8e 31 01 7a 7a 09 df 43 52
+                 d9 04 cd eb 79 aa 58 3a dc c3 1e a6 98 a4 c0 52 83 da ba 90 89 be 54 91 f6 7c 1a 4e e4 8d c7 4b
+                 bb e6 64 3a ef 84 66 79 b4 cb 39 5a 35 2d 5e d1 15 91 2d f6 96 ff e0 70 29 32 94 6d 71 49 2b 44"
+            )
+        ];
+        for (message, seed, encryption) in examples.iter() {
+            let (message, encryption) = (hex(message), hex(encryption));
+            let ciphertext = key.public_key().encrypt_oaep::<Sha1, _>(&message, b"", &mut replay(&hex(seed)));
+            assert_eq!(ciphertext, encryption);
+            assert_eq!(key.decrypt_oaep::<Sha1, _>(&encryption, b"", &mut replay(&[])), Some(message));
+        }
+    }
+
+    #[test]
+    fn pss_example_8() {
+        let key = example_8_key();
+        assert_eq!(key.bits(), 1031);
+        let examples = [
+            (
+                "81 33 2f 4b e6 29 48 41 5e a1 d8 99 79 2e ea cf 6c 6e 1d b1 da 8b e1 3b 5c ea 41 db 2f ed 46 70
+                 92 e1 ff 39 89 14 c7 14 25 97 75 f5 95 f8 54 7f 73 56 92 a5 75 e6 92 3a f7 8f 22 c6 99 7d db 90
+                 fb 6f 72 d7 bb 0d d5 74 4a 31 de cd 3d c3 68 58 49 83 6e d3 4a ec 59 63 04 ad 11 84 3c 4f 88 48
+                 9f 20 97 35 f5 fb 7f da f7 ce c8 ad dc 58 18 16 8f 88 0a cb f4 90 d5 10 05 b7 a8 e8 4e 43 e5 42
+                 87 97 75 71 dd 99 ee a4 b1 61 eb 2d f1 f5 10 8f 12 a4 14 2a 83 32 2e db 05 a7 54 87 a3 43 5c 9a
+                 78 ce 53 ed 93 bc 55 08 57 d7 a9 fb",
+                "1d 65 49 1d 79 c8 64 b3 73 00 9b e6 f6 f2 46 7b ac 4c 78 fa",
+                "02 62 ac 25 4b fa 77 f3 c1 ac a2 2c 51 79 f8 f0 40 42 2b 3c 5b af d4 0a 8f 21 cf 0f a5 a6 67 cc
+                 d5 99 3d 42 db af b4 09 c5 20 e2 5f ce 2b 1e e1 e7 16 57 7f 1e fa 17 f3 da 28 05 2f 40 f0 41 9b
+                 23 10 6d 78 45 aa f0 11 25 b6 98 e7 a4 df e9 2d 39 67 bb 00 c4 d0 d3 5b a3 55 2a b9 a8 b3 ee f0
+                 7c 7f ec db c5 42 4a c4 db 1e 20 cb 37 d0 b2 74 47 69 94 0e a9 07 e1 7f bb ca 67 3b 20 52 23 80
+                 c5"
+            ),
+            (
+                "e2 f9 6e af 0e 05 e7 ba 32 6e cc a0 ba 7f d2 f7 c0 23 56 f3 ce de 9d 0f aa bf 4f cc 8e 60 a9 73
+                 e5 59 5f d9 ea 08",
+                "43 5c 09 8a a9 90 9e b2 37 7f 12 48 b0 91 b6 89 87 ff 18 38",
+                "27 07 b9 ad 51 15 c5 8c 94 e9 32 e8 ec 0a 28 0f 56 33 9e 44 a1 b5 8d 4d dc ff 2f 31 2e 5f 34 dc
+                 fe 39 e8 9c 6a 94 dc ee 86 db bd ae 5b 79 ba 4e 08 19 a9 e7 bf d9 d9 82 e7 ee 6c 86 ee 68 39 6e
+                 8b 3a 14 c9 c8 f3 4b 17 8e b7 41 f9 d3 f1 21 10 9b f5 c8 17 2f ad a2 e7 68 f9 ea 14 33 03 2c 00
+                 4a 8a a0 7e b9 90 00 0a 48 dc 94 c8 ba c8 aa be 2b 09 b1 aa 46 c0 a2 aa 0e 12 f6 3f bb a7 75 ba
+                 7e"
+            )
+        ];
+        for (message, salt, signature) in examples.iter() {
+            let (message, signature) = (hex(message), hex(signature));
+            assert_eq!(key.sign_pss::<Sha1, _>(&message, 20, &mut replay(&hex(salt))), signature);
+            assert!(key.public_key().verify_pss::<Sha1>(&message, &signature, 20));
+            assert!(!key.public_key().verify_pss::<Sha1>(&message[1..], &signature, 20));
+        }
+    }
+
+    //the expected signature was computed with OpenSSL, as the v2.1 vectors have no SHA-256 case
+    #[test]
+    fn pkcs1v15_sha256_signature() {
+        let key = example_1_key();
+        let message = hex("66 28 19 4e 12 07 3d b0 3b a9 4c da 9e f9 53 23 97 d5 0d ba 79 b9 87 00 4a fe fe 34");
+        let signature = hex(
+            "50 20 0c e1 09 84 5e 23 8a eb 15 6a 1a 06 d6 8e 4f ed f9 45 58 4c c7 4b 1e 82 55 e9 28 7c 2c 95
+             5e ce 91 e8 4b 33 95 60 2a 69 a2 04 cc 64 1b 71 75 0b 62 b0 b9 d7 b8 36 49 bb 46 b0 32 b2 68 82
+             24 74 97 a7 6a e5 9b 78 fd 7a db 3b ba c9 b0 ed 76 27 4b f6 c5 5f b3 08 9b e5 50 ad ff ad 65 f2
+             3c 18 80 ee 96 3f 16 f8 16 fa 60 40 41 2e 64 e5 4d d8 f5 07 3e c1 99 93 6c 86 90 77 ee 75 c5 bf"
+        );
+        assert_eq!(key.sign_pkcs1v15::<Sha256, _>(&message, &mut replay(&[])), signature);
+        assert!(key.public_key().verify_pkcs1v15::<Sha256>(&message, &signature));
+        let mut forged = signature.clone();
+        forged[0] ^= 1;
+        assert!(!key.public_key().verify_pkcs1v15::<Sha256>(&message, &forged));
+    }
+
+    const PRIVATE_PKCS1_DER: &[u8] = include_bytes!("../fixtures/rsa/private_pkcs1.der");
+    const PRIVATE_PKCS1_PEM: &str = include_str!("../fixtures/rsa/private_pkcs1.pem");
+    const PRIVATE_PKCS8_DER: &[u8] = include_bytes!("../fixtures/rsa/private_pkcs8.der");
+    const PRIVATE_PKCS8_PEM: &str = include_str!("../fixtures/rsa/private_pkcs8.pem");
+    const PUBLIC_PKCS1_DER: &[u8] = include_bytes!("../fixtures/rsa/public_pkcs1.der");
+    const PUBLIC_PKCS1_PEM: &str = include_str!("../fixtures/rsa/public_pkcs1.pem");
+    const PUBLIC_SPKI_DER: &[u8] = include_bytes!("../fixtures/rsa/public_spki.der");
+    const PUBLIC_SPKI_PEM: &str = include_str!("../fixtures/rsa/public_spki.pem");
+
+    #[test]
+    fn private_key_fixtures_round_trip() {
+        let key = RsaPrivateKey::from_pkcs1_der(PRIVATE_PKCS1_DER).unwrap();
+        assert_eq!(key.to_pkcs1_der(), PRIVATE_PKCS1_DER);
+        assert_eq!(key.to_pkcs1_pem(), PRIVATE_PKCS1_PEM);
+        assert_eq!(key.to_pkcs8_der(), PRIVATE_PKCS8_DER);
+        assert_eq!(key.to_pkcs8_pem(), PRIVATE_PKCS8_PEM);
+
+        assert_eq!(RsaPrivateKey::from_pkcs1_pem(PRIVATE_PKCS1_PEM), Some(key.clone()));
+        assert_eq!(RsaPrivateKey::from_pkcs8_der(PRIVATE_PKCS8_DER), Some(key.clone()));
+        assert_eq!(RsaPrivateKey::from_pkcs8_pem(PRIVATE_PKCS8_PEM), Some(key.clone()));
+        assert_eq!(key.bits(), 2048);
+        assert_eq!(&key.p * &key.q, key.n);
+    }
+
+    #[test]
+    fn public_key_fixtures_round_trip() {
+        let key = RsaPublicKey::from_pkcs1_der(PUBLIC_PKCS1_DER).unwrap();
+        assert_eq!(key.to_pkcs1_der(), PUBLIC_PKCS1_DER);
+        assert_eq!(key.to_pkcs1_pem(), PUBLIC_PKCS1_PEM);
+        assert_eq!(key.to_public_key_der(), PUBLIC_SPKI_DER);
+        assert_eq!(key.to_public_key_pem(), PUBLIC_SPKI_PEM);
+
+        assert_eq!(RsaPublicKey::from_pkcs1_pem(PUBLIC_PKCS1_PEM), Some(key.clone()));
+        assert_eq!(RsaPublicKey::from_public_key_der(PUBLIC_SPKI_DER), Some(key.clone()));
+        assert_eq!(RsaPublicKey::from_public_key_pem(PUBLIC_SPKI_PEM), Some(key.clone()));
+        assert_eq!(RsaPrivateKey::from_pkcs1_der(PRIVATE_PKCS1_DER).unwrap().public_key(), key);
+
+        //the PKCS #1 and PKCS #8 formats aren't mixed up
+        assert_eq!(RsaPublicKey::from_pkcs1_der(PUBLIC_SPKI_DER), None);
+        assert_eq!(RsaPrivateKey::from_pkcs8_der(PRIVATE_PKCS1_DER), None);
+        assert_eq!(RsaPublicKey::from_public_key_pem(PUBLIC_PKCS1_PEM), None);
+    }
+
+    //a 513-bit prime times a 512-bit one has 8k + 1 bits, leaving no unused bits in the encoded message
+    #[test]
+    fn pss_modulus_of_8k_plus_1_bits() {
+        let mut rng = ChaChaDrbg::seed_from_u64(38);
+        let e = BigUint::from(PUBLIC_EXPONENT);
+        let p = generate_rand_prime(513, &e, &mut rng);
+        let q = generate_rand_prime(512, &e, &mut rng);
+        let key = RsaPrivateKey::from_primes(p, q, e).unwrap();
+        assert_eq!(key.bits(), 1025);
+        for message in [&b""[..], b"abc", b"a message signed with a 1025-bit key"].iter() {
+            let signature = key.sign_pss::<Sha256, _>(message, 32, &mut rng);
+            assert!(key.public_key().verify_pss::<Sha256>(message, &signature, 32));
+        }
+    }
 }
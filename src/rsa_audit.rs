@@ -0,0 +1,359 @@
+extern crate num;
+use num::{BigInt, BigUint, Integer, One, ToPrimitive, Zero};
+use crate::randomized::randomized_algos::crt;
+use crate::rsa::*;
+
+/**
+ * A weakness found in a public key, with the private key it gives away
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeyWeakness {
+    SharedFactor(RsaPrivateKey),            //a prime is shared with another key of the set
+    CloseFactors(RsaPrivateKey),            //p and q are close enough for Fermat's method
+    SmallPrivateExponent(RsaPrivateKey)     //d is small enough for Wiener's attack
+}
+
+/**
+ * The product tree of the numbers: the first level is the numbers themselves and each
+ * level above holds the products of pairs from the one below, up to the product of all.
+ */
+pub fn product_tree(numbers: &[BigUint]) -> Vec<Vec<BigUint>> {
+    let mut tree = vec![numbers.to_vec()];
+    while tree.last().unwrap().len() > 1 {
+        let level = tree.last().unwrap().chunks(2).map(|pair| {pair.iter().product()}).collect();
+        tree.push(level);
+    }
+    tree
+}
+
+/**
+ * Batch GCD (Bernstein, as used by Heninger et al. on keys collected from the internet):
+ * for every modulus N_i, gcd(N_i, product of all the other moduli), for the cost of a few
+ * multiplications of the whole set instead of a GCD for each pair.
+ *
+ * With P the product of everything (the root of the product tree), the remainder tree
+ * goes back down computing P mod N^2 for each node, from the remainder of its parent.
+ * At a leaf, (P mod N_i^2) / N_i = (P / N_i) mod N_i, whose GCD with N_i is the answer.
+ *
+ * A result of 1 means N_i shares nothing with the others, a proper factor means it can
+ * be factored, and N_i itself means all its primes are shared (or it appears twice).
+ */
+pub fn batch_gcd(moduli: &[BigUint]) -> Vec<BigUint> {
+    if moduli.is_empty() {
+        return Vec::new();
+    }
+    let tree = product_tree(moduli);
+    let mut remainders = tree.last().unwrap().clone();
+    for level in tree.iter().rev().skip(1) {
+        remainders = level.iter().enumerate().map(|(i, node)| {&remainders[i / 2] % (node * node)}).collect();
+    }
+    remainders.iter().zip(moduli.iter()).map(|(r, n)| {(r / n).gcd(n)}).collect()
+}
+
+/**
+ * Factors every key of the set that shares a prime with another, using batch_gcd.
+ * When both primes of a key are shared, batch_gcd only gives N back, so that key is
+ * compared with the others one at a time. Keys with the same modulus can't be told apart.
+ * Returns the index and recovered private key of each broken key.
+ */
+pub fn shared_factor_attack(keys: &[RsaPublicKey]) -> Vec<(usize, RsaPrivateKey)> {
+    let moduli: Vec<BigUint> = keys.iter().map(|key| {key.n.clone()}).collect();
+    let mut broken = Vec::new();
+    for (i, g) in batch_gcd(&moduli).into_iter().enumerate() {
+        let n = &moduli[i];
+        let factor = if g == *n {
+            moduli.iter().map(|other| {other.gcd(n)}).find(|g| {!g.is_one() && g != n})
+        } else if g.is_one() {
+            None
+        } else {
+            Some(g)
+        };
+        if let Some(key) = factor.and_then(|p| {RsaPrivateKey::from_primes(n / &p, p, keys[i].e.clone())}) {
+            broken.push((i, key));
+        }
+    }
+    broken
+}
+
+/**
+ * Fermat's factoring method: N = a^2 - b^2 = (a + b)(a - b), so a is tried upwards from
+ * ceil(sqrt(N)) until a^2 - N is a square. If N = pq with p and q close, a = (p + q)/2
+ * is found after about (p - q)^2 / (8 sqrt(N)) steps, immediately when |p - q| < N^(1/4).
+ * Returns the two factors (larger first) or None after max_iterations values of a.
+ */
+pub fn fermat_factor(n: &BigUint, max_iterations: u64) -> Option<(BigUint, BigUint)> {
+    let mut a = n.sqrt();
+    if &a * &a < *n {
+        a += 1u32;
+    }
+    //b2 = a^2 - N, updated as a grows by (a + 1)^2 - a^2 = 2a + 1
+    let mut b2 = &a * &a - n;
+    for _i in 0..max_iterations {
+        let b = b2.sqrt();
+        if &b * &b == b2 && &a - &b > BigUint::one() {
+            return Some((&a + &b, &a - &b));
+        }
+        b2 += &a * 2u32 + 1u32;
+        a += 1u32;
+    }
+    None
+}
+
+/**
+ * Fermat's method on a key, see fermat_factor
+ */
+pub fn fermat_attack(key: &RsaPublicKey, max_iterations: u64) -> Option<RsaPrivateKey> {
+    let (p, q) = fermat_factor(&key.n, max_iterations)?;
+    RsaPrivateKey::from_primes(p, q, key.e.clone())
+}
+
+/**
+ * The continued fraction expansion [a0; a1, a2, ...] of numerator/denominator,
+ * the quotients of Euclid's algorithm
+ */
+pub fn continued_fraction(numerator: &BigUint, denominator: &BigUint) -> Vec<BigUint> {
+    let mut quotients = Vec::new();
+    let (mut a, mut b) = (numerator.clone(), denominator.clone());
+    while !b.is_zero() {
+        let (q, r) = a.div_rem(&b);
+        quotients.push(q);
+        a = b;
+        b = r;
+    }
+    quotients
+}
+
+/**
+ * Wiener's attack on a small private exponent. ed = 1 + k φ(N) for some k, and φ(N) is
+ * close to N, so e/N is very close to k/d: if d < N^(1/4) / 3, k/d is one of the
+ * convergents of the continued fraction of e/N.
+ *
+ * Each convergent k/d gives a candidate φ = (ed - 1)/k, and then p + q = N - φ + 1
+ * and p, q are the roots of x^2 - (p + q)x + N; the right candidate is the one whose roots
+ * are integers. This is for keys where d is the inverse of e modulo φ(N), like most
+ * textbook keys; a key that picked d small modulo λ(N) may be missed.
+ */
+pub fn wiener_attack(key: &RsaPublicKey) -> Option<RsaPrivateKey> {
+    let n = &key.n;
+    //the convergents k/d, from k_(-1)/d_(-1) = 1/0 and k_(-2)/d_(-2) = 0/1
+    let (mut k, mut k_prev) = (BigUint::one(), BigUint::zero());
+    let (mut d, mut d_prev) = (BigUint::zero(), BigUint::one());
+    for a in continued_fraction(&key.e, n) {
+        let k_next = &a * &k + &k_prev;
+        let d_next = &a * &d + &d_prev;
+        k_prev = std::mem::replace(&mut k, k_next);
+        d_prev = std::mem::replace(&mut d, d_next);
+
+        //k/d is the guess for the multiplier over the private exponent
+        let ed_minus_one = &key.e * &d - 1u32;
+        if k.is_zero() || !ed_minus_one.is_multiple_of(&k) {
+            continue;
+        }
+        let phi = ed_minus_one / &k;
+        if phi >= *n {
+            continue;
+        }
+        let sum = n - &phi + 1u32;
+        let square = &sum * &sum;
+        if square < n * 4u32 {
+            continue;
+        }
+        let discriminant = square - n * 4u32;
+        let root = discriminant.sqrt();
+        if &root * &root != discriminant || sum.is_odd() == root.is_even() {
+            continue;
+        }
+        let p = (&sum + &root) / 2u32;
+        let q = (&sum - &root) / 2u32;
+        if q > BigUint::one() && &p * &q == *n {
+            return RsaPrivateKey::from_primes(p, q, key.e.clone());
+        }
+    }
+    None
+}
+
+/**
+ * Runs the attacks on single keys and on the set, and lists every key that was broken
+ * with how. Fermat's method is given fermat_iterations steps per key.
+ */
+pub fn audit_keys(keys: &[RsaPublicKey], fermat_iterations: u64) -> Vec<(usize, KeyWeakness)> {
+    let mut weaknesses: Vec<(usize, KeyWeakness)> = shared_factor_attack(keys).into_iter()
+        .map(|(i, key)| {(i, KeyWeakness::SharedFactor(key))})
+        .collect();
+    for (i, key) in keys.iter().enumerate() {
+        if let Some(private) = fermat_attack(key, fermat_iterations) {
+            weaknesses.push((i, KeyWeakness::CloseFactors(private)));
+        }
+        if let Some(private) = wiener_attack(key) {
+            weaknesses.push((i, KeyWeakness::SmallPrivateExponent(private)));
+        }
+    }
+    weaknesses.sort_by_key(|(i, _weakness)| {*i});
+    weaknesses
+}
+
+/**
+ * The small exponent attack on textbook RSA: if m^e < N, as for a short message
+ * encrypted with e = 3 and no padding, c = m^e exactly and m is its integer e-th root
+ * (the cube root for e = 3). Returns m, or None if c isn't a perfect e-th power.
+ */
+pub fn root_attack(key: &RsaPublicKey, ciphertext: &BigUint) -> Option<BigUint> {
+    let e = key.e.to_u32()?;
+    let m = ciphertext.nth_root(e);
+    if m.pow(e) == *ciphertext {
+        Some(m)
+    } else {
+        None
+    }
+}
+
+/**
+ * Håstad's broadcast attack: the same message sent with textbook RSA to e keys with the
+ * same small e. The CRT combines c_i = m^e mod N_i into m^e modulo the product of the
+ * N_i, which is larger than m^e, so the combination is m^e itself and m is its e-th root.
+ *
+ * ciphertexts[i] is for keys[i]. Returns None if the keys don't share e, there are fewer
+ * than e of them, or the result isn't an e-th power (the messages differed, or were padded).
+ */
+pub fn hastad_broadcast_attack(keys: &[RsaPublicKey], ciphertexts: &[BigUint]) -> Option<BigUint> {
+    if keys.len() != ciphertexts.len() {
+        panic!("Each ciphertext needs a key.");
+    }
+    let e = keys.first()?.e.to_u32()?;
+    if keys.len() < e as usize || keys.iter().any(|key| {key.e != keys[0].e}) {
+        return None;
+    }
+    let residues: Vec<BigInt> = ciphertexts.iter().map(|c| {BigInt::from(c.clone())}).collect();
+    let moduli: Vec<BigInt> = keys.iter().map(|key| {BigInt::from(key.n.clone())}).collect();
+    let (power, _modulus) = crt(&residues, &moduli)?;
+    let power = power.to_biguint()?;
+    let m = power.nth_root(e);
+    if m.pow(e) == power {
+        Some(m)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use crate::drbg::chacha_drbg::ChaChaDrbg;
+    use crate::modular::modular_arith::inv_mod_big;
+    use crate::randomized::randomized_algos::{baillie_psw, random_big_below};
+
+    fn prime(bits: u64, e: u32, rng: &mut ChaChaDrbg) -> BigUint {
+        generate_rand_prime(bits, &BigUint::from(e), rng)
+    }
+
+    fn key(p: &BigUint, q: &BigUint, e: u32) -> RsaPrivateKey {
+        RsaPrivateKey::from_primes(p.clone(), q.clone(), BigUint::from(e)).unwrap()
+    }
+
+    #[test]
+    fn shared_factor_attack_recovers_keys_sharing_a_prime() {
+        let mut rng = ChaChaDrbg::seed_from_u64(45);
+        let primes: Vec<BigUint> = (0..5).map(|_i| {prime(256, PUBLIC_EXPONENT, &mut rng)}).collect();
+        //keys 0 and 2 share primes[0]; key 1 is sound
+        let private = [key(&primes[0], &primes[1], PUBLIC_EXPONENT), key(&primes[2], &primes[3], PUBLIC_EXPONENT), key(&primes[0], &primes[4], PUBLIC_EXPONENT)];
+        let public: Vec<RsaPublicKey> = private.iter().map(|key| {key.public_key()}).collect();
+
+        let gcds = batch_gcd(&public.iter().map(|key| {key.n.clone()}).collect::<Vec<BigUint>>());
+        assert_eq!(gcds, vec![primes[0].clone(), BigUint::one(), primes[0].clone()]);
+        assert_eq!(shared_factor_attack(&public), vec![(0, private[0].clone()), (2, private[2].clone())]);
+        assert_eq!(audit_keys(&public, 10), vec![
+            (0, KeyWeakness::SharedFactor(private[0].clone())),
+            (2, KeyWeakness::SharedFactor(private[2].clone()))
+        ]);
+
+        //no key shares a prime with another
+        assert!(shared_factor_attack(&public[..2]).is_empty());
+        assert!(batch_gcd(&[]).is_empty());
+    }
+
+    #[test]
+    fn shared_factor_attack_with_both_primes_shared() {
+        let mut rng = ChaChaDrbg::seed_from_u64(450);
+        let primes: Vec<BigUint> = (0..3).map(|_i| {prime(128, PUBLIC_EXPONENT, &mut rng)}).collect();
+        //each prime of key 0 is in another key, so batch_gcd gives N back for it
+        let private = [key(&primes[0], &primes[1], PUBLIC_EXPONENT), key(&primes[0], &primes[2], PUBLIC_EXPONENT), key(&primes[1], &primes[2], PUBLIC_EXPONENT)];
+        let public: Vec<RsaPublicKey> = private.iter().map(|key| {key.public_key()}).collect();
+        let broken = shared_factor_attack(&public);
+        assert_eq!(broken.len(), 3);
+        for (i, recovered) in broken {
+            assert_eq!(recovered, private[i]);
+        }
+    }
+
+    #[test]
+    fn fermat_attack_recovers_close_primes() {
+        let mut rng = ChaChaDrbg::seed_from_u64(451);
+        let p = prime(256, PUBLIC_EXPONENT, &mut rng);
+        //the first prime from p + 2^100 on with q - 1 coprime to e
+        let mut q = &p + (BigUint::one() << 100);
+        while !baillie_psw(&q).is_probably_prime() || RsaPrivateKey::from_primes(p.clone(), q.clone(), BigUint::from(PUBLIC_EXPONENT)).is_none() {
+            q += 2u32;
+        }
+        let private = key(&p, &q, PUBLIC_EXPONENT);
+        assert_eq!(fermat_factor(&private.n, 10), Some((q.clone(), p.clone())));
+        assert_eq!(fermat_attack(&private.public_key(), 10), Some(private.clone()));
+
+        //independent primes are far apart
+        let far = key(&p, &prime(256, PUBLIC_EXPONENT, &mut rng), PUBLIC_EXPONENT);
+        assert_eq!(fermat_attack(&far.public_key(), 10_000), None);
+    }
+
+    #[test]
+    fn wiener_attack_recovers_a_small_private_exponent() {
+        let mut rng = ChaChaDrbg::seed_from_u64(452);
+        let p = prime(256, 3, &mut rng);
+        let q = prime(256, 3, &mut rng);
+        let phi = (&p - 1u32) * (&q - 1u32);
+        //d of 100 bits, well below N^(1/4) / 3
+        let (d, e) = loop {
+            let d = random_big_below(&(BigUint::one() << 100), &mut rng) | BigUint::one();
+            if let Some(e) = inv_mod_big(&d, &phi) {
+                break (d, e);
+            }
+        };
+        let public = RsaPublicKey{n: &p * &q, e};
+        let recovered = wiener_attack(&public).unwrap();
+        assert_eq!(recovered.d, d);
+        assert_eq!(recovered.n, public.n);
+        assert_eq!(audit_keys(std::slice::from_ref(&public), 10), vec![(0, KeyWeakness::SmallPrivateExponent(recovered))]);
+
+        //e = 65537 gives a d as long as N
+        let sound = key(&p, &prime(256, PUBLIC_EXPONENT, &mut rng), PUBLIC_EXPONENT);
+        assert_eq!(wiener_attack(&sound.public_key()), None);
+    }
+
+    #[test]
+    fn root_attack_recovers_a_short_message() {
+        let mut rng = ChaChaDrbg::seed_from_u64(453);
+        let public = key(&prime(512, 3, &mut rng), &prime(512, 3, &mut rng), 3).public_key();
+        let m = BigUint::from_bytes_be(b"attack at dawn");
+        assert_eq!(root_attack(&public, &public.encrypt(&m)), Some(m));
+
+        //a message whose cube wraps around N
+        let long = &public.n >> 8;
+        assert_eq!(root_attack(&public, &public.encrypt(&long)), None);
+    }
+
+    #[test]
+    fn hastad_broadcast_attack_recovers_the_message() {
+        let mut rng = ChaChaDrbg::seed_from_u64(454);
+        let keys: Vec<RsaPublicKey> = (0..3).map(|_i| {key(&prime(256, 3, &mut rng), &prime(256, 3, &mut rng), 3).public_key()}).collect();
+        //longer than a third of N, so the root attack alone fails
+        let m = BigUint::from_bytes_be(&[0x5a; 60]);
+        let ciphertexts: Vec<BigUint> = keys.iter().map(|key| {key.encrypt(&m)}).collect();
+        assert_eq!(root_attack(&keys[0], &ciphertexts[0]), None);
+        assert_eq!(hastad_broadcast_attack(&keys, &ciphertexts), Some(m.clone()));
+
+        //two keys aren't enough for e = 3, and different messages give no cube
+        assert_eq!(hastad_broadcast_attack(&keys[..2], &ciphertexts[..2]), None);
+        let mut mixed = ciphertexts.clone();
+        mixed[2] = keys[2].encrypt(&(&m + 1u32));
+        assert_eq!(hastad_broadcast_attack(&keys, &mixed), None);
+    }
+}